@@ -1,41 +1,198 @@
-use chrono::Utc;
-use cot::StatusCode;
-use cot::db::{Database, Model, StatementResult, query};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use cot::auth::{Password, PasswordHash};
+use cot::db::{Auto, Database, DatabaseBackend, ForeignKey, Model, StatementResult, query};
+use cot::form::{Form, FormResult};
+use cot::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, LOCATION};
 use cot::json::Json;
-use cot::request::extractors::{Path, UrlQuery};
-use cot::response::Redirect;
+use cot::openapi::{ApiOperationResponse, RouteContext};
+use cot::request::extractors::{Path, RequestForm, UrlQuery};
+use cot::response::{IntoResponse, Response};
+use cot::{Body, StatusCode};
 use nanoid::nanoid;
 use shrt_common::config::AppConfig;
+use shrt_common::enums::EnumName;
 use shrt_common::errors::ServiceError;
-use shrt_common::links::{Link as ApiLink, LinkCreateRequest, LinkExists, LinksResponse};
+use shrt_common::links::{
+    BatchItemResult, BatchResponse, Link as ApiLink, LinkBatchCreateRequest, LinkBatchSlugsRequest,
+    LinkCreateRequest, LinkExists, LinkSortField, LinkUpdateRequest, LinksCursorResponse,
+    LinksResponse, MAX_DESCRIPTION_LENGTH, MAX_NOTES_LENGTH, MAX_TITLE_LENGTH, QueryPassthrough,
+    RedirectStatus, SortOrder,
+};
+use shrt_common::qr_codes::{
+    DEFAULT_QR_CODE_MARGIN, DEFAULT_QR_CODE_SIZE, ErrorCorrection, MAX_QR_CODE_MARGIN,
+    MAX_QR_CODE_SIZE, QrCodeFormat,
+};
+use shrt_common::stats::{LinkStats, StatsInterval};
+use shrt_common::tags::normalize_tags;
+use shrt_common::tokens::{ApiToken as ApiTokenInfo, ApiTokenCreateRequest, ApiTokenCreated};
+use shrt_common::transfer::{
+    DuplicateStrategy, LinkFileFormat, LinkImportError, LinkImportReport, LinkImportRequest,
+};
 
-use crate::models::Link;
+use crate::canonical_url::canonicalize;
+use crate::extractors::{CurrentUser, IfMatch, UnlockedLinks, VisitorInfo};
+use crate::link_cursor::LinkCursor;
+use crate::link_search::{LinkFilter, UrlAccess};
+use crate::middleware::RateLimitBudget;
+use crate::models::{ApiToken, Link, Visit};
+use crate::slug_generator::{OffensiveWordFilter, SlugContext};
+use crate::transfer::{ImportedRow, LinkRecord};
+use crate::url_policy::{UrlPolicy, UrlPolicyViolation};
+use crate::{
+    metadata, pages, prefix_links, qr_codes, query_passthrough, slug_generator, stats, tags,
+    transfer,
+};
 
 pub async fn get_config(config: AppConfig) -> Json<AppConfig> {
     Json(config)
 }
 
 const DEFAULT_LINKS_PER_PAGE: u64 = 30;
-const DEFAULT_SLUG_LENGTH: usize = 7;
-/// The alphabet to generate the links from. Essentially [0-9A-Za-z], but with
-/// '0', 'o', 'O', '1', 'l', 'I' removed to avoid confusion (Base56 alphabet).
+/// Maximum number of links on a listed page.
+const MAX_LINKS_PER_PAGE: u64 = 1000;
+/// How long the browsers can cache the permanent redirects, in seconds.
+const PERMANENT_REDIRECT_MAX_AGE_SECS: u32 = 24 * 60 * 60;
+/// How often to remove the visits past the retention period, in seconds.
+const VISIT_PRUNE_INTERVAL_SECS: i64 = 60 * 60;
+/// Maximum number of slugs to try when generating one for a new link.
+const MAX_SLUG_ATTEMPTS: u32 = 12;
+/// Maximum number of items in a single batch request.
+const MAX_BATCH_SIZE: usize = 1000;
+/// The alphabet to generate the API tokens from. Essentially [0-9A-Za-z], but
+/// with '0', 'o', 'O', '1', 'l', 'I' removed to avoid confusion (Base56
+/// alphabet).
 const ALPHABET: [char; 56] = [
     '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k',
     'm', 'n', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F',
     'G', 'H', 'J', 'K', 'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
 ];
 
-// Helper to convert DB Link to API Link
-fn to_api_link(link: &Link) -> ApiLink {
+// Helper to convert DB Link to API Link; the target and the fallback URLs,
+// the title and the icon are only included if `reveal_url` is set, and the
+// notes only if `can_manage` is set
+fn to_api_link(link: &Link, reveal_url: bool, can_manage: bool, tags: Vec<String>) -> ApiLink {
     ApiLink {
         slug: link.slug.clone(),
-        url: link.url.clone(),
+        url: reveal_url.then(|| link.url.clone()),
         created_at: link.created_at,
         visits: link.visits,
+        expires_at: link.expires_at,
+        fallback_url: link.fallback_url.clone().filter(|_| reveal_url),
+        expired: link.is_expired(Utc::now()),
+        max_visits: link.max_visits,
+        exhausted: link.is_exhausted(),
+        password_protected: link.is_password_protected(),
+        etag: link.etag(&tags),
+        tags,
+        title: link.title.clone().filter(|_| reveal_url),
+        description: link.description.clone(),
+        notes: link.notes.clone().filter(|_| can_manage),
+        favicon_url: link.favicon_url.clone().filter(|_| reveal_url),
+        redirect_status: link.redirect_status().code(),
+        query_passthrough: link.query_passthrough(),
+        prefix: link.is_prefix(),
+    }
+}
+
+/// Converts `links` to API links along with their tags; the target URL is
+/// only included if `reveal_url` returns `true`, and the notes if
+/// `can_manage` does.
+async fn to_api_links<DB: DatabaseBackend>(
+    db: &DB,
+    links: &[Link],
+    reveal_url: impl Fn(&Link) -> bool,
+    can_manage: impl Fn(&Link) -> bool,
+) -> Result<Vec<ApiLink>, ServiceError> {
+    let tags = tags::tags_of(db, links).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok(links
+        .iter()
+        .zip(tags)
+        .map(|(link, tags)| to_api_link(link, reveal_url(link), can_manage(link), tags))
+        .collect())
+}
+
+/// Converts `link` to an API link along with its tags.
+async fn to_api_link_with_tags<DB: DatabaseBackend>(
+    db: &DB,
+    link: &Link,
+    reveal_url: bool,
+    can_manage: bool,
+) -> Result<ApiLink, ServiceError> {
+    let mut links = to_api_links(
+        db,
+        std::slice::from_ref(link),
+        |_| reveal_url,
+        |_| can_manage,
+    )
+    .await?;
+    Ok(links.remove(0))
+}
+
+/// Returns the normalized `tags`, or an error if any of them is invalid.
+fn validate_tags(tags: &[String]) -> Result<Vec<String>, ServiceError> {
+    normalize_tags(tags).map_err(|violation| {
+        error(
+            StatusCode::BAD_REQUEST,
+            "Invalid tags",
+            &violation.to_string(),
+        )
+    })
+}
+
+/// Replaces the tags of `link` with `tags`.
+async fn set_link_tags<DB: DatabaseBackend>(
+    db: &DB,
+    link: &Link,
+    tags: &[String],
+) -> Result<(), ServiceError> {
+    tags::set_link_tags(db, link, tags).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })
+}
+
+/// Link returned as JSON, with its entity tag in the `ETag` header as well.
+pub struct LinkWithEtag(ApiLink);
+
+impl IntoResponse for LinkWithEtag {
+    fn into_response(self) -> cot::Result<Response> {
+        let etag = self
+            .0
+            .etag
+            .parse()
+            .expect("entity tag is a valid header value");
+        let mut response = Json(self.0).into_response()?;
+        response.headers_mut().insert(ETAG, etag);
+        Ok(response)
     }
 }
 
-fn error(status: StatusCode, error: &str, message: &str) -> ServiceError {
+impl ApiOperationResponse for LinkWithEtag {
+    fn api_operation_responses(
+        operation: &mut cot::aide::openapi::Operation,
+        route_context: &RouteContext<'_>,
+        schema_generator: &mut cot::schemars::SchemaGenerator,
+    ) -> Vec<(
+        Option<cot::aide::openapi::StatusCode>,
+        cot::aide::openapi::Response,
+    )> {
+        Json::<ApiLink>::api_operation_responses(operation, route_context, schema_generator)
+    }
+}
+
+pub(crate) fn error(status: StatusCode, error: &str, message: &str) -> ServiceError {
     ServiceError {
         status,
         error: error.to_string(),
@@ -43,8 +200,29 @@ fn error(status: StatusCode, error: &str, message: &str) -> ServiceError {
     }
 }
 
+/// Returns an error unless `user` is allowed to edit and remove `link`.
+fn ensure_can_manage(user: &CurrentUser, link: &Link) -> Result<(), ServiceError> {
+    if user.can_manage(link) {
+        Ok(())
+    } else if user.id().is_none() {
+        Err(error(
+            StatusCode::UNAUTHORIZED,
+            "Authentication required",
+            "You need to sign in to manage links",
+        ))
+    } else {
+        Err(error(
+            StatusCode::FORBIDDEN,
+            "Permission denied",
+            &format!("You are not allowed to manage link with slug {}", link.slug),
+        ))
+    }
+}
+
 pub async fn get_link(
     db: Database,
+    user: CurrentUser,
+    unlocked_links: UnlockedLinks,
     Path(slug): Path<String>,
 ) -> Result<Json<ApiLink>, ServiceError> {
     let link: Option<Link> = query!(Link, $slug == slug.clone())
@@ -59,7 +237,13 @@ pub async fn get_link(
         })?;
 
     match link {
-        Some(link) => Ok(Json(to_api_link(&link))),
+        Some(link) => {
+            let can_manage = user.can_manage(&link);
+            let reveal_url = unlocked_links.is_unlocked(&link) || can_manage;
+            Ok(Json(
+                to_api_link_with_tags(&db, &link, reveal_url, can_manage).await?,
+            ))
+        }
         None => Err(error(
             StatusCode::NOT_FOUND,
             "Link not found",
@@ -70,10 +254,24 @@ pub async fn get_link(
 
 pub async fn remove_link(
     db: Database,
+    user: CurrentUser,
     Path(slug): Path<String>,
 ) -> Result<StatusCode, ServiceError> {
-    let result: StatementResult = query!(Link, $slug == slug.clone())
-        .delete(&db)
+    remove_link_in(&db, &user, &slug).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_link_in<DB: DatabaseBackend>(
+    db: &DB,
+    user: &CurrentUser,
+    slug: &str,
+) -> Result<(), ServiceError> {
+    let link = get_link_by_slug(db, slug).await?;
+    ensure_can_manage(user, &link)?;
+
+    let result: StatementResult = query!(Link, $slug == slug.to_owned())
+        .delete(db)
         .await
         .map_err(|e| {
             error(
@@ -84,7 +282,7 @@ pub async fn remove_link(
         })?;
 
     if result.rows_affected().0 == 1 {
-        Ok(StatusCode::NO_CONTENT)
+        Ok(())
     } else {
         Err(error(
             StatusCode::NOT_FOUND,
@@ -96,8 +294,14 @@ pub async fn remove_link(
 
 pub async fn link_exists(
     db: Database,
+    config: AppConfig,
     Path(slug): Path<String>,
 ) -> Result<Json<LinkExists>, ServiceError> {
+    let invalid_reason = config
+        .slug_policy
+        .validate(&slug)
+        .err()
+        .map(|violation| violation.to_string());
     let exists: bool = query!(Link, $slug == slug).exists(&db).await.map_err(|e| {
         error(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -106,39 +310,78 @@ pub async fn link_exists(
         )
     })?;
 
-    Ok(Json(LinkExists { exists }))
+    Ok(Json(LinkExists {
+        exists,
+        invalid_reason,
+    }))
 }
 
 #[derive(serde::Deserialize, cot::schemars::JsonSchema)]
 pub struct PaginationParams {
     page: Option<u64>,
     links_per_page: Option<u64>,
+    /// Only list the links owned by the current user
+    mine: Option<bool>,
+    /// Only list the links whose slug or target URL contains the text,
+    /// ignoring case
+    search: Option<String>,
+    /// Only list the links created at or after this time
+    created_from: Option<DateTime<Utc>>,
+    /// Only list the links created before this time
+    created_to: Option<DateTime<Utc>>,
+    /// Only list the links with at least this many visits
+    min_visits: Option<i32>,
+    /// Only list the links with this tag
+    tag: Option<String>,
+    sort: Option<LinkSortField>,
+    #[serde(default)]
+    order: SortOrder,
 }
 
 pub async fn get_links(
     db: Database,
+    user: CurrentUser,
+    unlocked_links: UnlockedLinks,
     UrlQuery(params): UrlQuery<PaginationParams>,
 ) -> Result<Json<LinksResponse>, ServiceError> {
     let page = params.page.unwrap_or(1).max(1);
     let links_per_page = params
         .links_per_page
         .unwrap_or(DEFAULT_LINKS_PER_PAGE)
-        .max(1);
-    let offset = (page - 1) * links_per_page;
+        .clamp(1, MAX_LINKS_PER_PAGE);
+    let offset = page.saturating_sub(1).saturating_mul(links_per_page);
 
-    let total_count = Link::objects().count(&db).await.map_err(|e| {
-        error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "Database error",
-            &e.to_string(),
-        )
-    })?;
-    let num_pages = total_count.div_ceil(links_per_page);
+    let owner_id = if params.mine.unwrap_or(false) {
+        Some(signed_in_user_id(&user, "list your links")?)
+    } else {
+        None
+    };
 
-    let links = Link::objects()
-        .limit(links_per_page)
-        .offset(offset)
-        .all(&db)
+    let filter = LinkFilter {
+        text: params.search,
+        owner_id,
+        created_from: params.created_from,
+        created_to: params.created_to,
+        min_visits: params.min_visits,
+        tag: params.tag,
+        url_access: UrlAccess {
+            all: user.is_admin(),
+            owner_id: user.id(),
+            unlocked_link_ids: unlocked_links.link_ids().to_vec(),
+        },
+    };
+    let count_query = filter.count_query();
+    let total_count = Link::count_raw(&db, &count_query.sql, &count_query.db_values())
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    let page_query = filter.page_query(params.sort, params.order, offset, links_per_page);
+    let links = Link::fetch_raw(&db, &page_query.sql, &page_query.db_values())
         .await
         .map_err(|e| {
             error(
@@ -147,27 +390,102 @@ pub async fn get_links(
                 &e.to_string(),
             )
         })?;
+    let num_pages = total_count.div_ceil(links_per_page);
 
+    let can_see_url = |link: &Link| unlocked_links.is_unlocked(link) || user.can_manage(link);
     Ok(Json(LinksResponse {
         page,
         links_per_page,
         num_pages,
-        links: links.iter().map(to_api_link).collect(),
+        links: to_api_links(&db, &links, can_see_url, |link| user.can_manage(link)).await?,
     }))
 }
 
-pub async fn create_link(
+#[derive(serde::Deserialize, cot::schemars::JsonSchema)]
+pub struct CursorParams {
+    /// Cursor after which the page starts, as returned in `next_cursor`
+    after: Option<String>,
+    /// Cursor before which the page ends, as returned in `prev_cursor`
+    before: Option<String>,
+    limit: Option<u64>,
+    /// Include the number of the links meeting the filters in the response
+    count: Option<bool>,
+    /// Only list the links owned by the current user
+    mine: Option<bool>,
+    /// Only list the links whose slug or target URL contains the text,
+    /// ignoring case
+    search: Option<String>,
+    /// Only list the links created at or after this time
+    created_from: Option<DateTime<Utc>>,
+    /// Only list the links created before this time
+    created_to: Option<DateTime<Utc>>,
+    /// Only list the links with at least this many visits
+    min_visits: Option<i32>,
+    /// Only list the links with this tag
+    tag: Option<String>,
+    /// Order of creation to list the links in; the newest links come first
+    /// by default
+    order: Option<SortOrder>,
+}
+
+/// Lists the links with keyset pagination, sorted by creation time.
+pub async fn get_links_cursor(
     db: Database,
-    Json(payload): Json<LinkCreateRequest>,
-) -> Result<Json<ApiLink>, ServiceError> {
-    let slug = payload
-        .slug
-        .clone()
-        .unwrap_or_else(|| nanoid!(DEFAULT_SLUG_LENGTH, &ALPHABET));
+    user: CurrentUser,
+    unlocked_links: UnlockedLinks,
+    UrlQuery(params): UrlQuery<CursorParams>,
+) -> Result<Json<LinksCursorResponse>, ServiceError> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_LINKS_PER_PAGE)
+        .clamp(1, MAX_LINKS_PER_PAGE);
+    let owner_id = if params.mine.unwrap_or(false) {
+        Some(signed_in_user_id(&user, "list your links")?)
+    } else {
+        None
+    };
 
-    // Check if slug exists
-    let exists: bool = query!(Link, $slug == slug.clone())
-        .exists(&db)
+    let decode = |cursor: &str| {
+        LinkCursor::decode(cursor).ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Invalid cursor",
+                "The cursor was not returned by this endpoint",
+            )
+        })
+    };
+    let (cursor, backwards) = match (&params.after, &params.before) {
+        (Some(_), Some(_)) => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Invalid cursor",
+                "Only one of after and before can be set",
+            ));
+        }
+        (Some(after), None) => (Some(decode(after)?), false),
+        (None, Some(before)) => (Some(decode(before)?), true),
+        (None, None) => (None, false),
+    };
+    let order = params.order.unwrap_or(SortOrder::Desc);
+
+    let filter = LinkFilter {
+        text: params.search,
+        owner_id,
+        created_from: params.created_from,
+        created_to: params.created_to,
+        min_visits: params.min_visits,
+        tag: params.tag,
+        url_access: UrlAccess {
+            all: user.is_admin(),
+            owner_id: user.id(),
+            unlocked_link_ids: unlocked_links.link_ids().to_vec(),
+        },
+    };
+    // The previous page is the next one in the reversed order; one more link
+    // is fetched to find out whether there are any more
+    let query_order = if backwards { order.reversed() } else { order };
+    let cursor_query = filter.cursor_query(cursor, query_order, limit + 1);
+    let mut links = Link::fetch_raw(&db, &cursor_query.sql, &cursor_query.db_values())
         .await
         .map_err(|e| {
             error(
@@ -176,70 +494,1671 @@ pub async fn create_link(
                 &e.to_string(),
             )
         })?;
-    if exists {
+    let has_more = links.len() as u64 > limit;
+    links.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+    if backwards {
+        links.reverse();
+    }
+
+    let first_cursor = links
+        .first()
+        .and_then(LinkCursor::of)
+        .map(LinkCursor::encode);
+    let last_cursor = links
+        .last()
+        .and_then(LinkCursor::of)
+        .map(LinkCursor::encode);
+    let (prev_cursor, next_cursor) = if backwards {
+        (first_cursor.filter(|_| has_more), last_cursor)
+    } else {
+        (
+            first_cursor.filter(|_| cursor.is_some()),
+            last_cursor.filter(|_| has_more),
+        )
+    };
+
+    let total_count = if params.count.unwrap_or(false) {
+        let count_query = filter.count_query();
+        let count = Link::count_raw(&db, &count_query.sql, &count_query.db_values())
+            .await
+            .map_err(|e| {
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Database error",
+                    &e.to_string(),
+                )
+            })?;
+        Some(count)
+    } else {
+        None
+    };
+
+    let can_see_url = |link: &Link| unlocked_links.is_unlocked(link) || user.can_manage(link);
+    Ok(Json(LinksCursorResponse {
+        links: to_api_links(&db, &links, can_see_url, |link| user.can_manage(link)).await?,
+        next_cursor,
+        prev_cursor,
+        total_count,
+    }))
+}
+
+/// Lists the names of the tags assigned to any link.
+pub async fn get_tags(db: Database) -> Result<Json<Vec<String>>, ServiceError> {
+    let names = tags::used_tag_names(&db).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok(Json(names))
+}
+
+fn expiration_time(
+    payload: &LinkCreateRequest,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ServiceError> {
+    let expires_at = match (payload.expires_at, payload.expires_in) {
+        (Some(_), Some(_)) => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Invalid expiration",
+                "Only one of expires_at and expires_in can be set",
+            ));
+        }
+        (Some(expires_at), None) => Some(expires_at),
+        (None, Some(expires_in)) => {
+            let expires_at = i64::try_from(expires_in)
+                .ok()
+                .and_then(TimeDelta::try_seconds)
+                .and_then(|ttl| now.checked_add_signed(ttl));
+            match expires_at {
+                Some(expires_at) => Some(expires_at),
+                None => {
+                    return Err(error(
+                        StatusCode::BAD_REQUEST,
+                        "Invalid expiration",
+                        &format!("Expiration time of {expires_in} seconds is out of range"),
+                    ));
+                }
+            }
+        }
+        (None, None) => None,
+    };
+
+    validate_expiration(expires_at, now)?;
+    Ok(expires_at)
+}
+
+fn validate_expiration(
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), ServiceError> {
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
         return Err(error(
             StatusCode::BAD_REQUEST,
-            "Slug already exists",
-            &format!("Slug {} already exists", slug),
+            "Invalid expiration",
+            "Expiration time must be in the future",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns `url` as parsed by `policy`, which is what gets stored, or an error
+/// if the policy does not allow it.
+fn validate_url(policy: &UrlPolicy, url: &str) -> Result<String, ServiceError> {
+    let violation = match policy.check(url) {
+        Ok(url) => return Ok(url.to_string()),
+        Err(violation) => violation,
+    };
+    let title = match violation {
+        UrlPolicyViolation::Invalid(_) => "Invalid URL",
+        UrlPolicyViolation::SchemeNotAllowed(_) => "URL scheme not allowed",
+        UrlPolicyViolation::BlockedDomain(_) => "Domain blocked",
+        UrlPolicyViolation::SelfReference => "Redirect loop",
+    };
+
+    Err(error(
+        StatusCode::BAD_REQUEST,
+        title,
+        &violation.to_string(),
+    ))
+}
+
+fn validate_max_visits(max_visits: Option<i32>) -> Result<(), ServiceError> {
+    if max_visits.is_some_and(|max_visits| max_visits < 1) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid visit limit",
+            "Maximum number of visits must be at least 1",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns `text` trimmed, or `None` if it is empty; `field` is the name of
+/// the field in the error messages.
+fn validate_text(
+    field: &str,
+    text: Option<String>,
+    max_length: usize,
+) -> Result<Option<String>, ServiceError> {
+    let Some(text) = text.map(|text| text.trim().to_owned()) else {
+        return Ok(None);
+    };
+    if text.chars().count() > max_length {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            &format!("Invalid {}", field.to_lowercase()),
+            &format!("{field} must be at most {max_length} characters long"),
+        ));
+    }
+
+    Ok(if text.is_empty() { None } else { Some(text) })
+}
+
+/// Returns the stored code of the redirect status `code`, if it is allowed.
+fn validate_redirect_status(code: u16) -> Result<i32, ServiceError> {
+    match RedirectStatus::from_code(code) {
+        Some(status) => Ok(i32::from(status.code())),
+        None => Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid redirect status",
+            "Redirect status must be 301, 302, 307 or 308",
+        )),
+    }
+}
+
+/// Returns the name the mode is stored under; `None` for the default one.
+fn query_passthrough_name(mode: QueryPassthrough) -> Option<String> {
+    (mode != QueryPassthrough::Off).then(|| mode.name().to_owned())
+}
+
+/// Returns the ID and the target URL of `link` if the title is to be taken
+/// from the target page.
+fn metadata_target(config: &AppConfig, link: &Link) -> Option<(i32, String)> {
+    match link.id {
+        Auto::Fixed(id) if config.metadata_fetcher.enabled && link.title.is_none() => {
+            Some((id, link.url.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn validate_password(password: Option<&str>) -> Result<(), ServiceError> {
+    if password.is_some_and(str::is_empty) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid password",
+            "Password must not be empty",
         ));
     }
 
+    Ok(())
+}
+
+pub async fn create_link(
+    db: Database,
+    config: AppConfig,
+    user: CurrentUser,
+    Json(payload): Json<LinkCreateRequest>,
+) -> Result<Json<ApiLink>, ServiceError> {
+    let link = create_link_in(&db, &config, &user, payload).await?;
+    let api_link = to_api_link_with_tags(&db, &link, true, true).await?;
+
+    let targets = metadata_target(&config, &link).into_iter().collect();
+    metadata::fetch_in_background(db, &config.metadata_fetcher, targets);
+
+    Ok(Json(api_link))
+}
+
+/// Validates `payload` and creates the link it describes in `db`, unless an
+/// existing link can be reused.
+async fn create_link_in<DB: DatabaseBackend>(
+    db: &DB,
+    config: &AppConfig,
+    user: &CurrentUser,
+    payload: LinkCreateRequest,
+) -> Result<Link, ServiceError> {
+    if let Some(slug) = &payload.slug {
+        config.slug_policy.validate(slug).map_err(|violation| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Invalid slug",
+                &violation.to_string(),
+            )
+        })?;
+    }
+    let url_policy = UrlPolicy::from_config(config);
+    let url = validate_url(&url_policy, &payload.url)?;
+    let fallback_url = payload
+        .fallback_url
+        .as_deref()
+        .map(|fallback_url| validate_url(&url_policy, fallback_url))
+        .transpose()?;
+    let now = Utc::now();
+    let expires_at = expiration_time(&payload, now)?;
+    validate_max_visits(payload.max_visits)?;
+    validate_password(payload.password.as_deref())?;
+    let tags = validate_tags(&payload.tags)?;
+    let title = validate_text("Title", payload.title, MAX_TITLE_LENGTH)?;
+    let description = validate_text("Description", payload.description, MAX_DESCRIPTION_LENGTH)?;
+    let notes = validate_text("Notes", payload.notes, MAX_NOTES_LENGTH)?;
+    let redirect_status = payload
+        .redirect_status
+        .map(validate_redirect_status)
+        .transpose()?;
+
+    let canonical_url = canonicalize(&url, config.strip_tracking_parameters).ok();
+    let is_plain_link = payload.slug.is_none()
+        && expires_at.is_none()
+        && fallback_url.is_none()
+        && payload.max_visits.is_none()
+        && payload.password.is_none()
+        && tags.is_empty()
+        && title.is_none()
+        && description.is_none()
+        && notes.is_none()
+        && redirect_status.is_none()
+        && payload.query_passthrough == QueryPassthrough::Off
+        && !payload.prefix;
+    if payload.reuse_existing
+        && is_plain_link
+        && let Some(canonical_url) = &canonical_url
+        && let Some(link) = find_reusable_link(db, canonical_url, user.id(), now).await?
+    {
+        return Ok(link);
+    }
+
     let mut link = Link {
         id: cot::db::Auto::auto(),
-        slug: slug.clone(),
-        url: payload.url,
-        created_at: Utc::now(),
+        slug: String::new(),
+        url,
+        created_at: now,
         visits: 0,
+        expires_at,
+        fallback_url,
+        max_visits: payload.max_visits,
+        password_hash: None,
+        owner_id: user.id(),
+        canonical_url,
+        generated_slug: Some(payload.slug.is_none()),
+        title,
+        description,
+        notes,
+        favicon_url: None,
+        redirect_status,
+        query_passthrough: query_passthrough_name(payload.query_passthrough),
+        prefix: payload.prefix.then_some(true),
+        revision: None,
     };
+    if let Some(password) = &payload.password {
+        link.set_password(&Password::new(password));
+    }
 
-    // Use insert to catch potential race condition if check above passed but
-    // another request inserted same slug
-    match link.insert(&db).await {
-        Ok(_) => Ok(Json(to_api_link(&link))),
-        Err(cot::db::DatabaseError::UniqueViolation) => Err(error(
-            StatusCode::BAD_REQUEST,
-            "Slug already exists",
-            &format!("Slug {} already exists", slug),
-        )),
-        Err(e) => Err(error(
+    match payload.slug {
+        Some(slug) => {
+            link.slug = slug;
+            insert_link(db, &mut link).await?;
+        }
+        None => insert_link_with_generated_slug(db, config, &mut link).await?,
+    }
+    if !tags.is_empty() {
+        set_link_tags(db, &link, &tags).await?;
+    }
+
+    Ok(link)
+}
+
+/// Returns an error if a batch request has too many items.
+fn validate_batch_size(size: usize) -> Result<(), ServiceError> {
+    if size > MAX_BATCH_SIZE {
+        return Err(error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Batch too large",
+            &format!("A batch can contain at most {MAX_BATCH_SIZE} items"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Converts the outcome of a single item of a batch request; `status` is the
+/// status code of the successful outcome.
+fn batch_item<T>(result: Result<T, ServiceError>, status: StatusCode) -> BatchItemResult<T> {
+    match result {
+        Ok(value) => BatchItemResult {
+            status: status.as_u16(),
+            result: Some(value),
+            error: None,
+        },
+        Err(e) => BatchItemResult {
+            status: e.status.as_u16(),
+            result: None,
+            error: Some(e),
+        },
+    }
+}
+
+/// Returns `result`, unless it failed in a way that could leave the
+/// transaction of the batch unusable; the whole batch fails then.
+fn ensure_batch_can_continue<T>(
+    result: Result<T, ServiceError>,
+) -> Result<Result<T, ServiceError>, ServiceError> {
+    match result {
+        Err(e) if e.status.is_server_error() => Err(e),
+        result => Ok(result),
+    }
+}
+
+pub async fn create_links_batch(
+    db: Database,
+    config: AppConfig,
+    user: CurrentUser,
+    budget: RateLimitBudget,
+    Json(payload): Json<LinkBatchCreateRequest>,
+) -> Result<Json<BatchResponse<ApiLink>>, ServiceError> {
+    signed_in_user_id(&user, "create links in batches")?;
+    validate_batch_size(payload.links.len())?;
+    budget.acquire(payload.links.len())?;
+
+    let transaction = db.transaction().await.map_err(|e| {
+        error(
             StatusCode::INTERNAL_SERVER_ERROR,
             "Database error",
             &e.to_string(),
-        )),
+        )
+    })?;
+    let mut results = Vec::with_capacity(payload.links.len());
+    let mut metadata_targets = Vec::new();
+    for request in payload.links {
+        let result =
+            ensure_batch_can_continue(create_link_in(&transaction, &config, &user, request).await)?;
+        let result = match result {
+            Ok(link) => {
+                metadata_targets.extend(metadata_target(&config, &link));
+                Ok(to_api_link_with_tags(&transaction, &link, true, true).await?)
+            }
+            Err(e) => Err(e),
+        };
+        results.push(batch_item(result, StatusCode::OK));
     }
+    transaction.commit().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    metadata::fetch_in_background(db, &config.metadata_fetcher, metadata_targets);
+
+    Ok(Json(BatchResponse { results }))
 }
 
-pub async fn redirect_to_link(
+pub async fn remove_links_batch(
     db: Database,
-    Path(slug): Path<String>,
-) -> Result<Redirect, ServiceError> {
-    let link: Option<Link> = query!(Link, $slug == slug.clone())
-        .get(&db)
-        .await
+    user: CurrentUser,
+    Json(payload): Json<LinkBatchSlugsRequest>,
+) -> Result<Json<BatchResponse<String>>, ServiceError> {
+    signed_in_user_id(&user, "manage links")?;
+    validate_batch_size(payload.slugs.len())?;
+
+    let transaction = db.transaction().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    let mut results = Vec::with_capacity(payload.slugs.len());
+    for slug in payload.slugs {
+        let result = ensure_batch_can_continue(remove_link_in(&transaction, &user, &slug).await)?;
+        results.push(batch_item(result.map(|()| slug), StatusCode::NO_CONTENT));
+    }
+    transaction.commit().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok(Json(BatchResponse { results }))
+}
+
+pub async fn get_links_batch(
+    db: Database,
+    user: CurrentUser,
+    unlocked_links: UnlockedLinks,
+    budget: RateLimitBudget,
+    Json(payload): Json<LinkBatchSlugsRequest>,
+) -> Result<Json<BatchResponse<ApiLink>>, ServiceError> {
+    validate_batch_size(payload.slugs.len())?;
+    budget.acquire(payload.slugs.len())?;
+
+    let mut results = Vec::with_capacity(payload.slugs.len());
+    for slug in &payload.slugs {
+        let result = ensure_batch_can_continue(get_link_by_slug(&db, slug).await)?;
+        let result = match result {
+            Ok(link) => {
+                let can_manage = user.can_manage(&link);
+                let reveal_url = unlocked_links.is_unlocked(&link) || can_manage;
+                Ok(to_api_link_with_tags(&db, &link, reveal_url, can_manage).await?)
+            }
+            Err(e) => Err(e),
+        };
+        results.push(batch_item(result, StatusCode::OK));
+    }
+
+    Ok(Json(BatchResponse { results }))
+}
+
+#[derive(serde::Deserialize, cot::schemars::JsonSchema)]
+pub struct ExportParams {
+    #[serde(default)]
+    format: LinkFileFormat,
+    /// Include the password hashes of the links; only allowed for admins
+    include_password_hashes: Option<bool>,
+}
+
+/// Streams all the links to a file for admins, or the links of the current
+/// user for everyone else.
+pub async fn export_links(
+    db: Database,
+    user: CurrentUser,
+    UrlQuery(params): UrlQuery<ExportParams>,
+) -> Result<Response, ServiceError> {
+    let user_id = signed_in_user_id(&user, "export links")?;
+    let owner_id = (!user.is_admin()).then_some(user_id);
+    let include_password_hashes = params.include_password_hashes.unwrap_or(false);
+    if include_password_hashes && !user.is_admin() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "Permission denied",
+            "Only admins can export the password hashes",
+        ));
+    }
+
+    let format = params.format;
+    Response::builder()
+        .header(CONTENT_TYPE, format.content_type())
+        .header(
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"links.{}\"", format.name()),
+        )
+        .body(Body::streaming(transfer::export(
+            db,
+            format,
+            owner_id,
+            include_password_hashes,
+        )))
         .map_err(|e| {
             error(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "Database error",
+                "Internal error",
                 &e.to_string(),
             )
-        })?;
+        })
+}
 
-    if let Some(mut link) = link {
-        link.visits += 1;
-        link.update(&db).await.map_err(|e| {
-            error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Database error",
-                &e.to_string(),
-            )
-        })?;
+/// Outcome of importing a single link, along with the metadata target of
+/// the imported one, as returned by [`metadata_target`].
+enum ImportOutcome {
+    Created(Option<(i32, String)>),
+    Overwritten(Option<(i32, String)>),
+    Duplicate,
+}
 
-        Ok(Redirect::new(link.url))
-    } else {
-        Err(error(
-            StatusCode::NOT_FOUND,
-            "Link not found",
-            &format!("Link with slug {} not found", slug),
-        ))
-    }
+pub async fn import_links(
+    db: Database,
+    config: AppConfig,
+    user: CurrentUser,
+    budget: RateLimitBudget,
+    Json(payload): Json<LinkImportRequest>,
+) -> Result<Json<LinkImportReport>, ServiceError> {
+    signed_in_user_id(&user, "import links")?;
+    let rows = transfer::parse_records(payload.format, &payload.data)
+        .map_err(|message| error(StatusCode::BAD_REQUEST, "Invalid file", &message))?;
+    budget.acquire(rows.iter().filter(|row| row.record.is_ok()).count())?;
+
+    let (report, metadata_targets) = import_rows(
+        &db,
+        &config,
+        rows,
+        payload.on_duplicate,
+        user.id(),
+        |link: &Link| ensure_can_manage(&user, link),
+    )
+    .await?;
+    metadata::fetch_in_background(db, &config.metadata_fetcher, metadata_targets);
+
+    Ok(Json(report))
+}
+
+/// Imports the links from `rows` in a single transaction, owned by
+/// `owner_id`.
+///
+/// The existing links are only overwritten if `can_overwrite` allows it. The
+/// invalid rows are listed in the report, but the whole import fails if
+/// `on_duplicate` is [`DuplicateStrategy::Fail`] and any of the slugs is
+/// taken. The imported links without a title are returned along with the
+/// report, as targets for the metadata fetcher.
+pub(crate) async fn import_rows(
+    db: &Database,
+    config: &AppConfig,
+    rows: Vec<ImportedRow>,
+    on_duplicate: DuplicateStrategy,
+    owner_id: Option<i64>,
+    can_overwrite: impl Fn(&Link) -> Result<(), ServiceError>,
+) -> Result<(LinkImportReport, Vec<(i32, String)>), ServiceError> {
+    let transaction = db.transaction().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    let now = Utc::now();
+    let mut report = LinkImportReport::default();
+    let mut duplicates = Vec::new();
+    let mut metadata_targets = Vec::new();
+    for row in rows {
+        let record = match row.record {
+            Ok(record) => record,
+            Err(message) => {
+                report.errors.push(LinkImportError {
+                    line: row.line,
+                    slug: None,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let slug = record.slug.clone();
+        let outcome = ensure_batch_can_continue(
+            import_record(
+                &transaction,
+                config,
+                record,
+                on_duplicate,
+                owner_id,
+                &can_overwrite,
+                now,
+            )
+            .await,
+        )?;
+        match outcome {
+            Ok(ImportOutcome::Created(target)) => {
+                report.created += 1;
+                metadata_targets.extend(target);
+            }
+            Ok(ImportOutcome::Overwritten(target)) => {
+                report.overwritten += 1;
+                metadata_targets.extend(target);
+            }
+            Ok(ImportOutcome::Duplicate) => {
+                report.skipped += 1;
+                duplicates.push(slug);
+            }
+            Err(e) => report.errors.push(LinkImportError {
+                line: row.line,
+                slug: Some(slug),
+                message: e.message.unwrap_or(e.error),
+            }),
+        }
+    }
+
+    if on_duplicate == DuplicateStrategy::Fail && !duplicates.is_empty() {
+        transaction.rollback().await.map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+        return Err(error(
+            StatusCode::CONFLICT,
+            "Duplicate slugs",
+            &format!(
+                "Links with the following slugs already exist: {}",
+                duplicates.join(", ")
+            ),
+        ));
+    }
+    transaction.commit().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok((report, metadata_targets))
+}
+
+/// Validates `record` and creates the link it describes, or overwrites the
+/// existing one with the same slug if `on_duplicate` says so.
+async fn import_record<DB: DatabaseBackend>(
+    db: &DB,
+    config: &AppConfig,
+    record: LinkRecord,
+    on_duplicate: DuplicateStrategy,
+    owner_id: Option<i64>,
+    can_overwrite: impl Fn(&Link) -> Result<(), ServiceError>,
+    now: DateTime<Utc>,
+) -> Result<ImportOutcome, ServiceError> {
+    config
+        .slug_policy
+        .validate(&record.slug)
+        .map_err(|violation| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Invalid slug",
+                &violation.to_string(),
+            )
+        })?;
+    let url_policy = UrlPolicy::from_config(config);
+    let url = validate_url(&url_policy, &record.url)?;
+    let fallback_url = record
+        .fallback_url
+        .as_deref()
+        .map(|fallback_url| validate_url(&url_policy, fallback_url))
+        .transpose()?;
+    validate_max_visits(record.max_visits)?;
+    if record.visits < 0 {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid visit count",
+            "Number of visits must not be negative",
+        ));
+    }
+    if let Some(password_hash) = &record.password_hash
+        && PasswordHash::new(password_hash.clone()).is_err()
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid password hash",
+            "Password hash must be in the PHC string format",
+        ));
+    }
+    let tags = validate_tags(&record.tags)?;
+    let title = validate_text("Title", record.title, MAX_TITLE_LENGTH)?;
+    let description = validate_text("Description", record.description, MAX_DESCRIPTION_LENGTH)?;
+    let notes = validate_text("Notes", record.notes, MAX_NOTES_LENGTH)?;
+    let redirect_status = record
+        .redirect_status
+        .map(validate_redirect_status)
+        .transpose()?;
+
+    let existing: Option<Link> = query!(Link, $slug == record.slug.clone())
+        .get(db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    let (mut link, is_new) = match existing {
+        None => (
+            Link {
+                id: cot::db::Auto::auto(),
+                slug: record.slug,
+                url: String::new(),
+                created_at: now,
+                visits: 0,
+                expires_at: None,
+                fallback_url: None,
+                max_visits: None,
+                password_hash: None,
+                owner_id,
+                canonical_url: None,
+                generated_slug: Some(false),
+                title: None,
+                description: None,
+                notes: None,
+                favicon_url: None,
+                redirect_status: None,
+                query_passthrough: None,
+                prefix: None,
+                revision: None,
+            },
+            true,
+        ),
+        Some(link) if on_duplicate == DuplicateStrategy::Overwrite => {
+            can_overwrite(&link)?;
+            (link, false)
+        }
+        Some(_) => return Ok(ImportOutcome::Duplicate),
+    };
+
+    link.canonical_url = canonicalize(&url, config.strip_tracking_parameters).ok();
+    link.url = url;
+    link.created_at = record.created_at.unwrap_or(now);
+    link.visits = record.visits;
+    link.expires_at = record.expires_at;
+    link.fallback_url = fallback_url;
+    link.max_visits = record.max_visits;
+    link.password_hash = record.password_hash;
+    link.title = title;
+    link.description = description;
+    link.notes = notes;
+    // The icon of the overwritten link's target page
+    link.favicon_url = None;
+    link.redirect_status = redirect_status;
+    link.query_passthrough = query_passthrough_name(record.query_passthrough);
+    link.prefix = record.prefix.then_some(true);
+
+    let result = if is_new {
+        link.insert(db).await
+    } else {
+        // Lets the concurrent edits know the settings have changed
+        link.revision = Some(link.revision.unwrap_or_default() + 1);
+        link.update(db).await
+    };
+    result.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    set_link_tags(db, &link, &tags).await?;
+
+    let target = metadata_target(config, &link);
+    Ok(if is_new {
+        ImportOutcome::Created(target)
+    } else {
+        ImportOutcome::Overwritten(target)
+    })
+}
+
+/// Returns a link with a generated slug and no other settings, created by
+/// `owner_id` and pointing to `canonical_url`, that still redirects.
+async fn find_reusable_link<DB: DatabaseBackend>(
+    db: &DB,
+    canonical_url: &str,
+    owner_id: Option<i64>,
+    now: DateTime<Utc>,
+) -> Result<Option<Link>, ServiceError> {
+    let candidates = query!(Link, $canonical_url == Some(canonical_url.to_owned()))
+        .all(db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+
+    // Only the links a plain create request could have resulted in are reused
+    let candidates = candidates.into_iter().filter(|link| {
+        link.generated_slug == Some(true)
+            && link.owner_id == owner_id
+            && link.fallback_url.is_none()
+            && link.max_visits.is_none()
+            && !link.is_password_protected()
+            && !link.is_expired(now)
+            && link.title.is_none()
+            && link.description.is_none()
+            && link.notes.is_none()
+            && link.redirect_status.is_none()
+            && link.query_passthrough.is_none()
+            && link.prefix.is_none()
+    });
+    for link in candidates {
+        let has_tags = tags::has_tags(db, &link).await.map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+        if !has_tags {
+            return Ok(Some(link));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Inserts `link`, failing if its slug is already taken.
+async fn insert_link<DB: DatabaseBackend>(db: &DB, link: &mut Link) -> Result<(), ServiceError> {
+    // Check if slug exists
+    let exists: bool = query!(Link, $slug == link.slug.clone())
+        .exists(db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    if exists {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Slug already exists",
+            &format!("Slug {} already exists", link.slug),
+        ));
+    }
+
+    // Use insert to catch potential race condition if check above passed but
+    // another request inserted same slug
+    match link.insert(db).await {
+        Ok(_) => Ok(()),
+        Err(cot::db::DatabaseError::UniqueViolation) => Err(error(
+            StatusCode::BAD_REQUEST,
+            "Slug already exists",
+            &format!("Slug {} already exists", link.slug),
+        )),
+        Err(e) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )),
+    }
+}
+
+/// Inserts `link` with a slug from the configured generator, retrying with
+/// new slugs until an unused one is found.
+async fn insert_link_with_generated_slug<DB: DatabaseBackend>(
+    db: &DB,
+    config: &AppConfig,
+    link: &mut Link,
+) -> Result<(), ServiceError> {
+    let generator = slug_generator::from_config(&config.slug_generator).map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Invalid configuration",
+            &e.to_string(),
+        )
+    })?;
+    let filter = OffensiveWordFilter::new(&config.slug_generator.blocked_words);
+    let link_count = if generator.needs_link_count() {
+        Link::objects().count(db).await.map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?
+    } else {
+        0
+    };
+    let url = link.url.clone();
+    let context = SlugContext {
+        url: &url,
+        link_count,
+    };
+
+    for attempt in 0..MAX_SLUG_ATTEMPTS {
+        let slug = generator.generate(context, attempt);
+        if filter.is_offensive(&slug) || config.slug_policy.validate(&slug).is_err() {
+            continue;
+        }
+        // A failed insert aborts the whole transaction on some databases, so
+        // the taken slugs are skipped beforehand
+        let exists = query!(Link, $slug == slug.clone())
+            .exists(db)
+            .await
+            .map_err(|e| {
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Database error",
+                    &e.to_string(),
+                )
+            })?;
+        if exists {
+            continue;
+        }
+
+        link.slug = slug;
+        match link.insert(db).await {
+            Ok(_) => return Ok(()),
+            Err(cot::db::DatabaseError::UniqueViolation) => {}
+            Err(e) => {
+                return Err(error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Database error",
+                    &e.to_string(),
+                ));
+            }
+        }
+    }
+
+    Err(error(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Could not generate slug",
+        &format!("No free slug found in {MAX_SLUG_ATTEMPTS} attempts; please choose one"),
+    ))
+}
+
+pub async fn update_link(
+    db: Database,
+    config: AppConfig,
+    user: CurrentUser,
+    if_match: IfMatch,
+    Path(slug): Path<String>,
+    Json(payload): Json<LinkUpdateRequest>,
+) -> Result<LinkWithEtag, ServiceError> {
+    let transaction = db.transaction().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    let original = get_link_by_slug(&transaction, &slug).await?;
+    ensure_can_manage(&user, &original)?;
+
+    let modified = || {
+        error(
+            StatusCode::PRECONDITION_FAILED,
+            "Link modified",
+            &format!(
+                "Link with slug {} has been modified since it was retrieved",
+                slug
+            ),
+        )
+    };
+    let original_tags = tags::tags_of(&transaction, std::slice::from_ref(&original))
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?
+        .pop()
+        .unwrap_or_default();
+    if !if_match.matches(&original.etag(&original_tags)) {
+        return Err(modified());
+    }
+    let mut link = original.clone();
+
+    let url_policy = UrlPolicy::from_config(&config);
+    let tags = payload.tags.as_deref().map(validate_tags).transpose()?;
+    let url = payload
+        .url
+        .as_deref()
+        .map(|url| validate_url(&url_policy, url))
+        .transpose()?;
+    let changes_target = url.as_ref().is_some_and(|url| *url != link.url);
+    let clears_title = payload.title.as_ref().is_some_and(Option::is_none);
+    if let Some(url) = url {
+        link.canonical_url = canonicalize(&url, config.strip_tracking_parameters).ok();
+        link.url = url;
+    }
+    if changes_target {
+        // The icon of the previous target page
+        link.favicon_url = None;
+    }
+    if let Some(title) = payload.title {
+        link.title = validate_text("Title", title, MAX_TITLE_LENGTH)?;
+    }
+    if let Some(description) = payload.description {
+        link.description = validate_text("Description", description, MAX_DESCRIPTION_LENGTH)?;
+    }
+    if let Some(notes) = payload.notes {
+        link.notes = validate_text("Notes", notes, MAX_NOTES_LENGTH)?;
+    }
+    if let Some(redirect_status) = payload.redirect_status {
+        link.redirect_status = Some(validate_redirect_status(redirect_status)?);
+    }
+    if let Some(query_passthrough) = payload.query_passthrough {
+        link.query_passthrough = query_passthrough_name(query_passthrough);
+    }
+    if let Some(prefix) = payload.prefix {
+        link.prefix = prefix.then_some(true);
+    }
+    if let Some(expires_at) = payload.expires_at {
+        validate_expiration(expires_at, Utc::now())?;
+        link.expires_at = expires_at;
+    }
+    if let Some(fallback_url) = payload.fallback_url {
+        link.fallback_url = fallback_url
+            .as_deref()
+            .map(|fallback_url| validate_url(&url_policy, fallback_url))
+            .transpose()?;
+    }
+    if let Some(max_visits) = payload.max_visits {
+        validate_max_visits(max_visits)?;
+        link.max_visits = max_visits;
+    }
+    if let Some(password) = payload.password {
+        validate_password(password.as_deref())?;
+        match password {
+            Some(password) => link.set_password(&Password::new(password)),
+            None => link.password_hash = None,
+        }
+    }
+
+    // The settings are only written if they have not been changed since
+    // they were read, so that the precondition holds at the time of writing
+    let updated = link
+        .update_settings(&transaction, &original)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    if !updated {
+        return Err(modified());
+    }
+    if let Some(tags) = &tags {
+        set_link_tags(&transaction, &link, tags).await?;
+    }
+    transaction.commit().await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    let api_link = to_api_link_with_tags(&db, &link, true, true).await?;
+
+    if changes_target || clears_title {
+        let targets = metadata_target(&config, &link).into_iter().collect();
+        metadata::fetch_in_background(db, &config.metadata_fetcher, targets);
+    }
+
+    Ok(LinkWithEtag(api_link))
+}
+
+async fn get_link_by_slug<DB: DatabaseBackend>(db: &DB, slug: &str) -> Result<Link, ServiceError> {
+    let link: Option<Link> = query!(Link, $slug == slug.to_owned())
+        .get(db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+
+    link.ok_or_else(|| {
+        error(
+            StatusCode::NOT_FOUND,
+            "Link not found",
+            &format!("Link with slug {} not found", slug),
+        )
+    })
+}
+
+/// Responds with a redirect to `url`, which the browsers are only allowed to
+/// cache if `cacheable` is set.
+fn redirect(
+    url: String,
+    status: RedirectStatus,
+    cacheable: bool,
+) -> Result<Response, ServiceError> {
+    let cache_control = if cacheable {
+        format!("public, max-age={PERMANENT_REDIRECT_MAX_AGE_SECS}")
+    } else {
+        "no-store".to_owned()
+    };
+
+    Response::builder()
+        .status(status.code())
+        .header(LOCATION, url)
+        .header(CACHE_CONTROL, cache_control)
+        .body(Body::empty())
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error",
+                &e.to_string(),
+            )
+        })
+}
+
+static LAST_VISIT_PRUNE: AtomicI64 = AtomicI64::new(0);
+
+/// Removes the visits past the retention period, at most once per
+/// [`VISIT_PRUNE_INTERVAL_SECS`].
+async fn prune_visits(db: &Database, config: &AppConfig, now: DateTime<Utc>) {
+    let Some(retention_days) = config.visit_retention_days else {
+        return;
+    };
+
+    let last_prune = LAST_VISIT_PRUNE.load(Ordering::Relaxed);
+    if now.timestamp() - last_prune < VISIT_PRUNE_INTERVAL_SECS
+        || LAST_VISIT_PRUNE
+            .compare_exchange(
+                last_prune,
+                now.timestamp(),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_err()
+    {
+        return;
+    }
+
+    let cutoff = now - TimeDelta::days(retention_days.into());
+    if let Err(e) = Visit::remove_older_than(db, cutoff).await {
+        log::error!("Could not remove old visits: {e}");
+    }
+}
+
+/// Makes sure the visitor only follows `link` with a path following the slug
+/// if it is a prefix link; the other links are not found under such paths.
+fn check_subpath_allowed(link: &Link, visitor: &VisitorInfo) -> Result<(), ServiceError> {
+    if visitor.subpath.is_some() && !link.is_prefix() {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "Link not found",
+            &format!("Link with slug {} not found", link.slug),
+        ));
+    }
+    Ok(())
+}
+
+/// Registers a visit and redirects the visitor to the target of `link`, with
+/// the path following the slug appended to it for the prefix links.
+async fn follow_link(
+    db: &Database,
+    config: &AppConfig,
+    visitor: &VisitorInfo,
+    mut link: Link,
+) -> Result<Response, ServiceError> {
+    let url = match &visitor.subpath {
+        Some(subpath) => prefix_links::append_subpath(&link.url, subpath)
+            .map_err(|e| error(StatusCode::BAD_REQUEST, "Invalid path", &e.to_string()))?,
+        None => link.url.clone(),
+    };
+
+    let now = Utc::now();
+    if link.is_expired(now) {
+        return match &link.fallback_url {
+            Some(fallback_url) => {
+                let fallback_url = query_passthrough::merge_query(
+                    fallback_url,
+                    visitor.query.as_deref(),
+                    link.query_passthrough(),
+                );
+                redirect(fallback_url, RedirectStatus::default(), false)
+            }
+            None => Err(error(
+                StatusCode::GONE,
+                "Link expired",
+                &format!("Link with slug {} has expired", link.slug),
+            )),
+        };
+    }
+
+    let visit = visitor.to_visit(&link, now);
+    let registered = link.register_visit(db, visit).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    if !registered {
+        return Err(error(
+            StatusCode::GONE,
+            "Link exhausted",
+            &format!(
+                "Link with slug {} has reached its limit of visits",
+                link.slug
+            ),
+        ));
+    }
+    prune_visits(db, config, now).await;
+
+    let target =
+        query_passthrough::merge_query(&url, visitor.query.as_deref(), link.query_passthrough());
+    redirect(target, link.redirect_status(), link.is_redirect_cacheable())
+}
+
+pub async fn redirect_to_link(
+    db: Database,
+    config: AppConfig,
+    visitor: VisitorInfo,
+    unlocked_links: UnlockedLinks,
+    Path(slug): Path<String>,
+) -> Result<Response, ServiceError> {
+    let link = get_link_by_slug(&db, &slug).await?;
+    check_subpath_allowed(&link, &visitor)?;
+
+    if !unlocked_links.is_unlocked(&link) {
+        return Ok(pages::link_password_form(&slug, None));
+    }
+
+    follow_link(&db, &config, &visitor, link).await
+}
+
+/// Shows where a link leads without following it, so no visit is counted.
+pub async fn preview_link(
+    db: Database,
+    user: CurrentUser,
+    unlocked_links: UnlockedLinks,
+    Path(slug): Path<String>,
+) -> Result<Response, ServiceError> {
+    let link = get_link_by_slug(&db, &slug).await?;
+
+    let reveal_url = unlocked_links.is_unlocked(&link) || user.can_manage(&link);
+    Ok(pages::link_preview(&link, reveal_url))
+}
+
+#[derive(Debug, Form)]
+pub struct LinkPasswordForm {
+    password: Password,
+}
+
+pub async fn unlock_link(
+    db: Database,
+    config: AppConfig,
+    visitor: VisitorInfo,
+    mut unlocked_links: UnlockedLinks,
+    Path(slug): Path<String>,
+    RequestForm(form): RequestForm<LinkPasswordForm>,
+) -> Result<Response, ServiceError> {
+    let link = get_link_by_slug(&db, &slug).await?;
+    check_subpath_allowed(&link, &visitor)?;
+
+    let password = match form {
+        FormResult::Ok(form) => form.password,
+        FormResult::ValidationError(_) => {
+            return Ok(pages::link_password_form(
+                &slug,
+                Some("Please enter the password"),
+            ));
+        }
+    };
+    if !link.verify_password(&password) {
+        return Ok(pages::link_password_form(&slug, Some("Invalid password")));
+    }
+
+    unlocked_links.unlock(&link).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Session error",
+            &e.to_string(),
+        )
+    })?;
+
+    let mut response = follow_link(&db, &config, &visitor, link).await?;
+    // The browser must not submit the password form again to the target, as
+    // it would with the redirects keeping the request method
+    if response.status().is_redirection() {
+        *response.status_mut() = StatusCode::SEE_OTHER;
+    }
+    Ok(response)
+}
+
+#[derive(serde::Deserialize, cot::schemars::JsonSchema)]
+pub struct StatsParams {
+    interval: Option<StatsInterval>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+pub async fn get_link_stats(
+    db: Database,
+    user: CurrentUser,
+    Path(slug): Path<String>,
+    UrlQuery(params): UrlQuery<StatsParams>,
+) -> Result<Json<LinkStats>, ServiceError> {
+    let link = get_link_by_slug(&db, &slug).await?;
+    ensure_can_manage(&user, &link)?;
+
+    let interval = params.interval.unwrap_or_default();
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params
+        .from
+        .unwrap_or_else(|| stats::default_from(interval, to));
+    if from >= to {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid range",
+            "Start of the range must be before its end",
+        ));
+    }
+    let Some(buckets) = stats::time_buckets(interval, from, to) else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid range",
+            &format!(
+                "Range must not span more than {} intervals",
+                stats::MAX_BUCKETS
+            ),
+        ));
+    };
+
+    let link_key = ForeignKey::<Link>::PrimaryKey(link.id);
+    let visits_in_range =
+        query!(Visit, $link == link_key && $visited_at >= from && $visited_at < to);
+    let visit_count = visits_in_range.count(&db).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+    if visit_count > stats::MAX_VISITS {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid range",
+            &format!(
+                "Range must not contain more than {} visits; it contains {visit_count}",
+                stats::MAX_VISITS
+            ),
+        ));
+    }
+    let visits = visits_in_range.all(&db).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok(Json(stats::link_stats(
+        link.slug, interval, from, to, buckets, &visits,
+    )))
+}
+
+#[derive(serde::Deserialize, cot::schemars::JsonSchema)]
+pub struct QrCodeParams {
+    #[serde(default)]
+    format: QrCodeFormat,
+    size: Option<u32>,
+    margin: Option<u32>,
+    ecc: Option<ErrorCorrection>,
+    foreground: Option<String>,
+    background: Option<String>,
+    /// Whether to put the configured logo in the middle of the code; `true`
+    /// by default
+    logo: Option<bool>,
+}
+
+fn qr_code_colour(
+    field: &str,
+    value: Option<&str>,
+    default: &str,
+) -> Result<qr_codes::Colour, ServiceError> {
+    if let Some(value) = value {
+        return qr_codes::Colour::parse(value).ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid {field} colour"),
+                "Colours must be written as #rrggbb, #rgb or transparent",
+            )
+        });
+    }
+    qr_codes::Colour::parse(default).ok_or_else(|| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Invalid configuration",
+            &format!("Default {field} colour of the QR codes is invalid: {default}"),
+        )
+    })
+}
+
+/// Renders the short URL of a link as a QR code.
+pub async fn get_link_qr_code(
+    db: Database,
+    config: AppConfig,
+    Path(slug): Path<String>,
+    UrlQuery(params): UrlQuery<QrCodeParams>,
+) -> Result<Response, ServiceError> {
+    let link = get_link_by_slug(&db, &slug).await?;
+
+    let size = params.size.unwrap_or(DEFAULT_QR_CODE_SIZE);
+    if size == 0 || size > MAX_QR_CODE_SIZE {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid size",
+            &format!("Size must be between 1 and {MAX_QR_CODE_SIZE} pixels"),
+        ));
+    }
+    let margin = params.margin.unwrap_or(DEFAULT_QR_CODE_MARGIN);
+    if margin > MAX_QR_CODE_MARGIN {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid margin",
+            &format!("Margin must be at most {MAX_QR_CODE_MARGIN} modules"),
+        ));
+    }
+    let foreground = qr_code_colour(
+        "foreground",
+        params.foreground.as_deref(),
+        &config.qr_codes.foreground,
+    )?;
+    if foreground.is_transparent() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid foreground colour",
+            "Foreground colour must not be transparent",
+        ));
+    }
+    let background = qr_code_colour(
+        "background",
+        params.background.as_deref(),
+        &config.qr_codes.background,
+    )?;
+
+    let logo = match &config.qr_codes.logo_path {
+        Some(path) if params.logo.unwrap_or(true) => {
+            Some(tokio::fs::read(path).await.map_err(|e| {
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Invalid configuration",
+                    &format!("Could not read the QR code logo {path}: {e}"),
+                )
+            })?)
+        }
+        _ => None,
+    };
+    // The logo covers part of the code, so by default use the level that
+    // restores the most of it
+    let error_correction = params.ecc.unwrap_or(if logo.is_some() {
+        ErrorCorrection::High
+    } else {
+        ErrorCorrection::Medium
+    });
+
+    let options = qr_codes::QrCodeOptions {
+        size,
+        margin,
+        error_correction,
+        foreground,
+        background,
+        logo,
+    };
+    let short_url = format!("{}/{}", config.base_url, urlencoding::encode(&link.slug));
+    let body = match params.format {
+        QrCodeFormat::Svg => qr_codes::render_svg(&short_url, &options).map(Body::from),
+        QrCodeFormat::Png => qr_codes::render_png(&short_url, &options).map(Body::from),
+    }
+    .map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Could not render the QR code",
+            &e.to_string(),
+        )
+    })?;
+
+    Response::builder()
+        .header(CONTENT_TYPE, params.format.content_type())
+        .header(
+            CONTENT_DISPOSITION,
+            format!(
+                "inline; filename*=UTF-8''{}.{}",
+                urlencoding::encode(&link.slug),
+                params.format.name()
+            ),
+        )
+        .body(body)
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error",
+                &e.to_string(),
+            )
+        })
+}
+
+const MAX_TOKEN_NAME_LENGTH: usize = 100;
+const TOKEN_SECRET_LENGTH: usize = 40;
+/// The number of random characters of a token kept to tell the tokens apart.
+const TOKEN_PREFIX_LENGTH: usize = 6;
+
+fn to_api_token(token: &ApiToken) -> ApiTokenInfo {
+    let Auto::Fixed(id) = token.id else {
+        unreachable!("tokens are only returned after being saved");
+    };
+
+    ApiTokenInfo {
+        id,
+        name: token.name.clone(),
+        prefix: token.prefix.clone(),
+        scope: token.scope(),
+        created_at: token.created_at,
+        expires_at: token.expires_at,
+        revoked_at: token.revoked_at,
+        last_used_at: token.last_used_at,
+    }
+}
+
+/// Returns the ID of `user`, or an error if the request is anonymous.
+fn signed_in_user_id(user: &CurrentUser, action: &str) -> Result<i64, ServiceError> {
+    user.id().ok_or_else(|| {
+        error(
+            StatusCode::UNAUTHORIZED,
+            "Authentication required",
+            &format!("You need to sign in to {action}"),
+        )
+    })
+}
+
+pub async fn get_tokens(
+    db: Database,
+    user: CurrentUser,
+) -> Result<Json<Vec<ApiTokenInfo>>, ServiceError> {
+    let user_id = signed_in_user_id(&user, "manage API tokens")?;
+
+    let mut tokens = query!(ApiToken, $user_id == user_id)
+        .all(&db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    tokens.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(Json(tokens.iter().map(to_api_token).collect()))
+}
+
+pub async fn create_token(
+    db: Database,
+    user: CurrentUser,
+    Json(payload): Json<ApiTokenCreateRequest>,
+) -> Result<Json<ApiTokenCreated>, ServiceError> {
+    let user_id = signed_in_user_id(&user, "manage API tokens")?;
+    if user.token_scope().is_some() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "Permission denied",
+            "API tokens can only be created after signing in with a password",
+        ));
+    }
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LENGTH {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "Invalid name",
+            &format!("Token name must be between 1 and {MAX_TOKEN_NAME_LENGTH} characters long"),
+        ));
+    }
+    let now = Utc::now();
+    validate_expiration(payload.expires_at, now)?;
+
+    let secret = format!(
+        "{}{}",
+        ApiToken::SECRET_PREFIX,
+        nanoid!(TOKEN_SECRET_LENGTH, &ALPHABET)
+    );
+    let mut token = ApiToken {
+        id: Auto::auto(),
+        user_id,
+        name: name.to_owned(),
+        prefix: secret[..ApiToken::SECRET_PREFIX.len() + TOKEN_PREFIX_LENGTH].to_owned(),
+        token_hash: ApiToken::hash_secret(&secret),
+        scope: payload.scope.name().to_owned(),
+        created_at: now,
+        expires_at: payload.expires_at,
+        revoked_at: None,
+        last_used_at: None,
+    };
+    token.insert(&db).await.map_err(|e| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error",
+            &e.to_string(),
+        )
+    })?;
+
+    Ok(Json(ApiTokenCreated {
+        token: to_api_token(&token),
+        secret,
+    }))
+}
+
+pub async fn revoke_token(
+    db: Database,
+    user: CurrentUser,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, ServiceError> {
+    let user_id = signed_in_user_id(&user, "manage API tokens")?;
+
+    let token_id = Auto::fixed(id);
+    let token: Option<ApiToken> = query!(ApiToken, $id == token_id && $user_id == user_id)
+        .get(&db)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    let Some(mut token) = token else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            "Token not found",
+            &format!("API token with ID {id} not found"),
+        ));
+    };
+
+    if token.revoked_at.is_none() {
+        token.revoked_at = Some(Utc::now());
+        token.update(&db).await.map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error",
+                &e.to_string(),
+            )
+        })?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,41 @@
+use url::Url;
+
+/// Query parameters added by analytics and ad platforms, which do not change
+/// the page being linked to.
+const TRACKING_PARAMETERS: &[&str] = &["fbclid", "gclid", "dclid", "msclkid", "mc_eid", "yclid"];
+const TRACKING_PARAMETER_PREFIXES: &[&str] = &["utm_"];
+
+/// Returns whether `name` is the name of a tracking query parameter.
+#[must_use]
+pub fn is_tracking_parameter(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    TRACKING_PARAMETERS.contains(&name.as_str())
+        || TRACKING_PARAMETER_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// Returns the canonical form of `url`, equal for the URLs pointing to the
+/// same resource.
+///
+/// The host is lowercased and converted to punycode, default ports are
+/// removed and the query parameters are sorted; if `strip_tracking` is set,
+/// tracking parameters such as `utm_source` are removed as well.
+pub fn canonicalize(url: &str, strip_tracking: bool) -> Result<String, url::ParseError> {
+    // Parsing takes care of the host and the port
+    let mut url = Url::parse(url.trim())?;
+
+    let mut parameters: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !(strip_tracking && is_tracking_parameter(name)))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    if parameters.is_empty() {
+        url.set_query(None);
+    } else {
+        parameters.sort();
+        url.query_pairs_mut().clear().extend_pairs(parameters);
+    }
+
+    Ok(url.into())
+}
@@ -0,0 +1,138 @@
+//! Subcommands of the backend's command line interface.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command, value_parser};
+use cot::auth::db::DatabaseUser;
+use cot::cli::CliTask;
+use cot::db::Database;
+use cot::project::{Bootstrapper, WithConfig};
+use shrt_common::config::AppConfig;
+use shrt_common::enums::EnumName;
+use shrt_common::transfer::{DuplicateStrategy, LinkImportReport};
+
+use crate::api;
+use crate::importers::ImportSource;
+use crate::transfer::ImportedRow;
+
+/// Imports the links exported from another link shortener.
+pub struct ImportLinksTask;
+
+#[async_trait(?Send)]
+impl CliTask for ImportLinksTask {
+    fn subcommand(&self) -> Command {
+        Command::new("import-links")
+            .about("Import the links exported from another link shortener")
+            .arg(
+                Arg::new("source")
+                    .long("source")
+                    .help("The link shortener the file was exported from")
+                    .required(true)
+                    .value_parser(ImportSource::ALL.map(|source| source.name())),
+            )
+            .arg(
+                Arg::new("on-duplicate")
+                    .long("on-duplicate")
+                    .help("What to do with the links whose slugs are taken")
+                    .default_value(DuplicateStrategy::default().name())
+                    .value_parser(DuplicateStrategy::ALL.map(|strategy| strategy.name())),
+            )
+            .arg(
+                Arg::new("owner")
+                    .long("owner")
+                    .value_name("USERNAME")
+                    .help("The user to own the imported links; no one if not set"),
+            )
+            .arg(
+                Arg::new("dry-run")
+                    .long("dry-run")
+                    .help("Only read the file and report the records that cannot be imported")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("file")
+                    .value_name("FILE")
+                    .help("The exported file")
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
+            )
+    }
+
+    async fn execute(
+        &mut self,
+        matches: &ArgMatches,
+        bootstrapper: Bootstrapper<WithConfig>,
+    ) -> cot::Result<()> {
+        let source = matches
+            .get_one::<String>("source")
+            .and_then(|name| ImportSource::from_name(name))
+            .expect("source is validated by clap");
+        let on_duplicate = matches
+            .get_one::<String>("on-duplicate")
+            .and_then(|name| DuplicateStrategy::from_name(name))
+            .expect("on-duplicate is validated by clap");
+        let path = matches
+            .get_one::<PathBuf>("file")
+            .expect("file is required");
+
+        let data = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| cot::Error::internal(format!("could not read {}: {e}", path.display())))?;
+        let rows = source.parse(&data).map_err(|message| {
+            cot::Error::internal(format!("could not read {}: {message}", path.display()))
+        })?;
+
+        if matches.get_flag("dry-run") {
+            print_dry_run_report(&rows);
+            return Ok(());
+        }
+
+        let bootstrapper = bootstrapper.with_apps().with_database().await?;
+        let context = bootstrapper.context();
+        let config = AppConfig::from_project_config(context.config())?;
+        let db: &Database = context.database();
+
+        let owner_id = match matches.get_one::<String>("owner") {
+            Some(username) => {
+                let user = DatabaseUser::get_by_username(db, username)
+                    .await?
+                    .ok_or_else(|| {
+                        cot::Error::internal(format!("user {username} does not exist"))
+                    })?;
+                Some(user.id())
+            }
+            None => None,
+        };
+
+        // The titles are not fetched, as the command exits right away
+        let (report, _) =
+            api::import_rows(db, &config, rows, on_duplicate, owner_id, |_| Ok(())).await?;
+        print_import_report(&report);
+
+        Ok(())
+    }
+}
+
+fn print_dry_run_report(rows: &[ImportedRow]) {
+    let valid = rows.iter().filter(|row| row.record.is_ok()).count();
+    println!("{valid} of {} records can be imported.", rows.len());
+    for row in rows {
+        if let Err(message) = &row.record {
+            println!("Line {}: {message}", row.line);
+        }
+    }
+}
+
+fn print_import_report(report: &LinkImportReport) {
+    println!(
+        "Created {}, overwritten {} and skipped {} links.",
+        report.created, report.overwritten, report.skipped
+    );
+    for row in &report.errors {
+        match &row.slug {
+            Some(slug) => println!("Line {} ({slug}): {}", row.line, row.message),
+            None => println!("Line {}: {}", row.line, row.message),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Reading and writing of comma-separated values, as described in RFC 4180.
+
+use std::fmt::{Display, Formatter};
+
+/// Appends `fields` to `out` as a single row, quoting the fields when needed.
+pub fn write_row<'a>(out: &mut String, fields: impl IntoIterator<Item = &'a str>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_field(out, field);
+    }
+    out.push('\n');
+}
+
+fn write_field(out: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+/// A row of a CSV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRow {
+    /// Number of the line the row starts at, counting from 1
+    pub line: u64,
+    pub fields: Vec<String>,
+}
+
+impl CsvRow {
+    /// Returns the field at `index`, or an empty string if the row is too
+    /// short.
+    #[must_use]
+    pub fn get(&self, index: usize) -> &str {
+        self.fields.get(index).map_or("", String::as_str)
+    }
+}
+
+/// Error returned when a CSV file ends inside a quoted field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    /// Number of the line the unterminated field starts at
+    pub line: u64,
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Quoted field starting at line {} is never closed",
+            self.line
+        )
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Splits `data` into rows, skipping the empty lines.
+///
+/// Both `\n` and `\r\n` line endings are accepted, and so are quotes in the
+/// middle of unquoted fields, which are kept as they are.
+pub fn parse(data: &str) -> Result<Vec<CsvRow>, CsvError> {
+    let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut quote_line = None;
+    let mut is_quoted_field = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quote_line.is_some() {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quote_line = None,
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !is_quoted_field => {
+                quote_line = Some(line);
+                is_quoted_field = true;
+            }
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                is_quoted_field = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                is_quoted_field = false;
+                if !(fields.len() == 1 && fields[0].is_empty()) {
+                    rows.push(CsvRow {
+                        line: row_line,
+                        fields: std::mem::take(&mut fields),
+                    });
+                }
+                fields.clear();
+                line += 1;
+                row_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+
+    if let Some(line) = quote_line {
+        return Err(CsvError { line });
+    }
+    if !field.is_empty() || !fields.is_empty() || is_quoted_field {
+        fields.push(field);
+        rows.push(CsvRow {
+            line: row_line,
+            fields,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Returns the index of the column named `name` in the `header` row,
+/// ignoring case and surrounding whitespace.
+#[must_use]
+pub fn column_index(header: &CsvRow, name: &str) -> Option<usize> {
+    header
+        .fields
+        .iter()
+        .position(|column| column.trim().eq_ignore_ascii_case(name))
+}
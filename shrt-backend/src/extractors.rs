@@ -0,0 +1,268 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use chrono::{DateTime, Utc};
+use cot::auth::db::DatabaseUser;
+use cot::auth::{Auth, UserId};
+use cot::db::{Auto, Database, ForeignKey};
+use cot::http::HeaderMap;
+use cot::request::RequestHead;
+use cot::request::extractors::FromRequestHead;
+use cot::session::Session;
+use shrt_common::config::AppConfig;
+use shrt_common::tokens::TokenScope;
+
+use crate::middleware::{LinkSubpath, RateLimitBudget, TokenAuth};
+use crate::models::{Link, Visit};
+
+const UNLOCKED_LINKS_KEY: &str = "unlocked_links";
+
+/// Password-protected links that have been unlocked in the current session.
+pub struct UnlockedLinks {
+    session: Session,
+    link_ids: Vec<i32>,
+}
+
+impl UnlockedLinks {
+    /// Returns whether the target of `link` can be revealed to the visitor.
+    ///
+    /// Links without a password are always considered unlocked.
+    #[must_use]
+    pub fn is_unlocked(&self, link: &Link) -> bool {
+        !link.is_password_protected()
+            || matches!(link.id, Auto::Fixed(id) if self.link_ids.contains(&id))
+    }
+
+    /// Returns the IDs of the links unlocked in the session.
+    #[must_use]
+    pub fn link_ids(&self) -> &[i32] {
+        &self.link_ids
+    }
+
+    /// Marks `link` as unlocked for the rest of the session.
+    pub async fn unlock(&mut self, link: &Link) -> cot::Result<()> {
+        if let Auto::Fixed(id) = link.id
+            && !self.link_ids.contains(&id)
+        {
+            self.link_ids.push(id);
+            self.session
+                .insert(UNLOCKED_LINKS_KEY, &self.link_ids)
+                .await
+                .map_err(cot::Error::internal)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromRequestHead for UnlockedLinks {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        let session = Session::from_request_head(head).await?;
+        let link_ids = session
+            .get::<Vec<i32>>(UNLOCKED_LINKS_KEY)
+            .await
+            .map_err(cot::Error::internal)?
+            .unwrap_or_default();
+
+        Ok(Self { session, link_ids })
+    }
+}
+
+impl cot::openapi::ApiOperationPart for UnlockedLinks {}
+
+/// Maximum length of the header values stored with each visit.
+const MAX_HEADER_LENGTH: usize = 512;
+
+/// Returns the IP address of the client, as reported by the reverse proxy.
+///
+/// The proxy appends the address it received the request from to
+/// `X-Forwarded-For`, so only the last entry is trusted; anything before it
+/// is supplied by the client.
+pub fn client_ip(headers: &HeaderMap) -> Option<IpAddr> {
+    let forwarded_for = headers
+        .get("X-Forwarded-For")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next());
+    let real_ip = headers
+        .get("X-Real-IP")
+        .and_then(|value| value.to_str().ok());
+
+    forwarded_for
+        .or(real_ip)
+        .and_then(|ip| ip.trim().parse().ok())
+}
+
+/// Zeroes out the host part of `ip`, keeping the /24 network for IPv4 and the
+/// /48 network for IPv6 addresses.
+#[must_use]
+pub fn anonymize_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => IpAddr::V4(Ipv4Addr::from_bits(ip.to_bits() & !0xff)),
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from_bits(ip.to_bits() & !((1 << 80) - 1))),
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let value = headers.get(name)?.to_str().ok()?;
+    Some(value.chars().take(MAX_HEADER_LENGTH).collect())
+}
+
+/// Information about a visitor following a short link.
+#[derive(Debug, Clone)]
+pub struct VisitorInfo {
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub accept_language: Option<String>,
+    /// Anonymized IP address of the visitor
+    pub client_ip: Option<IpAddr>,
+    /// Query string of the request, passed on to the target if the link is
+    /// set to
+    pub query: Option<String>,
+    /// Path following the slug, forwarded to the target of the prefix links
+    pub subpath: Option<String>,
+}
+
+impl VisitorInfo {
+    /// Creates a record of a visit of `link` at `visited_at`.
+    #[must_use]
+    pub fn to_visit(&self, link: &Link, visited_at: DateTime<Utc>) -> Visit {
+        Visit {
+            id: Auto::auto(),
+            link: ForeignKey::PrimaryKey(link.id),
+            visited_at,
+            referrer: self.referrer.clone(),
+            user_agent: self.user_agent.clone(),
+            accept_language: self.accept_language.clone(),
+            client_ip: self.client_ip.map(|ip| ip.to_string()),
+        }
+    }
+}
+
+impl FromRequestHead for VisitorInfo {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        Ok(Self {
+            referrer: header_value(&head.headers, "Referer"),
+            user_agent: header_value(&head.headers, "User-Agent"),
+            accept_language: header_value(&head.headers, "Accept-Language"),
+            client_ip: client_ip(&head.headers).map(anonymize_ip),
+            query: head.uri.query().map(ToOwned::to_owned),
+            subpath: head
+                .extensions
+                .get::<LinkSubpath>()
+                .map(|subpath| subpath.0.clone()),
+        })
+    }
+}
+
+impl cot::openapi::ApiOperationPart for VisitorInfo {}
+
+/// Entity tags listed in the `If-Match` request header, if it was sent.
+pub struct IfMatch(Option<String>);
+
+impl IfMatch {
+    /// Returns whether a resource with `etag` satisfies the precondition.
+    ///
+    /// Requests without the header always satisfy it.
+    #[must_use]
+    pub fn matches(&self, etag: &str) -> bool {
+        match &self.0 {
+            None => true,
+            Some(header) => header
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag == etag),
+        }
+    }
+}
+
+impl FromRequestHead for IfMatch {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        Ok(Self(header_value(&head.headers, "If-Match")))
+    }
+}
+
+impl cot::openapi::ApiOperationPart for IfMatch {}
+
+impl FromRequestHead for RateLimitBudget {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        Ok(head
+            .extensions
+            .get::<RateLimitBudget>()
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+impl cot::openapi::ApiOperationPart for RateLimitBudget {}
+
+/// The user making the request.
+pub struct CurrentUser {
+    user_id: Option<i64>,
+    is_admin: bool,
+    /// The scope of the API token used, if any
+    token_scope: Option<TokenScope>,
+}
+
+impl CurrentUser {
+    /// Returns the ID of the user, or `None` if the request is anonymous.
+    #[must_use]
+    pub fn id(&self) -> Option<i64> {
+        self.user_id
+    }
+
+    /// Returns the scope of the API token the request has been made with, or
+    /// `None` if the user has signed in with a password.
+    #[must_use]
+    pub fn token_scope(&self) -> Option<TokenScope> {
+        self.token_scope
+    }
+
+    /// Returns whether the user is one of the admins listed in
+    /// [`AppConfig::admins`].
+    #[must_use]
+    pub fn is_admin(&self) -> bool {
+        self.is_admin
+    }
+
+    /// Returns whether the user is allowed to edit and remove `link`.
+    ///
+    /// Only the owner of the link and the admins are allowed to do so.
+    #[must_use]
+    pub fn can_manage(&self, link: &Link) -> bool {
+        self.is_admin || (self.user_id.is_some() && link.owner_id == self.user_id)
+    }
+}
+
+impl FromRequestHead for CurrentUser {
+    async fn from_request_head(head: &RequestHead) -> cot::Result<Self> {
+        use cot::request::RequestExt as _;
+
+        let config = AppConfig::from_project_config(head.project_config())?;
+        let is_admin = |username: &str| config.admins.iter().any(|admin| admin == username);
+
+        if let Some(token_auth) = head.extensions.get::<TokenAuth>() {
+            let db = Database::from_request_head(head).await?;
+            let user = DatabaseUser::get_by_id(&db, token_auth.user_id).await?;
+
+            return Ok(Self {
+                user_id: Some(token_auth.user_id),
+                is_admin: user.is_some_and(|user| is_admin(user.username())),
+                token_scope: Some(token_auth.scope),
+            });
+        }
+
+        let auth = Auth::from_request_head(head).await?;
+        let user = auth.user();
+        let user_id = match user.id() {
+            Some(UserId::Int(id)) => Some(id),
+            _ => None,
+        };
+
+        Ok(Self {
+            user_id,
+            is_admin: user.username().is_some_and(|username| is_admin(&username)),
+            token_scope: None,
+        })
+    }
+}
+
+impl cot::openapi::ApiOperationPart for CurrentUser {}
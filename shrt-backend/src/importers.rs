@@ -0,0 +1,166 @@
+//! Conversion of the links exported from other link shorteners.
+
+mod bitly;
+mod kutt;
+mod shlink;
+mod yourls;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use shrt_common::enums::EnumName;
+use url::Url;
+
+use crate::csv::{self, CsvRow};
+use crate::transfer::{ImportedRow, LinkRecord};
+
+/// Link shorteners whose exports can be imported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    /// SQL dump of the `yourls_url` table, or its contents in CSV
+    Yourls,
+    /// CSV export of the Shlink web client
+    Shlink,
+    /// JSON returned by the links endpoint of the Kutt API
+    Kutt,
+    /// CSV export of the Bitly web app
+    Bitly,
+}
+
+impl ImportSource {
+    pub const ALL: [ImportSource; 4] = [
+        ImportSource::Yourls,
+        ImportSource::Shlink,
+        ImportSource::Kutt,
+        ImportSource::Bitly,
+    ];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|source| source.name() == name)
+    }
+
+    /// Converts a file exported from the source to link records.
+    ///
+    /// An error is returned if the file as a whole cannot be read; the
+    /// records that cannot be mapped to links are reported in
+    /// [`ImportedRow::record`] instead.
+    pub fn parse(self, data: &str) -> Result<Vec<ImportedRow>, String> {
+        match self {
+            ImportSource::Yourls => yourls::parse(data),
+            ImportSource::Shlink => shlink::parse(data),
+            ImportSource::Kutt => kutt::parse(data),
+            ImportSource::Bitly => bitly::parse(data),
+        }
+    }
+}
+
+impl EnumName for ImportSource {
+    fn name(&self) -> &'static str {
+        match self {
+            ImportSource::Yourls => "yourls",
+            ImportSource::Shlink => "shlink",
+            ImportSource::Kutt => "kutt",
+            ImportSource::Bitly => "bitly",
+        }
+    }
+}
+
+/// Returns the index of the first of the `names` columns found in `header`.
+fn find_column(header: &CsvRow, names: &[&str]) -> Option<usize> {
+    names
+        .iter()
+        .find_map(|name| csv::column_index(header, name))
+}
+
+/// Returns the field of `row` at `index`, unless it is missing or blank.
+fn field(row: &CsvRow, index: Option<usize>) -> Option<&str> {
+    index
+        .map(|index| row.get(index).trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Builds the record of a link from the fields of an exported one.
+fn link_record(
+    slug: Option<String>,
+    url: Option<&str>,
+    created_at: Option<&str>,
+    visits: Option<&str>,
+) -> Result<LinkRecord, String> {
+    let slug = slug.ok_or("Missing slug")?;
+    let url = url.ok_or("Missing target URL")?;
+    let created_at = created_at
+        .map(|value| parse_date(value).ok_or_else(|| format!("Invalid creation date: {value}")))
+        .transpose()?;
+    let visits = visits.map(parse_count).transpose()?.unwrap_or(0);
+
+    Ok(LinkRecord {
+        slug,
+        url: url.to_owned(),
+        created_at,
+        visits,
+        ..LinkRecord::default()
+    })
+}
+
+/// Parses a date in one of the formats used by the link shorteners; the
+/// dates without a time zone are assumed to be in UTC.
+#[must_use]
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    const ZONED_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z"];
+    const NAIVE_FORMATS: [&str; 5] = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+    ];
+    const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.to_utc());
+    }
+    if let Some(date) = ZONED_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(value, format).ok())
+    {
+        return Some(date.to_utc());
+    }
+    if let Some(date) = NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    {
+        return Some(date.and_utc());
+    }
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// Parses a number of visits, which may contain thousands separators.
+fn parse_count(value: &str) -> Result<i32, String> {
+    value
+        .trim()
+        .replace([',', '_', ' '], "")
+        .parse()
+        .map_err(|_| format!("Invalid number of visits: {value}"))
+}
+
+/// Returns the slug of a short URL, e.g. `abc` for `https://bit.ly/abc`; the
+/// scheme can be omitted.
+#[must_use]
+pub fn slug_from_short_url(short_url: &str) -> Option<String> {
+    let short_url = short_url.trim();
+    let url = if short_url.contains("://") {
+        Url::parse(short_url)
+    } else {
+        Url::parse(&format!("https://{short_url}"))
+    }
+    .ok()?;
+
+    let slug = url.path().trim_matches('/');
+    if slug.is_empty() {
+        return None;
+    }
+    urlencoding::decode(slug).ok().map(|slug| slug.into_owned())
+}
@@ -0,0 +1,40 @@
+//! Bitly, exported to CSV from its web app.
+
+use crate::csv;
+use crate::importers::{field, find_column, link_record, slug_from_short_url};
+use crate::transfer::ImportedRow;
+
+const BITLINK_COLUMNS: [&str; 4] = ["bitlink", "link", "short link", "short_url"];
+const LONG_URL_COLUMNS: [&str; 4] = ["long_url", "long url", "destination", "original url"];
+const CREATED_AT_COLUMNS: [&str; 4] = ["created_at", "created", "date created", "creation date"];
+const CLICKS_COLUMNS: [&str; 4] = ["clicks", "total clicks", "total_clicks", "engagements"];
+
+pub(super) fn parse(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let rows = csv::parse(data).map_err(|e| e.to_string())?;
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let bitlink = find_column(header, &BITLINK_COLUMNS).ok_or("The file has no bitlink column")?;
+    let long_url =
+        find_column(header, &LONG_URL_COLUMNS).ok_or("The file has no long_url column")?;
+    let created_at = find_column(header, &CREATED_AT_COLUMNS);
+    let clicks = find_column(header, &CLICKS_COLUMNS);
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            // Bitlinks are exported without the scheme, e.g. `bit.ly/abc`
+            let slug = field(row, Some(bitlink)).and_then(slug_from_short_url);
+            ImportedRow {
+                line: row.line,
+                record: link_record(
+                    slug,
+                    field(row, Some(long_url)),
+                    field(row, created_at),
+                    field(row, clicks),
+                ),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,91 @@
+//! Kutt, exported as the JSON returned by the links endpoint of its API.
+
+use serde::Deserialize;
+
+use crate::importers::{link_record, parse_date};
+use crate::transfer::{ImportedRow, LinkRecord};
+
+/// A link, as returned by the Kutt API.
+#[derive(Debug, Deserialize)]
+struct KuttLink {
+    address: Option<String>,
+    target: Option<String>,
+    created_at: Option<String>,
+    visit_count: Option<i64>,
+    #[serde(default)]
+    password: bool,
+    #[serde(default)]
+    banned: bool,
+    expire_in: Option<String>,
+}
+
+/// A page of links, as returned by the Kutt API.
+#[derive(Debug, Deserialize)]
+struct KuttLinks {
+    data: Vec<serde_json::Value>,
+}
+
+/// Accepts the response of the API, a bare array of links, or one link per
+/// line.
+pub(super) fn parse(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let data = data.trim_start_matches('\u{feff}');
+    let items = match serde_json::from_str::<serde_json::Value>(data) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(value @ serde_json::Value::Object(_)) if value.get("data").is_some() => {
+            serde_json::from_value::<KuttLinks>(value)
+                .map_err(|e| e.to_string())?
+                .data
+        }
+        _ => {
+            return Ok(data
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| ImportedRow {
+                    line: i as u64 + 1,
+                    record: serde_json::from_str(line)
+                        .map_err(|e| e.to_string())
+                        .and_then(to_record),
+                })
+                .collect());
+        }
+    };
+
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| ImportedRow {
+            line: i as u64 + 1,
+            record: serde_json::from_value(item)
+                .map_err(|e| e.to_string())
+                .and_then(to_record),
+        })
+        .collect())
+}
+
+fn to_record(link: KuttLink) -> Result<LinkRecord, String> {
+    if link.banned {
+        return Err("The link is banned".to_owned());
+    }
+    if link.password {
+        return Err("The link is protected with a password, which Kutt does not export".to_owned());
+    }
+
+    let expires_at = link
+        .expire_in
+        .as_deref()
+        .map(|value| parse_date(value).ok_or_else(|| format!("Invalid expiry date: {value}")))
+        .transpose()?;
+    let visits = link.visit_count.map(|count| count.to_string());
+    let record = link_record(
+        link.address,
+        link.target.as_deref(),
+        link.created_at.as_deref(),
+        visits.as_deref(),
+    )?;
+
+    Ok(LinkRecord {
+        expires_at,
+        ..record
+    })
+}
@@ -0,0 +1,46 @@
+//! Shlink, exported to CSV from its web client.
+
+use crate::csv;
+use crate::importers::{field, find_column, link_record, slug_from_short_url};
+use crate::transfer::ImportedRow;
+
+const SHORT_CODE_COLUMNS: [&str; 3] = ["shortCode", "short_code", "short code"];
+const SHORT_URL_COLUMNS: [&str; 3] = ["shortUrl", "short_url", "short url"];
+const LONG_URL_COLUMNS: [&str; 3] = ["longUrl", "long_url", "long url"];
+const CREATED_AT_COLUMNS: [&str; 3] = ["createdAt", "dateCreated", "created_at"];
+const VISITS_COLUMNS: [&str; 3] = ["visits", "visitsCount", "visits_count"];
+
+pub(super) fn parse(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let rows = csv::parse(data).map_err(|e| e.to_string())?;
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let short_code = find_column(header, &SHORT_CODE_COLUMNS);
+    let short_url = find_column(header, &SHORT_URL_COLUMNS);
+    if short_code.is_none() && short_url.is_none() {
+        return Err("The file has neither a shortCode nor a shortUrl column".to_owned());
+    }
+    let long_url =
+        find_column(header, &LONG_URL_COLUMNS).ok_or("The file has no longUrl column")?;
+    let created_at = find_column(header, &CREATED_AT_COLUMNS);
+    let visits = find_column(header, &VISITS_COLUMNS);
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let slug = field(row, short_code)
+                .map(ToOwned::to_owned)
+                .or_else(|| field(row, short_url).and_then(slug_from_short_url));
+            ImportedRow {
+                line: row.line,
+                record: link_record(
+                    slug,
+                    field(row, Some(long_url)),
+                    field(row, created_at),
+                    field(row, visits),
+                ),
+            }
+        })
+        .collect())
+}
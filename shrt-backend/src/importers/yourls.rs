@@ -0,0 +1,345 @@
+//! YOURLS, exported as an SQL dump of its `yourls_url` table, or as the
+//! contents of the table in CSV.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::csv;
+use crate::importers::{field, link_record};
+use crate::transfer::{ImportedRow, LinkRecord};
+
+/// The columns of the `yourls_url` table, in the order they are created in.
+const COLUMNS: [&str; 6] = ["keyword", "url", "title", "timestamp", "ip", "clicks"];
+const KEYWORD: usize = 0;
+const URL: usize = 1;
+const TIMESTAMP: usize = 3;
+const CLICKS: usize = 5;
+
+pub(super) fn parse(data: &str) -> Result<Vec<ImportedRow>, String> {
+    if data.to_ascii_uppercase().contains("INSERT INTO") {
+        parse_sql(data)
+    } else {
+        parse_csv(data)
+    }
+}
+
+fn parse_csv(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let rows = csv::parse(data).map_err(|e| e.to_string())?;
+    let Some(first_row) = rows.first() else {
+        return Ok(Vec::new());
+    };
+
+    // The table dumps made with e.g. phpMyAdmin have no header
+    let (columns, rows) = if csv::column_index(first_row, "keyword").is_some() {
+        (
+            COLUMNS.map(|column| csv::column_index(first_row, column)),
+            &rows[1..],
+        )
+    } else {
+        (
+            COLUMNS.map(|column| COLUMNS.iter().position(|c| *c == column)),
+            &rows[..],
+        )
+    };
+
+    Ok(rows
+        .iter()
+        .map(|row| ImportedRow {
+            line: row.line,
+            record: to_record(|i| field(row, columns[i])),
+        })
+        .collect())
+}
+
+fn to_record<'a>(field: impl Fn(usize) -> Option<&'a str>) -> Result<LinkRecord, String> {
+    // MySQL's placeholder for a missing date
+    let timestamp = field(TIMESTAMP).filter(|timestamp| !timestamp.starts_with("0000-00-00"));
+
+    link_record(
+        field(KEYWORD).map(ToOwned::to_owned),
+        field(URL),
+        timestamp,
+        field(CLICKS),
+    )
+}
+
+fn parse_sql(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let tokens = tokenize(data)?;
+    let mut rows = Vec::new();
+    let mut tokens = tokens.iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        if !token.is_keyword("INSERT") {
+            continue;
+        }
+        if tokens
+            .peek()
+            .is_some_and(|token| token.is_keyword("IGNORE"))
+        {
+            tokens.next();
+        }
+        if !tokens.next().is_some_and(|token| token.is_keyword("INTO")) {
+            continue;
+        }
+
+        // The table name may be qualified with the database name
+        let mut table = None;
+        while let Some(Token {
+            kind: TokenKind::Word(name) | TokenKind::Identifier(name),
+            ..
+        }) = tokens.peek()
+        {
+            if name.eq_ignore_ascii_case("VALUES") {
+                break;
+            }
+            table = Some(name.clone());
+            tokens.next();
+            if tokens.peek().is_some_and(|token| token.is_symbol('.')) {
+                tokens.next();
+            } else {
+                break;
+            }
+        }
+        let Some(table) = table else {
+            continue;
+        };
+        let is_url_table =
+            table.eq_ignore_ascii_case("url") || table.to_lowercase().ends_with("_url");
+
+        let mut columns = COLUMNS.map(|column| COLUMNS.iter().position(|c| *c == column));
+        if tokens.peek().is_some_and(|token| token.is_symbol('(')) {
+            tokens.next();
+            let names = parse_tuple(&mut tokens)?;
+            columns = COLUMNS.map(|column| {
+                names.iter().position(|name| {
+                    name.as_deref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(column))
+                })
+            });
+        }
+        if !tokens
+            .next()
+            .is_some_and(|token| token.is_keyword("VALUES"))
+        {
+            continue;
+        }
+
+        while let Some(token) = tokens.next() {
+            if !token.is_symbol('(') {
+                break;
+            }
+            let line = token.line;
+            let values = parse_tuple(&mut tokens)?;
+            if is_url_table {
+                let field = |i: usize| {
+                    columns[i]
+                        .and_then(|index| values.get(index))
+                        .and_then(|value| value.as_deref())
+                        .map(str::trim)
+                        .filter(|value| !value.is_empty())
+                };
+                rows.push(ImportedRow {
+                    line,
+                    record: to_record(field),
+                });
+            }
+
+            if !tokens.peek().is_some_and(|token| token.is_symbol(',')) {
+                break;
+            }
+            tokens.next();
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Reads the values up to the closing parenthesis; `NULL`s are returned as
+/// `None`.
+fn parse_tuple<'a>(
+    tokens: &mut Peekable<impl Iterator<Item = &'a Token>>,
+) -> Result<Vec<Option<String>>, String> {
+    let mut values = Vec::new();
+    let mut negative = false;
+    for token in tokens.by_ref() {
+        match &token.kind {
+            TokenKind::Symbol(')') => return Ok(values),
+            TokenKind::Symbol(',') => {}
+            TokenKind::Symbol('-') => negative = true,
+            TokenKind::Word(word) if word.eq_ignore_ascii_case("NULL") => values.push(None),
+            TokenKind::Word(value) if negative => {
+                values.push(Some(format!("-{value}")));
+                negative = false;
+            }
+            TokenKind::Word(value) | TokenKind::Identifier(value) | TokenKind::String(value) => {
+                values.push(Some(value.clone()));
+            }
+            TokenKind::Symbol(symbol) => {
+                return Err(format!(
+                    "Unexpected {symbol} in the SQL file at line {}",
+                    token.line
+                ));
+            }
+        }
+    }
+
+    Err("The SQL file ends in the middle of a statement".to_owned())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    kind: TokenKind,
+    line: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    /// A keyword, a number or an unquoted identifier
+    Word(String),
+    /// An identifier quoted with backticks or double quotes
+    Identifier(String),
+    /// A string literal
+    String(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol(symbol)
+    }
+}
+
+/// Splits an SQL dump into tokens, skipping the comments.
+fn tokenize(data: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = data.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        let start_line = line;
+        let kind = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '#' => {
+                skip_line(&mut chars, &mut line);
+                continue;
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                skip_line(&mut chars, &mut line);
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => return Err(unterminated("comment", start_line)),
+                    }
+                }
+                continue;
+            }
+            '\'' => TokenKind::String(read_quoted(&mut chars, '\'', &mut line, true)?),
+            '`' | '"' => TokenKind::Identifier(read_quoted(&mut chars, c, &mut line, false)?),
+            c if c.is_alphanumeric() || c == '_' || c == '.' && is_digit(chars.peek()) => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric()
+                        || c == '_'
+                        || c == '$'
+                        || (c == '.' && is_digit_word(&word))
+                    {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                TokenKind::Word(word)
+            }
+            c => TokenKind::Symbol(c),
+        };
+        tokens.push(Token {
+            kind,
+            line: start_line,
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn is_digit(c: Option<&char>) -> bool {
+    c.is_some_and(char::is_ascii_digit)
+}
+
+fn is_digit_word(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit())
+}
+
+fn skip_line(chars: &mut Peekable<Chars<'_>>, line: &mut u64) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            *line += 1;
+            break;
+        }
+    }
+}
+
+/// Reads a quoted string or identifier, after the opening `quote`.
+///
+/// The quote can be escaped by doubling it, and, in the strings, also with a
+/// backslash, as in MySQL.
+fn read_quoted(
+    chars: &mut Peekable<Chars<'_>>,
+    quote: char,
+    line: &mut u64,
+    backslash_escapes: bool,
+) -> Result<String, String> {
+    let start_line = *line;
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == quote => {
+                if chars.peek() == Some(&quote) {
+                    chars.next();
+                    value.push(quote);
+                } else {
+                    return Ok(value);
+                }
+            }
+            '\\' if backslash_escapes => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('0') => value.push('\0'),
+                Some(c) => value.push(c),
+                None => break,
+            },
+            c => {
+                if c == '\n' {
+                    *line += 1;
+                }
+                value.push(c);
+            }
+        }
+    }
+
+    Err(unterminated("quoted string", start_line))
+}
+
+fn unterminated(what: &str, line: u64) -> String {
+    format!("The {what} starting at line {line} of the SQL file is never closed")
+}
@@ -1,6 +1,6 @@
 use cot::admin::AdminApp;
 use cot::auth::db::DatabaseUserApp;
-use cot::cli::CliMetadata;
+use cot::cli::{Cli, CliMetadata};
 use cot::db::migrations::SyncDynMigration;
 use cot::middleware::{AuthMiddleware, SessionMiddleware};
 use cot::openapi::swagger_ui::SwaggerUi;
@@ -9,9 +9,31 @@ use cot::router::{Route, Router};
 use cot::static_files::StaticFilesMiddleware;
 use cot::{App, AppBuilder, Project};
 
+use crate::cli::ImportLinksTask;
+use crate::middleware::{ApiTokenMiddleware, LinkSubpathMiddleware, RateLimitMiddleware};
+
 pub mod api;
+pub mod canonical_url;
+pub mod cli;
+pub mod csv;
+pub mod extractors;
+pub mod importers;
+pub mod link_cursor;
+pub mod link_search;
+pub mod metadata;
+pub mod middleware;
 pub mod migrations;
 pub mod models;
+pub mod pages;
+pub mod prefix_links;
+pub mod qr_codes;
+pub mod query_passthrough;
+pub mod rate_limit;
+pub mod slug_generator;
+pub mod stats;
+pub mod tags;
+pub mod transfer;
+pub mod url_policy;
 
 pub struct LinkApp;
 
@@ -37,18 +59,63 @@ impl App for LinkApp {
             ),
             Route::with_api_handler(
                 "/links/{slug}/go",
-                ApiMethodRouter::new().get(NoApi(redirect_to_link)),
+                ApiMethodRouter::new()
+                    .get(NoApi(redirect_to_link))
+                    .post(NoApi(unlock_link)),
+            ),
+            Route::with_api_handler(
+                "/links/{slug}/preview",
+                ApiMethodRouter::new().get(NoApi(preview_link)),
+            ),
+            Route::with_api_handler(
+                "/links/{slug}/qr",
+                ApiMethodRouter::new().get(NoApi(get_link_qr_code)),
+            ),
+            Route::with_api_handler(
+                "/links/{slug}/stats",
+                ApiMethodRouter::new().get(get_link_stats),
+            ),
+            Route::with_api_handler(
+                "/links/batch",
+                ApiMethodRouter::new().post(create_links_batch),
+            ),
+            Route::with_api_handler(
+                "/links/batch-delete",
+                ApiMethodRouter::new().post(remove_links_batch),
             ),
+            Route::with_api_handler(
+                "/links/batch-get",
+                ApiMethodRouter::new().post(get_links_batch),
+            ),
+            Route::with_api_handler(
+                "/links/cursor",
+                ApiMethodRouter::new().get(get_links_cursor),
+            ),
+            Route::with_api_handler(
+                "/links/export",
+                ApiMethodRouter::new().get(NoApi(export_links)),
+            ),
+            Route::with_api_handler("/links/import", ApiMethodRouter::new().post(import_links)),
             Route::with_api_handler(
                 "/links/{slug}",
                 ApiMethodRouter::new()
                     .get(get_link)
+                    .patch(update_link)
                     .delete(NoApi(remove_link)),
             ),
             Route::with_api_handler(
                 "/links",
                 ApiMethodRouter::new().get(get_links).post(create_link),
             ),
+            Route::with_api_handler("/tags", ApiMethodRouter::new().get(get_tags)),
+            Route::with_api_handler(
+                "/tokens/{id}",
+                ApiMethodRouter::new().delete(NoApi(revoke_token)),
+            ),
+            Route::with_api_handler(
+                "/tokens",
+                ApiMethodRouter::new().get(get_tokens).post(create_token),
+            ),
         ])
     }
 }
@@ -60,11 +127,18 @@ impl Project for ShrtProject {
         cot::cli::metadata!()
     }
 
+    fn register_tasks(&self, cli: &mut Cli) {
+        cli.add_task(ImportLinksTask);
+    }
+
     fn middlewares(&self, handler: RootHandlerBuilder, context: &MiddlewareContext) -> RootHandler {
         handler
             .middleware(StaticFilesMiddleware::from_context(context))
+            .middleware(RateLimitMiddleware::new())
+            .middleware(ApiTokenMiddleware::new())
             .middleware(AuthMiddleware::new())
             .middleware(SessionMiddleware::from_context(context))
+            .middleware(LinkSubpathMiddleware::new())
             .build()
     }
 
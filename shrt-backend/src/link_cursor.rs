@@ -0,0 +1,58 @@
+//! Keyset pagination of the links, ordered by their creation time.
+//!
+//! A page holds the links following the cursor, so the cost of a page does
+//! not depend on how far into the list it is, and the pages do not shift
+//! when links are added.
+
+use chrono::{DateTime, Utc};
+use cot::db::Auto;
+
+use crate::models::Link;
+
+/// Position in the list of links sorted by creation time; the links created
+/// at the same time are sorted by ID.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LinkCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i32,
+}
+
+impl LinkCursor {
+    /// Returns the position of `link`, unless it has not been saved yet.
+    #[must_use]
+    pub fn of(link: &Link) -> Option<Self> {
+        match link.id {
+            Auto::Fixed(id) => Some(Self {
+                created_at: link.created_at,
+                id,
+            }),
+            Auto::Auto => None,
+        }
+    }
+
+    /// Encodes the cursor as an opaque string.
+    #[must_use]
+    pub fn encode(self) -> String {
+        format!(
+            "{:016x}{:08x}",
+            self.created_at.timestamp_micros().cast_unsigned(),
+            self.id.cast_unsigned()
+        )
+    }
+
+    /// Decodes a cursor returned by [`Self::encode`].
+    #[must_use]
+    pub fn decode(cursor: &str) -> Option<Self> {
+        if cursor.len() != 24 || !cursor.is_ascii() {
+            return None;
+        }
+        let (created_at, id) = cursor.split_at(16);
+        let created_at = u64::from_str_radix(created_at, 16).ok()?.cast_signed();
+        let id = u32::from_str_radix(id, 16).ok()?.cast_signed();
+
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(created_at)?,
+            id,
+        })
+    }
+}
@@ -0,0 +1,278 @@
+//! Searching, filtering and sorting of the listed links.
+//!
+//! The query builder supports neither substring matching nor ordering, so
+//! the listed links are selected with raw SQL, letting the database filter,
+//! sort and page them, either by page number or with a [`LinkCursor`].
+
+use chrono::{DateTime, Utc};
+use cot::db::ToDbValue;
+use shrt_common::links::{LinkSortField, SortOrder};
+
+use crate::link_cursor::LinkCursor;
+use crate::models::LINK_COLUMNS;
+
+/// Raw SQL query with the values of its `$n` placeholders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlQuery {
+    pub sql: String,
+    pub values: Vec<SqlValue>,
+}
+
+/// A value bound to a placeholder of a [`SqlQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlValue {
+    Int(i64),
+    Text(String),
+    Time(DateTime<Utc>),
+}
+
+impl SqlQuery {
+    /// Adds `value` to the values and returns its placeholder.
+    fn bind(&mut self, value: SqlValue) -> String {
+        self.values.push(value);
+        format!("${}", self.values.len())
+    }
+
+    /// Returns the values to bind to the placeholders, in their order.
+    #[must_use]
+    pub fn db_values(&self) -> Vec<&dyn ToDbValue> {
+        self.values
+            .iter()
+            .map(|value| match value {
+                SqlValue::Int(value) => value as &dyn ToDbValue,
+                SqlValue::Text(value) => value,
+                SqlValue::Time(value) => value,
+            })
+            .collect()
+    }
+}
+
+/// The links whose target URLs can be searched, so that the URLs of the
+/// password protected links cannot be guessed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlAccess {
+    /// All the links can be searched, e.g. by the admins
+    pub all: bool,
+    /// The links of this user can be searched, if set
+    pub owner_id: Option<i64>,
+    /// IDs of the password protected links unlocked in the session
+    pub unlocked_link_ids: Vec<i32>,
+}
+
+impl UrlAccess {
+    fn condition(&self, query: &mut SqlQuery) -> String {
+        if self.all {
+            return "1 = 1".to_owned();
+        }
+
+        let mut alternatives = vec!["password_hash IS NULL".to_owned()];
+        if let Some(owner_id) = self.owner_id {
+            alternatives.push(format!(
+                "owner_id = {}",
+                query.bind(SqlValue::Int(owner_id))
+            ));
+        }
+        if !self.unlocked_link_ids.is_empty() {
+            let placeholders: Vec<String> = self
+                .unlocked_link_ids
+                .iter()
+                .map(|&id| query.bind(SqlValue::Int(id.into())))
+                .collect();
+            alternatives.push(format!("id IN ({})", placeholders.join(", ")));
+        }
+        format!("({})", alternatives.join(" OR "))
+    }
+}
+
+/// Criteria the listed links have to meet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkFilter {
+    /// Text the slug or the target URL has to contain, ignoring case
+    pub text: Option<String>,
+    /// Only list the links owned by this user, if set
+    pub owner_id: Option<i64>,
+    /// Start of the creation time range (inclusive)
+    pub created_from: Option<DateTime<Utc>>,
+    /// End of the creation time range (exclusive)
+    pub created_to: Option<DateTime<Utc>>,
+    pub min_visits: Option<i32>,
+    /// Name of the tag the links have to have, ignoring case
+    pub tag: Option<String>,
+    /// The links whose target URLs are searched for the text
+    pub url_access: UrlAccess,
+}
+
+impl LinkFilter {
+    /// Returns the query for the number of the links meeting the criteria.
+    #[must_use]
+    pub fn count_query(&self) -> SqlQuery {
+        let mut query = SqlQuery::default();
+        let conditions = self.conditions(&mut query);
+        query.sql = format!(
+            "SELECT COUNT(*) FROM shrt_backend__link{}",
+            where_clause(&conditions)
+        );
+        query
+    }
+
+    /// Returns the query for up to `limit` links meeting the criteria,
+    /// skipping the first `offset` of them.
+    ///
+    /// The links are sorted by `sort` in `order`, the ones with equal values
+    /// by slug, or by ID if `sort` is not set.
+    #[must_use]
+    pub fn page_query(
+        &self,
+        sort: Option<LinkSortField>,
+        order: SortOrder,
+        offset: u64,
+        limit: u64,
+    ) -> SqlQuery {
+        let mut query = SqlQuery::default();
+        let conditions = self.conditions(&mut query);
+        let order_by = match sort {
+            // The slugs are unique, so they need no tiebreaker
+            Some(LinkSortField::Slug) => format!("slug {}", direction(order)),
+            Some(sort) => {
+                let direction = direction(order);
+                format!("{} {direction}, slug {direction}", sort_column(sort))
+            }
+            None => "id".to_owned(),
+        };
+        let limit = query.bind(SqlValue::Int(to_i64(limit)));
+        let offset = query.bind(SqlValue::Int(to_i64(offset)));
+        query.sql = format!(
+            "SELECT {} FROM shrt_backend__link{} ORDER BY {order_by} LIMIT {limit} OFFSET {offset}",
+            LINK_COLUMNS.join(", "),
+            where_clause(&conditions),
+        );
+        query
+    }
+
+    /// Returns the query for up to `limit` links meeting the criteria that
+    /// follow `cursor` in the `order` of creation, or the first ones if
+    /// `cursor` is not set.
+    ///
+    /// The links created at the same time are sorted by ID.
+    #[must_use]
+    pub fn cursor_query(
+        &self,
+        cursor: Option<LinkCursor>,
+        order: SortOrder,
+        limit: u64,
+    ) -> SqlQuery {
+        let mut query = SqlQuery::default();
+        let mut conditions = self.conditions(&mut query);
+        let direction = direction(order);
+        if let Some(cursor) = cursor {
+            let comparison = match order {
+                SortOrder::Asc => ">",
+                SortOrder::Desc => "<",
+            };
+            let created_at = query.bind(SqlValue::Time(cursor.created_at));
+            let id = query.bind(SqlValue::Int(cursor.id.into()));
+            conditions.push(format!(
+                "(created_at, id) {comparison} ({created_at}, {id})"
+            ));
+        }
+        let limit = query.bind(SqlValue::Int(to_i64(limit)));
+        query.sql = format!(
+            "SELECT {} FROM shrt_backend__link{} \
+             ORDER BY created_at {direction}, id {direction} LIMIT {limit}",
+            LINK_COLUMNS.join(", "),
+            where_clause(&conditions),
+        );
+        query
+    }
+
+    fn conditions(&self, query: &mut SqlQuery) -> Vec<String> {
+        let mut conditions = Vec::new();
+        if let Some(owner_id) = self.owner_id {
+            conditions.push(format!(
+                "owner_id = {}",
+                query.bind(SqlValue::Int(owner_id))
+            ));
+        }
+        if let Some(created_from) = self.created_from {
+            conditions.push(format!(
+                "created_at >= {}",
+                query.bind(SqlValue::Time(created_from))
+            ));
+        }
+        if let Some(created_to) = self.created_to {
+            conditions.push(format!(
+                "created_at < {}",
+                query.bind(SqlValue::Time(created_to))
+            ));
+        }
+        if let Some(min_visits) = self.min_visits {
+            conditions.push(format!(
+                "visits >= {}",
+                query.bind(SqlValue::Int(min_visits.into()))
+            ));
+        }
+        if let Some(tag) = &self.tag {
+            conditions.push(format!(
+                "id IN (SELECT link_tag.link FROM shrt_backend__link_tag AS link_tag \
+                 JOIN shrt_backend__tag AS tag ON tag.id = link_tag.tag \
+                 WHERE tag.name = {})",
+                query.bind(SqlValue::Text(tag.trim().to_lowercase()))
+            ));
+        }
+        if let Some(text) = non_empty(self.text.as_deref()) {
+            let pattern = query.bind(SqlValue::Text(like_pattern(text)));
+            let url_access = self.url_access.condition(query);
+            conditions.push(format!(
+                "(LOWER(slug) LIKE {pattern} ESCAPE '\\' \
+                 OR (LOWER(url) LIKE {pattern} ESCAPE '\\' AND {url_access}))"
+            ));
+        }
+        conditions
+    }
+}
+
+/// Returns the `LIKE` pattern matching the values containing `text`, ignoring
+/// case.
+#[must_use]
+pub fn like_pattern(text: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in text.to_lowercase().chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn sort_column(sort: LinkSortField) -> &'static str {
+    match sort {
+        LinkSortField::CreatedAt => "created_at",
+        LinkSortField::Visits => "visits",
+        LinkSortField::Slug => "slug",
+    }
+}
+
+fn direction(order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    }
+}
+
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+fn non_empty(text: Option<&str>) -> Option<&str> {
+    text.map(str::trim).filter(|text| !text.is_empty())
+}
@@ -0,0 +1,453 @@
+//! Fetching the titles and favicons of the links' target pages.
+
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use cot::db::Database;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode, redirect};
+use shrt_common::config::MetadataFetcherConfig;
+use shrt_common::links::MAX_TITLE_LENGTH;
+use url::{Host, Url};
+
+/// Maximum number of bytes of a page read to find its metadata.
+const MAX_PAGE_SIZE: usize = 512 * 1024;
+/// Maximum number of redirects followed when fetching a page.
+const MAX_REDIRECTS: usize = 5;
+const USER_AGENT: &str = concat!("shrt/", env!("CARGO_PKG_VERSION"));
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Metadata of a web page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    /// Absolute URL of the icon of the page
+    pub favicon_url: Option<String>,
+}
+
+/// Reasons for the metadata of a page not to be fetched.
+#[derive(Debug)]
+pub enum MetadataError {
+    /// The URL could not be parsed
+    InvalidUrl(url::ParseError),
+    /// The host is a loopback or private network address
+    PrivateAddress(String),
+    /// The page could not be retrieved
+    Request(reqwest::Error),
+    /// The server responded with an error
+    Status(StatusCode),
+    /// The response is not an HTML page
+    NotHtml,
+}
+
+impl Display for MetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::InvalidUrl(e) => write!(f, "URL is invalid: {e}"),
+            MetadataError::PrivateAddress(host) => {
+                write!(f, "{host} is a private network address")
+            }
+            MetadataError::Request(e) => write!(f, "could not fetch the page: {e}"),
+            MetadataError::Status(status) => write!(f, "server responded with {status}"),
+            MetadataError::NotHtml => write!(f, "response is not an HTML page"),
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {}
+
+impl From<reqwest::Error> for MetadataError {
+    fn from(e: reqwest::Error) -> Self {
+        MetadataError::Request(e)
+    }
+}
+
+/// Fetches the metadata of web pages.
+#[derive(Debug, Clone)]
+pub struct MetadataFetcher {
+    client: Client,
+    allow_private_addresses: bool,
+}
+
+impl MetadataFetcher {
+    pub fn new(config: &MetadataFetcherConfig) -> Result<Self, MetadataError> {
+        let allow_private_addresses = config.allow_private_addresses;
+        let redirect_policy = redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if !allow_private_addresses && has_private_ip_host(attempt.url()) {
+                attempt.error("redirect to a private network address")
+            } else {
+                attempt.follow()
+            }
+        });
+
+        let mut builder = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(config.timeout_secs.into()))
+            .redirect(redirect_policy);
+        if !allow_private_addresses {
+            // A proxy would resolve the host names itself, bypassing the
+            // check of the addresses
+            builder = builder.no_proxy().dns_resolver(PublicAddressResolver);
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            allow_private_addresses,
+        })
+    }
+
+    /// Fetches the page at `url` and returns its metadata.
+    pub async fn fetch(&self, url: &str) -> Result<PageMetadata, MetadataError> {
+        let url = Url::parse(url).map_err(MetadataError::InvalidUrl)?;
+        if !self.allow_private_addresses && has_private_ip_host(&url) {
+            return Err(MetadataError::PrivateAddress(
+                url.host_str().unwrap_or_default().to_owned(),
+            ));
+        }
+
+        let mut response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(MetadataError::Status(response.status()));
+        }
+        let is_html = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| {
+                let mime_type = content_type.split(';').next().unwrap_or_default().trim();
+                mime_type.eq_ignore_ascii_case("text/html")
+                    || mime_type.eq_ignore_ascii_case("application/xhtml+xml")
+            });
+        if !is_html {
+            return Err(MetadataError::NotHtml);
+        }
+
+        // The metadata is in the head of the page, so there is no need to
+        // read the whole of a large one
+        let page_url = response.url().clone();
+        let mut body = Vec::new();
+        while body.len() < MAX_PAGE_SIZE
+            && let Some(chunk) = response.chunk().await?
+        {
+            body.extend_from_slice(&chunk);
+        }
+        body.truncate(MAX_PAGE_SIZE);
+
+        // Non-UTF-8 pages are rare enough not to detect their encoding; the
+        // ASCII parts of the markup are still read correctly
+        Ok(parse_page(&String::from_utf8_lossy(&body), &page_url))
+    }
+}
+
+/// Resolves the host names, skipping the loopback and private network
+/// addresses.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_owned();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(Box::new(MetadataError::PrivateAddress(host)) as BoxError);
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn has_private_ip_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => !is_public(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => !is_public(IpAddr::V6(ip)),
+        // The domains are checked once they are resolved
+        Some(Host::Domain(_)) | None => false,
+    }
+}
+
+/// Returns whether `ip` is reachable on the public internet.
+#[must_use]
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", shared address space and reserved ranges
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local and link-local addresses
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+/// Finds the title and the icon of the HTML page served at `page_url`.
+#[must_use]
+pub fn parse_page(html: &str, page_url: &Url) -> PageMetadata {
+    // ASCII lowercase keeps the byte offsets, so they can be used to slice
+    // the original
+    let lowercase = html.to_ascii_lowercase();
+
+    let mut title = element_text(html, &lowercase, "title");
+    let mut favicon_url = None;
+    for (name, attributes) in tags(html, &lowercase) {
+        let attribute = |wanted: &str| {
+            attributes
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                .map(|(_, value)| value.as_str())
+        };
+        match name {
+            "link" if favicon_url.is_none() => {
+                let is_icon = attribute("rel").is_some_and(|rel| {
+                    rel.split_ascii_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("icon"))
+                });
+                if is_icon {
+                    favicon_url = attribute("href")
+                        .and_then(|href| page_url.join(href.trim()).ok())
+                        .filter(|url| matches!(url.scheme(), "http" | "https"))
+                        .map(String::from);
+                }
+            }
+            "meta"
+                if title.is_none()
+                    && attribute("property").is_some_and(|property| property == "og:title") =>
+            {
+                title = attribute("content").and_then(clean_text);
+            }
+            _ => {}
+        }
+    }
+
+    PageMetadata { title, favicon_url }
+}
+
+/// Returns the text of the first `name` element.
+fn element_text(html: &str, lowercase: &str, name: &str) -> Option<String> {
+    let open = format!("<{name}");
+    let mut from = 0;
+    while let Some(offset) = lowercase[from..].find(&open) {
+        let start = from + offset + open.len();
+        from = start;
+        // Skip the elements that only start with the name, e.g. `<titles>`
+        if !lowercase[start..].starts_with(['>', ' ', '\t', '\n', '\r', '/']) {
+            continue;
+        }
+        let content_start = start + lowercase[start..].find('>')? + 1;
+        let content_end = content_start + lowercase[content_start..].find(&format!("</{name}"))?;
+        return clean_text(&html[content_start..content_end]);
+    }
+
+    None
+}
+
+/// Returns the names and attributes of the `link` and `meta` tags.
+fn tags(html: &str, lowercase: &str) -> Vec<(&'static str, Vec<(String, String)>)> {
+    let mut tags = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = lowercase[from..].find('<') {
+        let start = from + offset + 1;
+        from = start;
+        let name = ["link", "meta"].into_iter().find(|name| {
+            lowercase[start..].starts_with(name)
+                && lowercase[start + name.len()..].starts_with([' ', '\t', '\n', '\r', '/'])
+        });
+        let Some(name) = name else {
+            continue;
+        };
+        let Some(length) = lowercase[start..].find('>') else {
+            break;
+        };
+        tags.push((name, attributes(&html[start + name.len()..start + length])));
+        from = start + length;
+    }
+
+    tags
+}
+
+/// Parses the attributes of a tag, with the values unescaped.
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut chars = tag.chars().peekable();
+    loop {
+        while chars
+            .next_if(|c| c.is_ascii_whitespace() || *c == '/')
+            .is_some()
+        {}
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_ascii_whitespace() && !"/=".contains(*c)) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            break;
+        }
+        while chars.next_if(char::is_ascii_whitespace).is_some() {}
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            while chars.next_if(char::is_ascii_whitespace).is_some() {}
+            match chars.next_if(|c| *c == '"' || *c == '\'') {
+                Some(quote) => {
+                    for c in chars.by_ref() {
+                        if c == quote {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                None => {
+                    while let Some(c) = chars.next_if(|c| !c.is_ascii_whitespace()) {
+                        value.push(c);
+                    }
+                }
+            }
+        }
+        attributes.push((name, unescape(&value)));
+    }
+
+    attributes
+}
+
+/// Unescapes the text, collapses the whitespace and limits its length.
+fn clean_text(text: &str) -> Option<String> {
+    let text = unescape(text);
+    let text: String = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_TITLE_LENGTH)
+        .collect();
+
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Replaces the character references with the characters; only the most
+/// common named ones are recognized.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let reference = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..=end]);
+        let c = reference.and_then(|reference| match reference {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = match reference.strip_prefix('#')? {
+                    hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16),
+                    decimal => decimal.parse(),
+                };
+                char::from_u32(code.ok()?)
+            }
+        });
+        match (c, reference) {
+            (Some(c), Some(reference)) => {
+                unescaped.push(c);
+                rest = &rest[reference.len() + 2..];
+            }
+            _ => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+
+    unescaped
+}
+
+/// Fetches the metadata of the target pages of the `links`, given by their
+/// IDs and target URLs, in the background and stores it, unless the links
+/// have been given a title or a new target in the meantime.
+pub fn fetch_in_background(
+    db: Database,
+    config: &MetadataFetcherConfig,
+    links: Vec<(i32, String)>,
+) {
+    if links.is_empty() {
+        return;
+    }
+    let fetcher = match MetadataFetcher::new(config) {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            log::error!("Could not create the metadata fetcher: {e}");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        for (id, url) in links {
+            let metadata = match fetcher.fetch(&url).await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    log::info!("Could not fetch the metadata of {url}: {e}");
+                    continue;
+                }
+            };
+            if let Err(e) = store(&db, id, &url, metadata).await {
+                log::error!("Could not store the metadata of {url}: {e}");
+            }
+        }
+    });
+}
+
+async fn store(db: &Database, id: i32, url: &str, metadata: PageMetadata) -> cot::db::Result<()> {
+    if metadata.title.is_none() && metadata.favicon_url.is_none() {
+        return Ok(());
+    }
+
+    // Only the title and the icon are written, so that the visits counted in
+    // the meantime are kept, and only if the link still has no title and the
+    // same target, and one of them has changed; the revision tells the
+    // editors about the new values
+    db.raw_with(
+        "UPDATE shrt_backend__link \
+         SET title = $3, favicon_url = $4, revision = COALESCE(revision, 0) + 1 \
+         WHERE id = $1 AND title IS NULL AND url = $2 \
+         AND ($3 IS NOT NULL OR favicon_url IS DISTINCT FROM $4)",
+        &[&id, &url.to_owned(), &metadata.title, &metadata.favicon_url],
+    )
+    .await?;
+    Ok(())
+}
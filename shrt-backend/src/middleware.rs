@@ -0,0 +1,482 @@
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use cot::StatusCode;
+use cot::http::uri::PathAndQuery;
+use cot::http::{HeaderMap, Method, Uri};
+use cot::request::extractors::FromRequestHead;
+use cot::request::{Request, RequestExt};
+use cot::response::{IntoResponse, Response};
+use futures::future::BoxFuture;
+use shrt_common::config::{AppConfig, RateLimit};
+use shrt_common::errors::ServiceError;
+use shrt_common::tokens::TokenScope;
+
+use crate::api::error;
+use crate::extractors::{CurrentUser, client_ip};
+use crate::models::ApiToken;
+use crate::prefix_links::split_link_path;
+use crate::rate_limit::{RateLimitKey, RateLimitedAction, RateLimiter};
+
+/// The API token the request has been authenticated with.
+///
+/// Inserted into the request extensions by [`ApiTokenMiddleware`].
+#[derive(Debug, Clone)]
+pub struct TokenAuth {
+    pub user_id: i64,
+    pub scope: TokenScope,
+}
+
+/// The path following the slug of a link being visited, forwarded to the
+/// target if it is a prefix link.
+///
+/// Inserted into the request extensions by [`LinkSubpathMiddleware`].
+#[derive(Debug, Clone)]
+pub struct LinkSubpath(pub String);
+
+/// The budget of the client for the action of a batch request, used up by
+/// its handler for every item with [`RateLimitBudget::acquire`].
+///
+/// Inserted into the request extensions by [`RateLimitMiddleware`]; the
+/// requests without it are not limited.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitBudget(Option<Arc<ClientBudget>>);
+
+#[derive(Debug)]
+struct ClientBudget {
+    limiter: Arc<RateLimiter>,
+    action: RateLimitedAction,
+    key: RateLimitKey,
+    limit: RateLimit,
+    /// Time after which the client can retry, set if the budget was too low
+    retry_after: Mutex<Option<Duration>>,
+}
+
+impl RateLimitBudget {
+    /// Uses up one request of the budget for each of the `items`, or none of
+    /// them if there are not enough left.
+    pub fn acquire(&self, items: usize) -> Result<(), ServiceError> {
+        let Some(budget) = &self.0 else {
+            return Ok(());
+        };
+        let limit = budget.limit;
+        if !limit.is_enabled() {
+            return Ok(());
+        }
+
+        let requests = u32::try_from(items)
+            .ok()
+            .filter(|&requests| requests <= limit.requests)
+            .ok_or_else(|| {
+                error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "Batch too large",
+                    &format!(
+                        "The rate limit allows at most {} items every {} seconds",
+                        limit.requests, limit.period_secs
+                    ),
+                )
+            })?;
+        budget
+            .limiter
+            .acquire_many(budget.action, budget.key, limit, requests, Instant::now())
+            .map_err(|retry_after| {
+                *budget
+                    .retry_after
+                    .lock()
+                    .expect("retry after lock poisoned") = Some(retry_after);
+                too_many_requests_error(retry_after)
+            })
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        let budget = self.0.as_ref()?;
+        *budget
+            .retry_after
+            .lock()
+            .expect("retry after lock poisoned")
+    }
+}
+
+/// Returns the token sent in the `Authorization: Bearer` header, if any.
+#[must_use]
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get("Authorization")?.to_str().ok()?;
+    let (scheme, token) = value.trim().split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Authenticates the requests carrying a personal API token.
+///
+/// Requests with an invalid, expired or revoked token are rejected, as are
+/// the requests modifying anything when made with a read-only token.
+/// Requests without a token are passed through unchanged.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ApiTokenMiddleware;
+
+impl ApiTokenMiddleware {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for ApiTokenMiddleware {
+    type Service = ApiTokenService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiTokenService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiTokenService<S> {
+    inner: S,
+}
+
+impl<S> tower::Service<Request> for ApiTokenService<S>
+where
+    S: tower::Service<Request, Response = Response, Error = cot::Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Error = cot::Error;
+    type Future = BoxFuture<'static, cot::Result<Response>>;
+    type Response = Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<cot::Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // Take the service that has been polled ready, leaving a fresh clone
+        // in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let Some(secret) = bearer_token(request.headers()).map(ToOwned::to_owned) else {
+                return inner.call(request).await;
+            };
+
+            let db = request.db().clone();
+            let token = ApiToken::authenticate(&db, &secret, Utc::now())
+                .await
+                .map_err(|e| {
+                    error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Database error",
+                        &e.to_string(),
+                    )
+                });
+            let token = match token {
+                Ok(Some(token)) => token,
+                Ok(None) => {
+                    return bearer_error(
+                        StatusCode::UNAUTHORIZED,
+                        "invalid_token",
+                        "Invalid token",
+                        "The API token is invalid, expired or revoked",
+                    );
+                }
+                Err(e) => return e.into_response(),
+            };
+
+            let scope = token.scope();
+            if scope == TokenScope::Read
+                && !is_read_only_request(request.method(), request.uri().path())
+            {
+                return bearer_error(
+                    StatusCode::FORBIDDEN,
+                    "insufficient_scope",
+                    "Insufficient scope",
+                    "The API token only allows reading data",
+                );
+            }
+
+            request.extensions_mut().insert(TokenAuth {
+                user_id: token.user_id,
+                scope,
+            });
+            inner.call(request).await
+        })
+    }
+}
+
+/// Returns an error response with the `WWW-Authenticate` header set, as
+/// described in RFC 6750.
+fn bearer_error(
+    status: StatusCode,
+    code: &str,
+    title: &str,
+    message: &str,
+) -> cot::Result<Response> {
+    let mut response = error(status, title, message).into_response()?;
+    let challenge = format!("Bearer error=\"{code}\"")
+        .parse()
+        .expect("challenge is a valid header value");
+    response.headers_mut().insert("WWW-Authenticate", challenge);
+    Ok(response)
+}
+
+/// Routes the visits of links with a path following the slug,
+/// `/links/{slug}/go/{subpath}`, to the link itself.
+///
+/// The router only matches a single segment for the slug, so the subpath is
+/// cut off the request URI and put into its extensions as [`LinkSubpath`].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LinkSubpathMiddleware;
+
+impl LinkSubpathMiddleware {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> tower::Layer<S> for LinkSubpathMiddleware {
+    type Service = LinkSubpathService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LinkSubpathService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkSubpathService<S> {
+    inner: S,
+}
+
+impl<S> tower::Service<Request> for LinkSubpathService<S>
+where
+    S: tower::Service<Request, Response = Response, Error = cot::Error>,
+{
+    type Error = cot::Error;
+    type Future = S::Future;
+    type Response = Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<cot::Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        if let Some((uri, subpath)) = strip_link_subpath(request.uri()) {
+            *request.uri_mut() = uri;
+            request.extensions_mut().insert(LinkSubpath(subpath));
+        }
+        self.inner.call(request)
+    }
+}
+
+/// Returns `uri` without the path following the slug of the link being
+/// visited, and that path.
+fn strip_link_subpath(uri: &Uri) -> Option<(Uri, String)> {
+    let (path, subpath) = split_link_path(uri.path())?;
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_owned(),
+    };
+
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+    let uri = Uri::from_parts(parts).ok()?;
+    Some((uri, subpath.to_owned()))
+}
+
+/// Returns whether `method` on `path` only reads data, and so can be made
+/// with a read-only API token.
+#[must_use]
+pub fn is_read_only_request(method: &Method, path: &str) -> bool {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+
+    // Lookups taking too many parameters to fit in the URL
+    *method == Method::POST && path.trim_matches('/') == "links/batch-get"
+}
+
+/// Returns the rate-limited action `method` on `path` corresponds to, if any.
+#[must_use]
+pub fn rate_limited_action(method: &Method, path: &str) -> Option<RateLimitedAction> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (&Method::POST, ["links"] | ["links", "batch" | "import"]) => {
+            Some(RateLimitedAction::CreateLink)
+        }
+        (&Method::GET, ["links", _, "exists"]) | (&Method::POST, ["links", "batch-get"]) => {
+            Some(RateLimitedAction::LinkExists)
+        }
+        (
+            &Method::GET,
+            ["links", _] | ["links", _, "preview" | "qr" | "stats"] | ["links", _, "go", ..],
+        ) => Some(RateLimitedAction::LinkNotFound),
+        (&Method::POST, ["links", _, "go", ..]) => Some(RateLimitedAction::LinkPassword),
+        _ => None,
+    }
+}
+
+/// Returns whether `method` on `path` is a batch request, counted towards the
+/// limit of its action once for every item by its handler, through
+/// [`RateLimitBudget`].
+fn is_batch_request(method: &Method, path: &str) -> bool {
+    *method == Method::POST
+        && matches!(
+            path.trim_matches('/'),
+            "links/batch" | "links/batch-get" | "links/import"
+        )
+}
+
+/// Returns whether the requests for `action` only count towards the limit
+/// once they have failed, as told by [`is_failed`].
+fn charges_failures_only(action: RateLimitedAction) -> bool {
+    matches!(
+        action,
+        RateLimitedAction::LinkNotFound | RateLimitedAction::LinkPassword
+    )
+}
+
+/// Returns whether the request for `action` has failed, judging by the
+/// `status` of the response: the link does not exist or, for the password
+/// form, the password is wrong.
+fn is_failed(action: RateLimitedAction, status: StatusCode) -> bool {
+    match action {
+        RateLimitedAction::LinkPassword => {
+            status == StatusCode::NOT_FOUND || status == StatusCode::FORBIDDEN
+        }
+        _ => status == StatusCode::NOT_FOUND,
+    }
+}
+
+/// Limits the rate of the requests that are expensive or can be used to
+/// enumerate the existing links.
+///
+/// The budgets are configured in [`AppConfig::rate_limits`]. Lookups, visits
+/// and previews of links are only counted towards the limit if the link does
+/// not exist, and the passwords of the links if they are wrong.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitMiddleware {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitMiddleware {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> tower::Layer<S> for RateLimitMiddleware {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> tower::Service<Request> for RateLimitService<S>
+where
+    S: tower::Service<Request, Response = Response, Error = cot::Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Error = cot::Error;
+    type Future = BoxFuture<'static, cot::Result<Response>>;
+    type Response = Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<cot::Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // Take the service that has been polled ready, leaving a fresh clone
+        // in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = Arc::clone(&self.limiter);
+
+        Box::pin(async move {
+            let Some(action) = rate_limited_action(request.method(), request.uri().path()) else {
+                return inner.call(request).await;
+            };
+
+            let is_batch = is_batch_request(request.method(), request.uri().path());
+            let config = AppConfig::from_project_config(request.project_config())?;
+            let limit = action.limit(&config.rate_limits);
+            let (head, body) = request.into_parts();
+            let user = CurrentUser::from_request_head(&head).await?;
+            let key = match (user.id(), client_ip(&head.headers)) {
+                (Some(user_id), _) => RateLimitKey::User(user_id),
+                (None, Some(ip)) => RateLimitKey::Ip(ip),
+                (None, None) => RateLimitKey::Unknown,
+            };
+            let mut request = Request::from_parts(head, body);
+
+            let allowed = if is_batch || charges_failures_only(action) {
+                limiter.check(action, key, limit, Instant::now())
+            } else {
+                limiter.acquire(action, key, limit, Instant::now())
+            };
+            if let Err(retry_after) = allowed {
+                return too_many_requests(retry_after);
+            }
+
+            let budget = RateLimitBudget(is_batch.then(|| {
+                Arc::new(ClientBudget {
+                    limiter: Arc::clone(&limiter),
+                    action,
+                    key,
+                    limit,
+                    retry_after: Mutex::new(None),
+                })
+            }));
+            request.extensions_mut().insert(budget.clone());
+
+            let mut response = inner.call(request).await?;
+            if charges_failures_only(action) && is_failed(action, response.status()) {
+                limiter.charge(action, key, limit, Instant::now());
+            }
+            if let Some(retry_after) = budget.retry_after() {
+                response
+                    .headers_mut()
+                    .insert("Retry-After", retry_after_secs(retry_after).into());
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Returns the whole number of seconds to wait for `retry_after` to pass.
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).max(1)
+}
+
+fn too_many_requests_error(retry_after: Duration) -> ServiceError {
+    error(
+        StatusCode::TOO_MANY_REQUESTS,
+        "Too many requests",
+        &format!(
+            "Rate limit exceeded; please retry after {} seconds",
+            retry_after_secs(retry_after)
+        ),
+    )
+}
+
+fn too_many_requests(retry_after: Duration) -> cot::Result<Response> {
+    let mut response = too_many_requests_error(retry_after).into_response()?;
+    response
+        .headers_mut()
+        .insert("Retry-After", retry_after_secs(retry_after).into());
+    Ok(response)
+}
@@ -1,3 +1,35 @@
 pub mod m_0001_initial;
+pub mod m_0002_link_expiration;
+pub mod m_0003_link_max_visits;
+pub mod m_0004_link_password;
+pub mod m_0005_visit;
+pub mod m_0006_link_owner;
+pub mod m_0007_api_token;
+pub mod m_0008_link_canonical_url;
+pub mod m_0009_link_created_at_index;
+pub mod m_0010_tag;
+pub mod m_0011_link_metadata;
+pub mod m_0012_link_redirect_status;
+pub mod m_0013_link_query_passthrough;
+pub mod m_0014_link_prefix;
+pub mod m_0015_link_revision;
+pub mod m_0016_visit_link_index;
 
-pub const MIGRATIONS: &[&::cot::db::migrations::SyncDynMigration] = &[&m_0001_initial::Migration];
+pub const MIGRATIONS: &[&::cot::db::migrations::SyncDynMigration] = &[
+    &m_0001_initial::Migration,
+    &m_0002_link_expiration::Migration,
+    &m_0003_link_max_visits::Migration,
+    &m_0004_link_password::Migration,
+    &m_0005_visit::Migration,
+    &m_0006_link_owner::Migration,
+    &m_0007_api_token::Migration,
+    &m_0008_link_canonical_url::Migration,
+    &m_0009_link_created_at_index::Migration,
+    &m_0010_tag::Migration,
+    &m_0011_link_metadata::Migration,
+    &m_0012_link_redirect_status::Migration,
+    &m_0013_link_query_passthrough::Migration,
+    &m_0014_link_prefix::Migration,
+    &m_0015_link_revision::Migration,
+    &m_0016_visit_link_index::Migration,
+];
@@ -0,0 +1,38 @@
+//! Generated by cot CLI 0.5.0 on 2026-02-12 10:41:05+00:00
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "shrt-backend";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "shrt-backend",
+            "m_0002_link_expiration",
+        )];
+    const MIGRATION_NAME: &'static str = "m_0003_link_max_visits";
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] =
+        &[::cot::db::migrations::Operation::add_field()
+            .table_name(::cot::db::Identifier::new("shrt_backend__link"))
+            .field(
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("max_visits"),
+                    <Option<i32> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<Option<i32> as ::cot::db::DatabaseField>::NULLABLE),
+            )
+            .build()];
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _Link {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i32>,
+    pub slug: String,
+    pub url: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub visits: i32,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub fallback_url: Option<String>,
+    pub max_visits: Option<i32>,
+}
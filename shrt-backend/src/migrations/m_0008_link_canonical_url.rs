@@ -0,0 +1,78 @@
+//! Generated by cot CLI 0.5.0 on 2026-03-23 17:06:12+00:00
+
+use cot::db::migrations::{MigrationContext, migration_op};
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "shrt-backend";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "shrt-backend",
+            "m_0007_api_token",
+        )];
+    const MIGRATION_NAME: &'static str = "m_0008_link_canonical_url";
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        ::cot::db::migrations::Operation::add_field()
+            .table_name(::cot::db::Identifier::new("shrt_backend__link"))
+            .field(
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("canonical_url"),
+                    <Option<String> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<Option<String> as ::cot::db::DatabaseField>::NULLABLE),
+            )
+            .build(),
+        ::cot::db::migrations::Operation::add_field()
+            .table_name(::cot::db::Identifier::new("shrt_backend__link"))
+            .field(
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("generated_slug"),
+                    <Option<bool> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .set_null(<Option<bool> as ::cot::db::DatabaseField>::NULLABLE),
+            )
+            .build(),
+        // Added by hand: the model definitions cannot describe indexes
+        ::cot::db::migrations::Operation::custom(create_canonical_url_index)
+            .backwards(drop_canonical_url_index)
+            .build(),
+    ];
+}
+
+#[migration_op]
+async fn create_canonical_url_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw(
+            "CREATE INDEX IF NOT EXISTS shrt_backend__link_canonical_url \
+             ON shrt_backend__link (canonical_url)",
+        )
+        .await?;
+    Ok(())
+}
+
+#[migration_op]
+async fn drop_canonical_url_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw("DROP INDEX IF EXISTS shrt_backend__link_canonical_url")
+        .await?;
+    Ok(())
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _Link {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i32>,
+    pub slug: String,
+    pub url: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub visits: i32,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub fallback_url: Option<String>,
+    pub max_visits: Option<i32>,
+    pub password_hash: Option<String>,
+    pub owner_id: Option<i64>,
+    pub canonical_url: Option<String>,
+    pub generated_slug: Option<bool>,
+}
@@ -0,0 +1,58 @@
+//! Written by hand in the format generated by cot CLI 0.5.0
+
+use cot::db::migrations::{MigrationContext, migration_op};
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "shrt-backend";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "shrt-backend",
+            "m_0008_link_canonical_url",
+        )];
+    const MIGRATION_NAME: &'static str = "m_0009_link_created_at_index";
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        // Added by hand: the model definitions cannot describe indexes
+        ::cot::db::migrations::Operation::custom(create_created_at_index)
+            .backwards(drop_created_at_index)
+            .build(),
+    ];
+}
+
+#[migration_op]
+async fn create_created_at_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw(
+            "CREATE INDEX IF NOT EXISTS shrt_backend__link_created_at \
+             ON shrt_backend__link (created_at)",
+        )
+        .await?;
+    Ok(())
+}
+
+#[migration_op]
+async fn drop_created_at_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw("DROP INDEX IF EXISTS shrt_backend__link_created_at")
+        .await?;
+    Ok(())
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _Link {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i32>,
+    pub slug: String,
+    pub url: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub visits: i32,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub fallback_url: Option<String>,
+    pub max_visits: Option<i32>,
+    pub password_hash: Option<String>,
+    pub owner_id: Option<i64>,
+    pub canonical_url: Option<String>,
+    pub generated_slug: Option<bool>,
+}
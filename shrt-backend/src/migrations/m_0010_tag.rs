@@ -0,0 +1,123 @@
+//! Written by hand in the format generated by cot CLI 0.5.0
+
+use cot::db::migrations::{MigrationContext, migration_op};
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "shrt-backend";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "shrt-backend",
+            "m_0009_link_created_at_index",
+        )];
+    const MIGRATION_NAME: &'static str = "m_0010_tag";
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        ::cot::db::migrations::Operation::create_model()
+            .table_name(::cot::db::Identifier::new("shrt_backend__tag"))
+            .fields(&[
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("id"),
+                    <cot::db::Auto<i32> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .auto()
+                .primary_key()
+                .set_null(<cot::db::Auto<i32> as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("name"),
+                    <String as ::cot::db::DatabaseField>::TYPE,
+                )
+                .unique()
+                .set_null(<String as ::cot::db::DatabaseField>::NULLABLE),
+            ])
+            .build(),
+        ::cot::db::migrations::Operation::create_model()
+            .table_name(::cot::db::Identifier::new("shrt_backend__link_tag"))
+            .fields(&[
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("id"),
+                    <cot::db::Auto<i64> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .auto()
+                .primary_key()
+                .set_null(<cot::db::Auto<i64> as ::cot::db::DatabaseField>::NULLABLE),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("link"),
+                    <cot::db::ForeignKey<crate::models::Link> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .foreign_key(
+                    <crate::models::Link as ::cot::db::Model>::TABLE_NAME,
+                    <crate::models::Link as ::cot::db::Model>::PRIMARY_KEY_NAME,
+                    ::cot::db::ForeignKeyOnDeletePolicy::Cascade,
+                    ::cot::db::ForeignKeyOnUpdatePolicy::Cascade,
+                )
+                .set_null(
+                    <cot::db::ForeignKey<crate::models::Link> as ::cot::db::DatabaseField>::NULLABLE,
+                ),
+                ::cot::db::migrations::Field::new(
+                    ::cot::db::Identifier::new("tag"),
+                    <cot::db::ForeignKey<crate::models::Tag> as ::cot::db::DatabaseField>::TYPE,
+                )
+                .foreign_key(
+                    <crate::models::Tag as ::cot::db::Model>::TABLE_NAME,
+                    <crate::models::Tag as ::cot::db::Model>::PRIMARY_KEY_NAME,
+                    ::cot::db::ForeignKeyOnDeletePolicy::Cascade,
+                    ::cot::db::ForeignKeyOnUpdatePolicy::Cascade,
+                )
+                .set_null(
+                    <cot::db::ForeignKey<crate::models::Tag> as ::cot::db::DatabaseField>::NULLABLE,
+                ),
+            ])
+            .build(),
+        // Added by hand: the model definitions cannot describe indexes
+        ::cot::db::migrations::Operation::custom(create_link_tag_indexes)
+            .backwards(drop_link_tag_indexes)
+            .build(),
+    ];
+}
+
+#[migration_op]
+async fn create_link_tag_indexes(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw(
+            "CREATE UNIQUE INDEX IF NOT EXISTS shrt_backend__link_tag_link_tag \
+             ON shrt_backend__link_tag (link, tag)",
+        )
+        .await?;
+    ctx.db
+        .raw(
+            "CREATE INDEX IF NOT EXISTS shrt_backend__link_tag_tag \
+             ON shrt_backend__link_tag (tag)",
+        )
+        .await?;
+    Ok(())
+}
+
+#[migration_op]
+async fn drop_link_tag_indexes(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw("DROP INDEX IF EXISTS shrt_backend__link_tag_tag")
+        .await?;
+    ctx.db
+        .raw("DROP INDEX IF EXISTS shrt_backend__link_tag_link_tag")
+        .await?;
+    Ok(())
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _Tag {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i32>,
+    #[model(unique)]
+    pub name: String,
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _LinkTag {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i64>,
+    pub link: cot::db::ForeignKey<crate::models::Link>,
+    pub tag: cot::db::ForeignKey<crate::models::Tag>,
+}
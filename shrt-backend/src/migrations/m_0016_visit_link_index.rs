@@ -0,0 +1,53 @@
+//! Written by hand in the format generated by cot CLI 0.5.0
+
+use cot::db::migrations::{MigrationContext, migration_op};
+
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Migration;
+impl ::cot::db::migrations::Migration for Migration {
+    const APP_NAME: &'static str = "shrt-backend";
+    const DEPENDENCIES: &'static [::cot::db::migrations::MigrationDependency] =
+        &[::cot::db::migrations::MigrationDependency::migration(
+            "shrt-backend",
+            "m_0015_link_revision",
+        )];
+    const MIGRATION_NAME: &'static str = "m_0016_visit_link_index";
+    const OPERATIONS: &'static [::cot::db::migrations::Operation] = &[
+        // Added by hand: the model definitions cannot describe indexes
+        ::cot::db::migrations::Operation::custom(create_link_visited_at_index)
+            .backwards(drop_link_visited_at_index)
+            .build(),
+    ];
+}
+
+#[migration_op]
+async fn create_link_visited_at_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw(
+            "CREATE INDEX IF NOT EXISTS shrt_backend__visit_link_visited_at \
+             ON shrt_backend__visit (link, visited_at)",
+        )
+        .await?;
+    Ok(())
+}
+
+#[migration_op]
+async fn drop_link_visited_at_index(ctx: MigrationContext<'_>) -> cot::db::Result<()> {
+    ctx.db
+        .raw("DROP INDEX IF EXISTS shrt_backend__visit_link_visited_at")
+        .await?;
+    Ok(())
+}
+
+#[derive(::core::fmt::Debug)]
+#[::cot::db::model(model_type = "migration")]
+struct _Visit {
+    #[model(primary_key)]
+    pub id: cot::db::Auto<i64>,
+    pub link: cot::db::ForeignKey<crate::models::Link>,
+    pub visited_at: chrono::DateTime<chrono::Utc>,
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub accept_language: Option<String>,
+    pub client_ip: Option<String>,
+}
@@ -1,6 +1,11 @@
 use chrono::{DateTime, Utc};
-use cot::db::{Auto, model};
+use cot::auth::{Password, PasswordHash, PasswordVerificationResult};
+use cot::db::{Auto, Database, ForeignKey, Model, ToDbValue, Transaction, model, query};
+use sha2::{Digest, Sha256};
+use shrt_common::links::{QueryPassthrough, RedirectStatus};
+use shrt_common::tokens::TokenScope;
 
+#[derive(Debug, Clone)]
 #[model]
 pub struct Link {
     #[model(primary_key)]
@@ -9,4 +14,447 @@ pub struct Link {
     pub url: String,
     pub created_at: DateTime<Utc>,
     pub visits: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub fallback_url: Option<String>,
+    pub max_visits: Option<i32>,
+    /// Argon2 hash of the password required to follow the link, in the PHC
+    /// string format
+    pub password_hash: Option<String>,
+    /// ID of the user who created the link
+    pub owner_id: Option<i64>,
+    /// Canonical form of [`Self::url`], used to find the links pointing to
+    /// the same resource
+    pub canonical_url: Option<String>,
+    /// Whether the slug was generated rather than chosen by the user
+    pub generated_slug: Option<bool>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    /// URL of the icon of the target page
+    pub favicon_url: Option<String>,
+    /// HTTP status code of the redirect to the target; the default one if not
+    /// set
+    pub redirect_status: Option<i32>,
+    /// Name of the [`QueryPassthrough`] mode; off if not set
+    pub query_passthrough: Option<String>,
+    /// Whether the path following the slug is forwarded to the target; not
+    /// if not set
+    pub prefix: Option<bool>,
+    /// Number of times the settings have been changed, 0 if not set; used to
+    /// detect concurrent changes
+    pub revision: Option<i32>,
+}
+
+/// The columns of [`Link`] written by [`Link::update_settings`].
+const SETTINGS_COLUMNS: [&str; 13] = [
+    "url",
+    "canonical_url",
+    "favicon_url",
+    "title",
+    "description",
+    "notes",
+    "redirect_status",
+    "query_passthrough",
+    "prefix",
+    "expires_at",
+    "fallback_url",
+    "max_visits",
+    "password_hash",
+];
+
+/// The columns of [`Link`], in the order of its fields, selected by
+/// [`Link::fetch_raw`].
+pub const LINK_COLUMNS: [&str; 20] = [
+    "id",
+    "slug",
+    "url",
+    "created_at",
+    "visits",
+    "expires_at",
+    "fallback_url",
+    "max_visits",
+    "password_hash",
+    "owner_id",
+    "canonical_url",
+    "generated_slug",
+    "title",
+    "description",
+    "notes",
+    "favicon_url",
+    "redirect_status",
+    "query_passthrough",
+    "prefix",
+    "revision",
+];
+
+impl Link {
+    /// Returns the entity tag of the link's current settings, along with the
+    /// sorted names of its `tags`.
+    ///
+    /// The tag changes whenever any of the editable fields changes, but not
+    /// when the link is visited.
+    #[must_use]
+    pub fn etag(&self, tags: &[String]) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            Some(self.slug.clone()),
+            Some(self.url.clone()),
+            self.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+            self.fallback_url.clone(),
+            self.max_visits.map(|max_visits| max_visits.to_string()),
+            self.password_hash.clone(),
+            self.title.clone(),
+            self.description.clone(),
+            self.notes.clone(),
+            self.redirect_status.map(|status| status.to_string()),
+            self.query_passthrough.clone(),
+            self.prefix.map(|prefix| prefix.to_string()),
+        ] {
+            match field {
+                Some(value) => {
+                    hasher.update([1]);
+                    hasher.update(value.len().to_le_bytes());
+                    hasher.update(value);
+                }
+                None => hasher.update([0]),
+            }
+        }
+        hasher.update(tags.len().to_le_bytes());
+        for tag in tags {
+            hasher.update(tag.len().to_le_bytes());
+            hasher.update(tag);
+        }
+
+        format!("\"{:.32x}\"", hasher.finalize())
+    }
+
+    /// Returns the links selected by the raw `sql` query, which has to select
+    /// [`LINK_COLUMNS`].
+    ///
+    /// Used where the query builder falls short, e.g. for substring
+    /// matching and ordering.
+    pub async fn fetch_raw(
+        db: &Database,
+        sql: &str,
+        values: &[&dyn ToDbValue],
+    ) -> cot::db::Result<Vec<Link>> {
+        db.fetch_all_with(sql, values)
+            .await?
+            .into_iter()
+            .map(Link::from_db)
+            .collect()
+    }
+
+    /// Returns the number counted by the raw `sql` query, which has to
+    /// select a single `COUNT(*)` column.
+    pub async fn count_raw(
+        db: &Database,
+        sql: &str,
+        values: &[&dyn ToDbValue],
+    ) -> cot::db::Result<u64> {
+        let count: i64 = match db.fetch_all_with(sql, values).await?.first() {
+            Some(row) => row.get(0)?,
+            None => 0,
+        };
+        Ok(u64::try_from(count).unwrap_or_default())
+    }
+
+    /// Returns whether the link has passed its expiration time at `now`.
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Returns whether the link has reached its maximum number of visits.
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.max_visits
+            .is_some_and(|max_visits| self.visits >= max_visits)
+    }
+
+    /// Returns the redirect to respond with when the link is followed.
+    #[must_use]
+    pub fn redirect_status(&self) -> RedirectStatus {
+        self.redirect_status
+            .and_then(|code| u16::try_from(code).ok())
+            .and_then(RedirectStatus::from_code)
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn query_passthrough(&self) -> QueryPassthrough {
+        self.query_passthrough
+            .as_deref()
+            .and_then(QueryPassthrough::from_name)
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn is_prefix(&self) -> bool {
+        self.prefix.unwrap_or_default()
+    }
+
+    /// Returns whether the browsers can cache the redirect, which is only
+    /// the case for the permanent redirects of the links that will keep
+    /// redirecting to the same URL and do not need to see every visit.
+    #[must_use]
+    pub fn is_redirect_cacheable(&self) -> bool {
+        self.redirect_status().is_permanent()
+            && self.expires_at.is_none()
+            && self.max_visits.is_none()
+            && self.password_hash.is_none()
+    }
+
+    #[must_use]
+    pub fn is_password_protected(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    /// Sets the password required to follow the link.
+    pub fn set_password(&mut self, password: &Password) {
+        self.password_hash = Some(PasswordHash::from_password(password).into_string());
+    }
+
+    /// Returns whether `password` unlocks the link.
+    #[must_use]
+    pub fn verify_password(&self, password: &Password) -> bool {
+        let Some(password_hash) = &self.password_hash else {
+            return true;
+        };
+
+        match PasswordHash::new(password_hash.clone()) {
+            Ok(password_hash) => matches!(
+                password_hash.verify(password),
+                PasswordVerificationResult::Ok | PasswordVerificationResult::OkObsolete(_)
+            ),
+            Err(_) => false,
+        }
+    }
+
+    /// Writes the settings of the link that differ from the ones of
+    /// `original`, the link as it was read, unless they have been changed
+    /// since.
+    ///
+    /// The revision check and the write are done in a single statement,
+    /// which leaves the visit counter alone. Returns `false` if the settings
+    /// have been changed concurrently.
+    pub async fn update_settings(
+        &mut self,
+        transaction: &Transaction<'_>,
+        original: &Link,
+    ) -> cot::db::Result<bool> {
+        let revision = original.revision.unwrap_or_default();
+        let assignments = SETTINGS_COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                format!(
+                    "{column} = CASE WHEN ${} THEN ${} ELSE {column} END",
+                    2 * i + 3,
+                    2 * i + 4
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "UPDATE shrt_backend__link SET revision = $2 + 1, {assignments} \
+             WHERE id = $1 AND COALESCE(revision, 0) = $2"
+        );
+
+        // The values are in the order of `SETTINGS_COLUMNS`, each preceded
+        // by whether it has changed
+        let result = transaction
+            .raw_with(
+                &sql,
+                &[
+                    &self.id,
+                    &revision,
+                    &(self.url != original.url),
+                    &self.url,
+                    &(self.canonical_url != original.canonical_url),
+                    &self.canonical_url,
+                    &(self.favicon_url != original.favicon_url),
+                    &self.favicon_url,
+                    &(self.title != original.title),
+                    &self.title,
+                    &(self.description != original.description),
+                    &self.description,
+                    &(self.notes != original.notes),
+                    &self.notes,
+                    &(self.redirect_status != original.redirect_status),
+                    &self.redirect_status,
+                    &(self.query_passthrough != original.query_passthrough),
+                    &self.query_passthrough,
+                    &(self.prefix != original.prefix),
+                    &self.prefix,
+                    &(self.expires_at != original.expires_at),
+                    &self.expires_at,
+                    &(self.fallback_url != original.fallback_url),
+                    &self.fallback_url,
+                    &(self.max_visits != original.max_visits),
+                    &self.max_visits,
+                    &(self.password_hash != original.password_hash),
+                    &self.password_hash,
+                ],
+            )
+            .await?;
+        if result.rows_affected().0 != 1 {
+            return Ok(false);
+        }
+
+        self.revision = Some(revision + 1);
+        Ok(true)
+    }
+
+    /// Records `visit` and increments the visit counter, unless the link has
+    /// reached its maximum number of visits.
+    ///
+    /// The limit check and the increment are done in a single statement, so
+    /// concurrent visits can never exceed the limit. Returns `false` if the
+    /// link is exhausted.
+    pub async fn register_visit(
+        &mut self,
+        db: &Database,
+        mut visit: Visit,
+    ) -> cot::db::Result<bool> {
+        let transaction = db.transaction().await?;
+        let result = transaction
+            .raw_with(
+                "UPDATE shrt_backend__link SET visits = visits + 1 \
+                 WHERE id = $1 AND (max_visits IS NULL OR visits < max_visits)",
+                &[&self.id],
+            )
+            .await?;
+        if result.rows_affected().0 != 1 {
+            transaction.rollback().await?;
+            return Ok(false);
+        }
+
+        visit.insert(&transaction).await?;
+        transaction.commit().await?;
+
+        self.visits += 1;
+        Ok(true)
+    }
+}
+
+#[model]
+pub struct Visit {
+    #[model(primary_key)]
+    pub id: Auto<i64>,
+    pub link: ForeignKey<Link>,
+    pub visited_at: DateTime<Utc>,
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub accept_language: Option<String>,
+    /// Client IP address with the host part zeroed out
+    pub client_ip: Option<String>,
+}
+
+impl Visit {
+    /// Removes the visits recorded before `cutoff`.
+    pub async fn remove_older_than(db: &Database, cutoff: DateTime<Utc>) -> cot::db::Result<()> {
+        query!(Visit, $visited_at < cutoff).delete(db).await?;
+        Ok(())
+    }
+}
+
+/// Personal API token, used to access the API on behalf of its owner.
+#[model]
+pub struct ApiToken {
+    #[model(primary_key)]
+    pub id: Auto<i32>,
+    /// ID of the user who created the token
+    pub user_id: i64,
+    pub name: String,
+    /// The first characters of the token, to tell the tokens apart
+    pub prefix: String,
+    /// Hex-encoded SHA-256 hash of the token
+    #[model(unique)]
+    pub token_hash: String,
+    /// Name of the [`TokenScope`] of the token
+    pub scope: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    /// How often to update the last usage time of a token, in seconds.
+    const LAST_USED_RESOLUTION_SECS: i64 = 60;
+    /// The prefix of all the tokens, making them easy to recognize.
+    pub const SECRET_PREFIX: &'static str = "shrt_";
+
+    /// Returns the value stored in place of the `secret` token.
+    #[must_use]
+    pub fn hash_secret(secret: &str) -> String {
+        format!("{:x}", Sha256::digest(secret))
+    }
+
+    /// Returns the scope of the token.
+    ///
+    /// Unknown scopes are treated as read-only.
+    #[must_use]
+    pub fn scope(&self) -> TokenScope {
+        TokenScope::from_name(&self.scope).unwrap_or(TokenScope::Read)
+    }
+
+    /// Returns whether the token can be used at `now`, i.e. it has neither
+    /// been revoked nor expired.
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+
+    /// Returns the active token with the `secret` value and records its use.
+    pub async fn authenticate(
+        db: &Database,
+        secret: &str,
+        now: DateTime<Utc>,
+    ) -> cot::db::Result<Option<Self>> {
+        let token_hash = Self::hash_secret(secret);
+        let Some(mut token) = query!(ApiToken, $token_hash == token_hash).get(db).await? else {
+            return Ok(None);
+        };
+        if !token.is_active(now) {
+            return Ok(None);
+        }
+
+        let recently_used = token.last_used_at.is_some_and(|last_used_at| {
+            (now - last_used_at).num_seconds() < Self::LAST_USED_RESOLUTION_SECS
+        });
+        if !recently_used {
+            // Only the usage time is written, so that a token revoked in the
+            // meantime stays revoked
+            db.raw_with(
+                "UPDATE shrt_backend__api_token SET last_used_at = $2 WHERE id = $1",
+                &[&token.id, &now],
+            )
+            .await?;
+            token.last_used_at = Some(now);
+        }
+
+        Ok(Some(token))
+    }
+}
+
+/// Label for grouping links, e.g. by project or campaign.
+#[model]
+pub struct Tag {
+    #[model(primary_key)]
+    pub id: Auto<i32>,
+    /// Normalized name of the tag, as returned by
+    /// [`shrt_common::tags::normalize_tags`]
+    #[model(unique)]
+    pub name: String,
+}
+
+/// Assignment of a [`Tag`] to a [`Link`].
+#[model]
+pub struct LinkTag {
+    #[model(primary_key)]
+    pub id: Auto<i64>,
+    pub link: ForeignKey<Link>,
+    pub tag: ForeignKey<Tag>,
 }
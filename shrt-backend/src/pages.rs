@@ -0,0 +1,163 @@
+//! Minimal server-rendered pages for visitors following a short link.
+
+use chrono::Utc;
+use cot::http::HeaderValue;
+use cot::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use cot::response::Response;
+use cot::{Body, StatusCode};
+use shrt_common::links::target_domain;
+
+use crate::models::Link;
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn page(status: StatusCode, title: &str, content: &str) -> Response {
+    let body = format!(
+        "<!doctype html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <meta name=\"robots\" content=\"noindex\">\n\
+         <title>{}</title>\n\
+         </head>\n\
+         <body>\n\
+         {content}\n\
+         </body>\n\
+         </html>\n",
+        escape_html(title),
+    );
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// Form asking for the password of a protected link.
+///
+/// The form is submitted to the URL it was served from.
+pub fn link_password_form(slug: &str, error: Option<&str>) -> Response {
+    let status = if error.is_some() {
+        StatusCode::FORBIDDEN
+    } else {
+        StatusCode::OK
+    };
+    let error = error
+        .map(|error| format!("<p role=\"alert\">{}</p>\n", escape_html(error)))
+        .unwrap_or_default();
+
+    page(
+        status,
+        "Password required",
+        &format!(
+            "<h1>Password required</h1>\n\
+             <p>The link <code>{}</code> is password protected.</p>\n\
+             {error}\
+             <form method=\"post\">\n\
+             <label for=\"password\">Password:</label>\n\
+             <input type=\"password\" id=\"password\" name=\"password\" required autofocus>\n\
+             <button type=\"submit\">Continue</button>\n\
+             </form>",
+            escape_html(slug),
+        ),
+    )
+}
+
+/// Page describing where a link leads, without following it.
+///
+/// The target URL, the fallback URL and the title are only shown if
+/// `reveal_url` is set; the button leads to the link itself, relative to the
+/// URL the page was served from.
+pub fn link_preview(link: &Link, reveal_url: bool) -> Response {
+    let locked = (
+        None,
+        Some("This link is password protected; its destination is shown after unlocking it."),
+        true,
+    );
+    let (destination, notice, can_continue) = if link.is_expired(Utc::now()) {
+        match link.fallback_url.as_deref() {
+            Some(fallback_url) if reveal_url => (
+                Some(fallback_url),
+                Some("This link has expired and leads to its fallback URL instead."),
+                true,
+            ),
+            Some(_) => locked,
+            None => (None, Some("This link has expired."), false),
+        }
+    } else if link.is_exhausted() {
+        (
+            None,
+            Some("This link has reached its limit of visits."),
+            false,
+        )
+    } else if reveal_url {
+        (Some(link.url.as_str()), None, true)
+    } else {
+        locked
+    };
+
+    let title = link
+        .title
+        .as_deref()
+        .filter(|_| reveal_url)
+        .map(|title| format!("<dt>Title</dt>\n<dd>{}</dd>\n", escape_html(title)))
+        .unwrap_or_default();
+    let destination_rows = destination
+        .map(|url| {
+            let domain = target_domain(url).unwrap_or(url);
+            format!(
+                "<dt>Destination</dt>\n<dd><code>{}</code></dd>\n\
+                 <dt>Domain</dt>\n<dd><strong>{}</strong></dd>\n",
+                escape_html(url),
+                escape_html(domain),
+            )
+        })
+        .unwrap_or_default();
+    let notice = notice
+        .map(|notice| format!("<p role=\"alert\">{}</p>\n", escape_html(notice)))
+        .unwrap_or_default();
+    let button = if can_continue {
+        "<p><a href=\"go\" role=\"button\" rel=\"noreferrer\">Continue</a></p>"
+    } else {
+        ""
+    };
+
+    page(
+        StatusCode::OK,
+        "Link preview",
+        &format!(
+            "<h1>Link preview</h1>\n\
+             <p>Short link: <code>{}</code></p>\n\
+             {notice}\
+             <dl>\n\
+             {title}\
+             {destination_rows}\
+             <dt>Created</dt>\n<dd>{}</dd>\n\
+             <dt>Visits</dt>\n<dd>{}</dd>\n\
+             </dl>\n\
+             {button}",
+            escape_html(&link.slug),
+            link.created_at.format("%Y-%m-%d %H:%M UTC"),
+            link.visits,
+        ),
+    )
+}
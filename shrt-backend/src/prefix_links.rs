@@ -0,0 +1,66 @@
+//! Forwarding the path segments following the slugs of the prefix links to
+//! their targets.
+
+use std::fmt::{Display, Formatter};
+
+/// The reason for a path not to be forwarded to the target of a link.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidSubpath;
+
+impl Display for InvalidSubpath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "path must not contain `.` or `..` segments")
+    }
+}
+
+impl std::error::Error for InvalidSubpath {}
+
+/// Splits the path of a visit of a link, `/links/{slug}/go/{subpath}`, into
+/// the path of the visit without the subpath and the subpath itself.
+///
+/// Returns `None` for any other path, including the ones with an empty
+/// subpath.
+#[must_use]
+pub fn split_link_path(path: &str) -> Option<(&str, &str)> {
+    let rest = path.strip_prefix("/links/")?;
+    let (slug, rest) = rest.split_once('/')?;
+    let subpath = rest.strip_prefix("go/")?;
+    if slug.is_empty() || subpath.is_empty() {
+        return None;
+    }
+    let visit_path_len = "/links/".len() + slug.len() + "/go".len();
+    Some((&path[..visit_path_len], subpath))
+}
+
+/// Returns `target` with the segments of `subpath` appended to its path.
+///
+/// Every segment is decoded and percent-encoded again, so it cannot contain
+/// anything but a single segment of the path; the empty ones are skipped and
+/// the dot segments rejected, as they could lead outside the path of the
+/// target. The query and the fragment of the target are kept.
+pub fn append_subpath(target: &str, subpath: &str) -> Result<String, InvalidSubpath> {
+    let segments = subpath
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let decoded = urlencoding::decode_binary(segment.as_bytes());
+            match decoded.as_ref() {
+                b"." | b".." => Err(InvalidSubpath),
+                decoded => Ok(urlencoding::encode_binary(decoded).into_owned()),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let split = target.find(['?', '#']).unwrap_or(target.len());
+    let (base, suffix) = target.split_at(split);
+    let mut url = base.to_owned();
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    url.push_str(&segments.join("/"));
+    if subpath.ends_with('/') && !segments.is_empty() {
+        url.push('/');
+    }
+    url.push_str(suffix);
+    Ok(url)
+}
@@ -0,0 +1,250 @@
+//! Rendering the short URLs of the links as QR codes.
+
+use std::fmt::{Display, Formatter, Write as _};
+use std::io::Cursor;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, Rgba, RgbaImage};
+use qrcode::types::QrError;
+use qrcode::{Color, EcLevel, QrCode};
+use shrt_common::qr_codes::{DEFAULT_QR_CODE_MARGIN, DEFAULT_QR_CODE_SIZE, ErrorCorrection};
+
+/// Part of the width of the code, without the margin, taken by the logo.
+const LOGO_SCALE: f64 = 0.2;
+
+/// An RGBA colour of the modules of a QR code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Colour(pub [u8; 4]);
+
+impl Colour {
+    pub const BLACK: Colour = Colour([0, 0, 0, 255]);
+    pub const TRANSPARENT: Colour = Colour([0, 0, 0, 0]);
+    pub const WHITE: Colour = Colour([255, 255, 255, 255]);
+
+    /// Parses a colour written as `#rrggbb`, `#rgb` (with or without the
+    /// `#`), or `transparent`.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.eq_ignore_ascii_case("transparent") {
+            return Some(Colour::TRANSPARENT);
+        }
+
+        let hex = value.strip_prefix('#').unwrap_or(value);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+        match hex.len() {
+            6 => Some(Colour([
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+                255,
+            ])),
+            3 => Some(Colour([
+                channel(&hex[0..1])? * 17,
+                channel(&hex[1..2])? * 17,
+                channel(&hex[2..3])? * 17,
+                255,
+            ])),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_transparent(self) -> bool {
+        self.0[3] == 0
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_transparent() {
+            write!(f, "transparent")
+        } else {
+            let [r, g, b, _] = self.0;
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        }
+    }
+}
+
+/// How to render a QR code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCodeOptions {
+    /// Width and height of the image, in pixels
+    pub size: u32,
+    /// Width of the empty border around the code, in modules
+    pub margin: u32,
+    pub error_correction: ErrorCorrection,
+    pub foreground: Colour,
+    pub background: Colour,
+    /// PNG or JPEG image to put in the middle of the code
+    pub logo: Option<Vec<u8>>,
+}
+
+impl Default for QrCodeOptions {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_QR_CODE_SIZE,
+            margin: DEFAULT_QR_CODE_MARGIN,
+            error_correction: ErrorCorrection::Medium,
+            foreground: Colour::BLACK,
+            background: Colour::WHITE,
+            logo: None,
+        }
+    }
+}
+
+/// Reasons for a QR code not to be rendered.
+#[derive(Debug)]
+pub enum QrCodeError {
+    /// The data could not be encoded, e.g. because it is too long
+    Encoding(QrError),
+    /// The logo is not an image in a supported format
+    InvalidLogo(image::ImageError),
+    /// The image could not be encoded
+    Image(image::ImageError),
+}
+
+impl Display for QrCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QrCodeError::Encoding(e) => write!(f, "could not encode the QR code: {e}"),
+            QrCodeError::InvalidLogo(e) => write!(f, "logo is not a valid image: {e}"),
+            QrCodeError::Image(e) => write!(f, "could not encode the image: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for QrCodeError {}
+
+fn encode(data: &str, error_correction: ErrorCorrection) -> Result<QrCode, QrCodeError> {
+    let level = match error_correction {
+        ErrorCorrection::Low => EcLevel::L,
+        ErrorCorrection::Medium => EcLevel::M,
+        ErrorCorrection::Quartile => EcLevel::Q,
+        ErrorCorrection::High => EcLevel::H,
+    };
+    QrCode::with_error_correction_level(data, level).map_err(QrCodeError::Encoding)
+}
+
+/// Returns the positions of the dark modules of the code, counted from its
+/// top left corner, without the margin.
+fn dark_modules(code: &QrCode) -> impl Iterator<Item = (usize, usize)> {
+    let width = code.width();
+    code.to_colors()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, color)| *color == Color::Dark)
+        .map(move |(i, _)| (i % width, i / width))
+}
+
+/// Renders the data as an SVG image, one unit per module.
+pub fn render_svg(data: &str, options: &QrCodeOptions) -> Result<String, QrCodeError> {
+    let code = encode(data, options.error_correction)?;
+    let width = code.width();
+    let margin = options.margin as usize;
+    let total = width + 2 * margin;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+         viewBox=\"0 0 {total} {total}\" shape-rendering=\"crispEdges\">",
+        size = options.size,
+    );
+    if !options.background.is_transparent() {
+        let _ = write!(
+            svg,
+            "<rect width=\"{total}\" height=\"{total}\" fill=\"{}\"/>",
+            options.background
+        );
+    }
+    let _ = write!(svg, "<path fill=\"{}\" d=\"", options.foreground);
+    for (x, y) in dark_modules(&code) {
+        let _ = write!(svg, "M{} {}h1v1h-1z", x + margin, y + margin);
+    }
+    svg.push_str("\"/>");
+
+    if let Some(logo) = &options.logo {
+        let format = image::guess_format(logo).map_err(QrCodeError::InvalidLogo)?;
+        let side = width as f64 * LOGO_SCALE;
+        let offset = margin as f64 + (width as f64 - side) / 2.0;
+        if !options.background.is_transparent() {
+            let _ = write!(
+                svg,
+                "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>",
+                offset - 0.5,
+                offset - 0.5,
+                side + 1.0,
+                side + 1.0,
+                options.background
+            );
+        }
+        let _ = write!(
+            svg,
+            "<image x=\"{offset:.2}\" y=\"{offset:.2}\" width=\"{side:.2}\" \
+             height=\"{side:.2}\" href=\"data:{};base64,{}\"/>",
+            format.to_mime_type(),
+            BASE64.encode(logo)
+        );
+    }
+
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+/// Renders the data as a PNG image.
+///
+/// The modules are scaled by a whole number of pixels to stay sharp, so the
+/// code is centred on the image and the rest of it is filled with the
+/// background colour. The image is larger than requested if the code does not
+/// fit in it with one pixel per module.
+pub fn render_png(data: &str, options: &QrCodeOptions) -> Result<Vec<u8>, QrCodeError> {
+    let code = encode(data, options.error_correction)?;
+    let width = code.width() as u32;
+    let total = width + 2 * options.margin;
+    let scale = (options.size / total).max(1);
+    let canvas = options.size.max(total * scale);
+    let origin = (canvas - total * scale) / 2 + options.margin * scale;
+
+    let mut image = RgbaImage::from_pixel(canvas, canvas, Rgba(options.background.0));
+    let foreground = Rgba(options.foreground.0);
+    for (x, y) in dark_modules(&code) {
+        let left = origin + x as u32 * scale;
+        let top = origin + y as u32 * scale;
+        for dy in 0..scale {
+            for dx in 0..scale {
+                image.put_pixel(left + dx, top + dy, foreground);
+            }
+        }
+    }
+
+    if let Some(logo) = &options.logo {
+        let code_size = width * scale;
+        let side = ((f64::from(code_size) * LOGO_SCALE) as u32).max(1);
+        let logo = image::load_from_memory(logo)
+            .map_err(QrCodeError::InvalidLogo)?
+            .resize(side, side, FilterType::Lanczos3)
+            .to_rgba8();
+        let left = origin + (code_size - logo.width()) / 2;
+        let top = origin + (code_size - logo.height()) / 2;
+
+        if !options.background.is_transparent() {
+            let padding = scale / 2;
+            let background = Rgba(options.background.0);
+            for y in top - padding..top + logo.height() + padding {
+                for x in left - padding..left + logo.width() + padding {
+                    image.put_pixel(x, y, background);
+                }
+            }
+        }
+        imageops::overlay(&mut image, &logo, i64::from(left), i64::from(top));
+    }
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(QrCodeError::Image)?;
+    Ok(png)
+}
@@ -0,0 +1,76 @@
+//! Passing the query parameters of the visitors on to the targets of the
+//! links.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use shrt_common::links::QueryPassthrough;
+
+/// Returns `target` with the parameters of the query string of the visitor
+/// merged into its query string as `mode` says.
+///
+/// The parameters are copied as they were sent, without decoding and
+/// encoding them again; only their names are decoded to find the conflicting
+/// ones. The fragment of the target is kept.
+#[must_use]
+pub fn merge_query(target: &str, visitor_query: Option<&str>, mode: QueryPassthrough) -> String {
+    let visitor_params = params(visitor_query.unwrap_or_default());
+    if mode == QueryPassthrough::Off || visitor_params.is_empty() {
+        return target.to_owned();
+    }
+
+    let (rest, fragment) = match target.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (target, None),
+    };
+    let (base, target_query) = rest.split_once('?').unwrap_or((rest, ""));
+    let target_params = params(target_query);
+
+    let merged: Vec<&str> = match mode {
+        QueryPassthrough::Off => unreachable!("checked above"),
+        QueryPassthrough::VisitorWins => {
+            let names: HashSet<_> = visitor_params.iter().copied().map(name).collect();
+            target_params
+                .into_iter()
+                .filter(|param| !names.contains(&name(param)))
+                .chain(visitor_params)
+                .collect()
+        }
+        QueryPassthrough::TargetWins => {
+            let names: HashSet<_> = target_params.iter().copied().map(name).collect();
+            let visitor_params = visitor_params
+                .into_iter()
+                .filter(|param| !names.contains(&name(param)));
+            target_params.into_iter().chain(visitor_params).collect()
+        }
+        QueryPassthrough::Append => target_params.into_iter().chain(visitor_params).collect(),
+    };
+
+    let mut url = base.to_owned();
+    if !merged.is_empty() {
+        url.push('?');
+        url.push_str(&merged.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
+fn params(query: &str) -> Vec<&str> {
+    query.split('&').filter(|param| !param.is_empty()).collect()
+}
+
+/// Returns the decoded name of a `name=value` parameter.
+fn name(param: &str) -> Cow<'_, str> {
+    let name = param.split_once('=').map_or(param, |(name, _)| name);
+    if !name.contains(['+', '%']) {
+        return Cow::Borrowed(name);
+    }
+    let name = name.replace('+', " ");
+    match urlencoding::decode(&name) {
+        Ok(decoded) => Cow::Owned(decoded.into_owned()),
+        Err(_) => Cow::Owned(name),
+    }
+}
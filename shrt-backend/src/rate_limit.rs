@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use shrt_common::config::{RateLimit, RateLimits};
+
+/// Maximum number of budgets to track; once reached, the ones that are not
+/// used up at all are forgotten, followed by the least recently used ones.
+pub const MAX_TRACKED_BUDGETS: usize = 10_000;
+
+/// Actions with a separate budget of requests.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitedAction {
+    CreateLink,
+    LinkExists,
+    LinkNotFound,
+    LinkPassword,
+}
+
+impl RateLimitedAction {
+    #[must_use]
+    pub fn limit(self, limits: &RateLimits) -> RateLimit {
+        match self {
+            RateLimitedAction::CreateLink => limits.create_link,
+            RateLimitedAction::LinkExists => limits.link_exists,
+            RateLimitedAction::LinkNotFound => limits.link_not_found,
+            RateLimitedAction::LinkPassword => limits.link_password,
+        }
+    }
+}
+
+/// Identifies the client the budget belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    User(i64),
+    Ip(IpAddr),
+    /// Clients whose address is unknown, e.g. because the requests do not go
+    /// through the reverse proxy; they all share a single budget
+    Unknown,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.requests),
+            updated_at: now,
+        }
+    }
+
+    /// Number of requests regained per second.
+    fn refill_rate(&self) -> f64 {
+        f64::from(self.limit.requests) / f64::from(self.limit.period_secs)
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.refill_rate()).min(f64::from(self.limit.requests));
+        self.updated_at = now;
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let mut bucket = *self;
+        bucket.refill(now);
+        bucket.tokens >= f64::from(self.limit.requests)
+    }
+
+    /// Returns an error with the time until `requests` requests can be made
+    /// if there is not enough budget left.
+    fn check(&self, requests: u32) -> Result<(), Duration> {
+        let requests = f64::from(requests);
+        if self.tokens >= requests {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (requests - self.tokens) / self.refill_rate(),
+            ))
+        }
+    }
+}
+
+/// Token bucket rate limiter: each client can make up to
+/// [`RateLimit::requests`] requests at once, and regains the ability to make
+/// them evenly over [`RateLimit::period_secs`].
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<(RateLimitedAction, RateLimitKey), Bucket>>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses up one request of the budget of `key`.
+    ///
+    /// Returns the time after which the client can retry if the budget has
+    /// been exhausted.
+    pub fn acquire(
+        &self,
+        action: RateLimitedAction,
+        key: RateLimitKey,
+        limit: RateLimit,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.acquire_many(action, key, limit, 1, now)
+    }
+
+    /// Uses up `requests` requests of the budget of `key` at once, or none of
+    /// them if there are not enough left, e.g. for the items of a batch.
+    ///
+    /// Returns the time after which the client can retry if the budget is
+    /// too low; `requests` must not exceed [`RateLimit::requests`], or the
+    /// budget will never be enough.
+    pub fn acquire_many(
+        &self,
+        action: RateLimitedAction,
+        key: RateLimitKey,
+        limit: RateLimit,
+        requests: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.with_bucket(action, key, limit, now, |bucket| {
+            bucket.check(requests)?;
+            bucket.tokens -= f64::from(requests);
+            Ok(())
+        })
+        .unwrap_or(Ok(()))
+    }
+
+    /// Returns an error with the time after which the client can retry if the
+    /// budget of `key` has been exhausted, without using it up.
+    pub fn check(
+        &self,
+        action: RateLimitedAction,
+        key: RateLimitKey,
+        limit: RateLimit,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.with_bucket(action, key, limit, now, |bucket| bucket.check(1))
+            .unwrap_or(Ok(()))
+    }
+
+    /// Uses up one request of the budget of `key`, even if there is none left.
+    ///
+    /// This is meant for requests that only count towards the limit once
+    /// their outcome is known, after a successful [`Self::check`].
+    pub fn charge(
+        &self,
+        action: RateLimitedAction,
+        key: RateLimitKey,
+        limit: RateLimit,
+        now: Instant,
+    ) {
+        self.with_bucket(action, key, limit, now, |bucket| {
+            bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+        });
+    }
+
+    /// Calls `f` with the up-to-date budget of `key`, unless the limit is
+    /// disabled.
+    fn with_bucket<R>(
+        &self,
+        action: RateLimitedAction,
+        key: RateLimitKey,
+        limit: RateLimit,
+        now: Instant,
+        f: impl FnOnce(&mut Bucket) -> R,
+    ) -> Option<R> {
+        if !limit.is_enabled() {
+            return None;
+        }
+
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+        if buckets.len() >= MAX_TRACKED_BUDGETS && !buckets.contains_key(&(action, key)) {
+            evict(&mut buckets, now);
+        }
+
+        let bucket = buckets
+            .entry((action, key))
+            .or_insert_with(|| Bucket::full(limit, now));
+        bucket.refill(now);
+        Some(f(bucket))
+    }
+}
+
+/// Makes room for new budgets by forgetting the ones that are full again
+/// and, if that is not enough, the least recently used ones.
+///
+/// A tenth of the budgets is forgotten at once, so that this does not happen
+/// on every request of a new client.
+fn evict(buckets: &mut HashMap<(RateLimitedAction, RateLimitKey), Bucket>, now: Instant) {
+    buckets.retain(|_, bucket| !bucket.is_full(now));
+
+    let max_len = MAX_TRACKED_BUDGETS - MAX_TRACKED_BUDGETS / 10;
+    if buckets.len() > max_len {
+        let excess = buckets.len() - max_len;
+        let mut by_last_use: Vec<_> = buckets
+            .iter()
+            .map(|(key, bucket)| (bucket.updated_at, *key))
+            .collect();
+        by_last_use.sort_unstable_by_key(|(updated_at, _)| *updated_at);
+        for (_, key) in by_last_use.into_iter().take(excess) {
+            buckets.remove(&key);
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use nanoid::nanoid;
+use rand::seq::IndexedRandom;
+use sha2::{Digest, Sha256};
+use shrt_common::config::{SlugGeneratorConfig, SlugStrategy};
+
+/// Number of attempts made at each slug length before making the slugs
+/// longer.
+const ATTEMPTS_PER_LENGTH: u32 = 3;
+
+const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+const ADJECTIVES: &[&str] = &[
+    "agile", "amber", "ancient", "azure", "bold", "brave", "breezy", "bright", "calm", "clever",
+    "cosmic", "crisp", "curious", "daring", "eager", "fancy", "fast", "fluffy", "gentle", "giant",
+    "golden", "happy", "honest", "humble", "jolly", "keen", "kind", "lively", "lucky", "mellow",
+    "mighty", "misty", "noble", "polite", "proud", "quick", "quiet", "rapid", "shiny", "silent",
+    "silver", "sleepy", "smooth", "sunny", "swift", "tidy", "vivid", "witty",
+];
+
+const NOUNS: &[&str] = &[
+    "badger", "bear", "beaver", "bison", "crane", "dolphin", "eagle", "falcon", "ferret", "finch",
+    "fox", "gecko", "heron", "ibis", "koala", "lemur", "lion", "llama", "lynx", "marmot", "moose",
+    "newt", "ocelot", "orca", "otter", "owl", "panda", "parrot", "pelican", "penguin", "puffin",
+    "quokka", "rabbit", "raven", "robin", "salmon", "seal", "sparrow", "squid", "swan", "tiger",
+    "toucan", "turtle", "walrus", "whale", "wombat", "yak", "zebra",
+];
+
+/// Words that must never appear in generated slugs.
+const OFFENSIVE_WORDS: &[&str] = &[
+    "bitch", "cock", "cunt", "dick", "fag", "fuck", "kike", "nazi", "nigg", "piss", "porn", "rape",
+    "shit", "slut", "spic", "tits", "twat", "wank", "whore",
+];
+
+/// Information about the link being created.
+#[derive(Debug, Copy, Clone)]
+pub struct SlugContext<'a> {
+    /// The target URL of the link
+    pub url: &'a str,
+    /// The number of the existing links
+    pub link_count: u64,
+}
+
+/// A way of generating slugs for the links created without a custom one.
+pub trait SlugGenerator: Send + Sync {
+    /// Returns a candidate slug for a new link.
+    ///
+    /// `attempt` is the number of candidates rejected so far, e.g. because
+    /// they were already taken; each attempt should yield a different slug,
+    /// and the slugs should get longer as the attempts pile up.
+    fn generate(&self, context: SlugContext<'_>, attempt: u32) -> String;
+
+    /// Whether the generator uses [`SlugContext::link_count`].
+    fn needs_link_count(&self) -> bool {
+        false
+    }
+}
+
+/// Maximum number of characters in the alphabet.
+const MAX_ALPHABET_LENGTH: usize = 255;
+
+/// Error returned when the alphabet of the slug generator is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidAlphabet;
+
+impl std::fmt::Display for InvalidAlphabet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "slug alphabet must contain between 2 and {MAX_ALPHABET_LENGTH} distinct characters"
+        )
+    }
+}
+
+impl std::error::Error for InvalidAlphabet {}
+
+/// Creates the slug generator described by `config`.
+pub fn from_config(
+    config: &SlugGeneratorConfig,
+) -> Result<Box<dyn SlugGenerator>, InvalidAlphabet> {
+    Ok(match &config.strategy {
+        SlugStrategy::Random { alphabet, length } => Box::new(RandomSlugGenerator {
+            alphabet: parse_alphabet(alphabet)?,
+            length: *length,
+        }),
+        SlugStrategy::Words { digits } => Box::new(WordsSlugGenerator { digits: *digits }),
+        SlugStrategy::Sequential {
+            alphabet,
+            min_length,
+            salt,
+        } => {
+            let mut alphabet = parse_alphabet(alphabet)?;
+            if let Some(salt) = salt {
+                shuffle(&mut alphabet, salt);
+            }
+            Box::new(SequentialSlugGenerator {
+                alphabet,
+                min_length: *min_length,
+                salt: salt.clone(),
+            })
+        }
+        SlugStrategy::UrlHash { alphabet, length } => Box::new(UrlHashSlugGenerator {
+            alphabet: parse_alphabet(alphabet)?,
+            length: *length,
+        }),
+    })
+}
+
+/// Returns the distinct characters of `alphabet`, in order.
+fn parse_alphabet(alphabet: &str) -> Result<Vec<char>, InvalidAlphabet> {
+    let mut chars: Vec<char> = Vec::new();
+    for c in alphabet.chars() {
+        if !chars.contains(&c) {
+            chars.push(c);
+        }
+    }
+
+    if !(2..=MAX_ALPHABET_LENGTH).contains(&chars.len()) {
+        return Err(InvalidAlphabet);
+    }
+    Ok(chars)
+}
+
+/// Number of characters to add to the slug length at `attempt`.
+fn extra_length(attempt: u32) -> usize {
+    (attempt / ATTEMPTS_PER_LENGTH) as usize
+}
+
+/// Random characters from an alphabet.
+#[derive(Debug, Clone)]
+pub struct RandomSlugGenerator {
+    alphabet: Vec<char>,
+    length: usize,
+}
+
+impl SlugGenerator for RandomSlugGenerator {
+    fn generate(&self, _context: SlugContext<'_>, attempt: u32) -> String {
+        let length = self.length.max(1) + extra_length(attempt);
+        nanoid!(length, &self.alphabet)
+    }
+}
+
+/// Random adjective and noun followed by a random number, e.g.
+/// `brave-otter-42`.
+#[derive(Debug, Clone)]
+pub struct WordsSlugGenerator {
+    digits: usize,
+}
+
+impl SlugGenerator for WordsSlugGenerator {
+    fn generate(&self, _context: SlugContext<'_>, attempt: u32) -> String {
+        let mut rng = rand::rng();
+        let adjective = ADJECTIVES.choose(&mut rng).expect("list is not empty");
+        let noun = NOUNS.choose(&mut rng).expect("list is not empty");
+
+        let digits = self.digits + extra_length(attempt);
+        if digits == 0 {
+            format!("{adjective}-{noun}")
+        } else {
+            format!("{adjective}-{noun}-{}", nanoid!(digits, &DIGITS))
+        }
+    }
+}
+
+/// Consecutive numbers, starting after the number of the existing links.
+///
+/// Links that have been removed make the count lag behind the numbers
+/// already in use, so the consecutive attempts probe further and further
+/// ahead.
+///
+/// If there is a salt, the numbers are scrambled with it before being
+/// written, so that the consecutive slugs do not share their beginnings;
+/// the scrambling keeps the length of the slugs and never maps two numbers
+/// to the same slug.
+#[derive(Debug, Clone)]
+pub struct SequentialSlugGenerator {
+    alphabet: Vec<char>,
+    min_length: usize,
+    salt: Option<String>,
+}
+
+impl SlugGenerator for SequentialSlugGenerator {
+    fn generate(&self, context: SlugContext<'_>, attempt: u32) -> String {
+        let attempt = u64::from(attempt);
+        let number = context
+            .link_count
+            .saturating_add(1)
+            .saturating_add(attempt * attempt);
+
+        // Numbers written with more characters than the minimum length are
+        // scrambled with the ones of the same length
+        let length = encode_number(number, &self.alphabet, self.min_length)
+            .chars()
+            .count();
+        let number = match &self.salt {
+            Some(salt) => scramble(number, self.alphabet.len() as u64, length, salt),
+            None => number,
+        };
+        encode_number(number, &self.alphabet, length)
+    }
+
+    fn needs_link_count(&self) -> bool {
+        true
+    }
+}
+
+/// Writes `number` using the digits from `alphabet`, padded to `min_length`
+/// with the first character of the alphabet.
+#[must_use]
+pub fn encode_number(mut number: u64, alphabet: &[char], min_length: usize) -> String {
+    let base = alphabet.len() as u64;
+    let mut chars = Vec::new();
+    loop {
+        chars.push(alphabet[(number % base) as usize]);
+        number /= base;
+        if number == 0 {
+            break;
+        }
+    }
+    while chars.len() < min_length {
+        chars.push(alphabet[0]);
+    }
+
+    chars.iter().rev().collect()
+}
+
+/// Maps `number`, written with `length` digits in base `base`, to another
+/// number written with at most as many digits, deterministically based on
+/// `salt`.
+///
+/// The numbers below `base` to the power of `length` are mapped with an affine
+/// permutation of that range, so no two of them are mapped to the same number;
+/// the range is capped at the powers that fit in `u64`, and the numbers above
+/// it are left as they are.
+fn scramble(number: u64, base: u64, length: usize, salt: &str) -> u64 {
+    let mut digits = u32::try_from(length).unwrap_or(u32::MAX);
+    let modulus = loop {
+        if let Some(modulus) = base.checked_pow(digits) {
+            break modulus;
+        }
+        digits -= 1;
+    };
+    if number >= modulus {
+        return number;
+    }
+
+    let hash = Sha256::new()
+        .chain_update(salt)
+        .chain_update(u64::from(digits).to_le_bytes())
+        .finalize();
+    let mut multiplier = u64::from_le_bytes(hash[..8].try_into().expect("hash is long enough"));
+    let increment = u64::from_le_bytes(hash[8..16].try_into().expect("hash is long enough"));
+    // The multiplier has to be coprime with the modulus for the mapping to be
+    // a permutation
+    while gcd(multiplier, base) != 1 {
+        multiplier = multiplier.wrapping_add(1);
+    }
+
+    let modulus = u128::from(modulus);
+    let product = u128::from(multiplier) * u128::from(number) % modulus;
+    let scrambled = (product + u128::from(increment) % modulus) % modulus;
+    u64::try_from(scrambled).expect("scrambled number is below the modulus")
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Shuffles `alphabet` deterministically, based on `salt`.
+fn shuffle(alphabet: &mut [char], salt: &str) {
+    for i in (1..alphabet.len()).rev() {
+        let hash = Sha256::new()
+            .chain_update(salt)
+            .chain_update((i as u64).to_le_bytes())
+            .finalize();
+        let random = u64::from_le_bytes(hash[..8].try_into().expect("hash is long enough"));
+        let j = (random % (i as u64 + 1)) as usize;
+        alphabet.swap(i, j);
+    }
+}
+
+/// Hash of the target URL; longer prefixes of the hash are used when the
+/// shorter ones are taken.
+#[derive(Debug, Clone)]
+pub struct UrlHashSlugGenerator {
+    alphabet: Vec<char>,
+    length: usize,
+}
+
+impl SlugGenerator for UrlHashSlugGenerator {
+    fn generate(&self, context: SlugContext<'_>, attempt: u32) -> String {
+        let length = self.length.max(1) + attempt as usize;
+        let mut slug = String::new();
+        let mut round = 0u32;
+        while slug.chars().count() < length {
+            let hash = Sha256::new()
+                .chain_update(context.url)
+                .chain_update(round.to_le_bytes())
+                .finalize();
+            slug.extend(hash_digits(&hash, &self.alphabet));
+            round += 1;
+        }
+
+        slug.chars().take(length).collect()
+    }
+}
+
+/// Converts `hash` to the digits from `alphabet`, as if it was a big-endian
+/// number.
+fn hash_digits(hash: &[u8], alphabet: &[char]) -> Vec<char> {
+    let base = alphabet.len() as u32;
+    let mut number = hash.to_vec();
+    let mut digits = Vec::new();
+    while number.iter().any(|&byte| byte != 0) {
+        let mut remainder = 0u32;
+        for byte in &mut number {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = (value / base) as u8;
+            remainder = value % base;
+        }
+        digits.push(alphabet[remainder as usize]);
+    }
+
+    digits
+}
+
+/// Rejects the slugs containing offensive words.
+#[derive(Debug, Clone)]
+pub struct OffensiveWordFilter {
+    words: Vec<String>,
+}
+
+impl OffensiveWordFilter {
+    /// Creates a filter rejecting the built-in offensive words and the
+    /// `blocked_words`.
+    #[must_use]
+    pub fn new(blocked_words: &[String]) -> Self {
+        Self {
+            words: OFFENSIVE_WORDS
+                .iter()
+                .map(|&word| word.to_owned())
+                .chain(blocked_words.iter().map(|word| normalize(word)))
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Returns whether `slug` contains any of the words, ignoring case,
+    /// separators and digits resembling letters.
+    #[must_use]
+    pub fn is_offensive(&self, slug: &str) -> bool {
+        let slug = normalize(slug);
+        self.words.iter().any(|word| slug.contains(word.as_str()))
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.'))
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'i',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            c => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
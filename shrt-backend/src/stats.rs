@@ -0,0 +1,197 @@
+//! Aggregation of the per-visit records into link statistics.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveTime, TimeDelta, Timelike, Utc};
+use shrt_common::stats::{LinkStats, StatsInterval, TopEntry, VisitCount};
+use url::Url;
+
+use crate::models::Visit;
+
+/// Maximum number of time buckets in a single response.
+pub const MAX_BUCKETS: usize = 1000;
+/// Maximum number of visits aggregated in a single response; the visits are
+/// read from the database all at once.
+pub const MAX_VISITS: u64 = 100_000;
+/// Maximum number of entries in each of the "top" lists.
+const TOP_ENTRIES: usize = 10;
+
+/// Returns the start of the time bucket containing `time`.
+///
+/// Weeks start on Monday; all buckets are aligned to UTC.
+#[must_use]
+pub fn bucket_start(interval: StatsInterval, time: DateTime<Utc>) -> DateTime<Utc> {
+    let day = time.date_naive();
+    let midnight = match interval {
+        StatsInterval::Hour | StatsInterval::Day => day,
+        StatsInterval::Week => day - Days::new(u64::from(day.weekday().num_days_from_monday())),
+        StatsInterval::Month => day - Days::new(u64::from(day.day0())),
+    }
+    .and_time(NaiveTime::MIN)
+    .and_utc();
+
+    match interval {
+        StatsInterval::Hour => midnight + TimeDelta::hours(time.hour().into()),
+        StatsInterval::Day | StatsInterval::Week | StatsInterval::Month => midnight,
+    }
+}
+
+fn next_bucket(interval: StatsInterval, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match interval {
+        StatsInterval::Hour => start.checked_add_signed(TimeDelta::hours(1)),
+        StatsInterval::Day => start.checked_add_days(Days::new(1)),
+        StatsInterval::Week => start.checked_add_days(Days::new(7)),
+        StatsInterval::Month => start.checked_add_months(Months::new(1)),
+    }
+}
+
+/// Returns the default range of the statistics ending at `now`.
+#[must_use]
+pub fn default_from(interval: StatsInterval, now: DateTime<Utc>) -> DateTime<Utc> {
+    let from = match interval {
+        StatsInterval::Hour => now.checked_sub_signed(TimeDelta::hours(48)),
+        StatsInterval::Day => now.checked_sub_days(Days::new(30)),
+        StatsInterval::Week => now.checked_sub_days(Days::new(7 * 26)),
+        StatsInterval::Month => now.checked_sub_months(Months::new(12)),
+    };
+    bucket_start(interval, from.unwrap_or(DateTime::<Utc>::MIN_UTC))
+}
+
+/// Returns the starts of the time buckets covering `from..to`, or `None` if
+/// there are more than [`MAX_BUCKETS`] of them.
+#[must_use]
+pub fn time_buckets(
+    interval: StatsInterval,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<Vec<DateTime<Utc>>> {
+    let mut buckets = Vec::new();
+    let mut start = Some(bucket_start(interval, from));
+    while let Some(current) = start
+        && current < to
+    {
+        if buckets.len() == MAX_BUCKETS {
+            return None;
+        }
+        buckets.push(current);
+        start = next_bucket(interval, current);
+    }
+
+    Some(buckets)
+}
+
+/// Returns the name of the browser that sent `user_agent`.
+#[must_use]
+pub fn browser_name(user_agent: Option<&str>) -> &'static str {
+    let Some(user_agent) = user_agent else {
+        return "Unknown";
+    };
+    let lowercase = user_agent.to_lowercase();
+
+    if ["bot", "crawl", "spider"]
+        .iter()
+        .any(|name| lowercase.contains(name))
+    {
+        "Bot"
+    } else if user_agent.contains("Edg/") || user_agent.contains("EdgA/") {
+        "Edge"
+    } else if user_agent.contains("OPR/") || user_agent.contains("Opera") {
+        "Opera"
+    } else if user_agent.contains("Firefox/") || user_agent.contains("FxiOS/") {
+        "Firefox"
+    } else if user_agent.contains("Chrome/") || user_agent.contains("CriOS/") {
+        "Chrome"
+    } else if user_agent.contains("Safari/") {
+        "Safari"
+    } else {
+        "Other"
+    }
+}
+
+/// Returns the region subtag of the most preferred language of the visitor,
+/// e.g. `en-GB,en;q=0.9` yields `GB`.
+///
+/// This is a language preference rather than the location of the visitor.
+#[must_use]
+pub fn language_region(accept_language: Option<&str>) -> Option<String> {
+    let language = accept_language?.split([',', ';']).next()?.trim();
+    let region = language.split(['-', '_']).nth(1)?;
+
+    (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| region.to_ascii_uppercase())
+}
+
+/// Returns the host of `referrer`, or `Direct` if there is no referrer.
+#[must_use]
+pub fn referrer_host(referrer: Option<&str>) -> String {
+    match referrer {
+        None => "Direct".to_owned(),
+        Some(referrer) => Url::parse(referrer)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned))
+            .unwrap_or_else(|| "Unknown".to_owned()),
+    }
+}
+
+fn top_entries(counts: HashMap<String, u64>) -> Vec<TopEntry> {
+    let mut entries: Vec<_> = counts
+        .into_iter()
+        .map(|(name, visits)| TopEntry { name, visits })
+        .collect();
+    entries.sort_by(|a, b| b.visits.cmp(&a.visits).then_with(|| a.name.cmp(&b.name)));
+    entries.truncate(TOP_ENTRIES);
+    entries
+}
+
+/// Builds the statistics of the link with `slug` from its `visits`, which are
+/// expected to be within `from..to`.
+#[must_use]
+pub fn link_stats(
+    slug: String,
+    interval: StatsInterval,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    buckets: Vec<DateTime<Utc>>,
+    visits: &[Visit],
+) -> LinkStats {
+    let mut bucket_counts = HashMap::<DateTime<Utc>, u64>::new();
+    let mut referrers = HashMap::new();
+    let mut browsers = HashMap::new();
+    let mut language_regions = HashMap::new();
+
+    for visit in visits {
+        *bucket_counts
+            .entry(bucket_start(interval, visit.visited_at))
+            .or_default() += 1;
+        *referrers
+            .entry(referrer_host(visit.referrer.as_deref()))
+            .or_default() += 1;
+        *browsers
+            .entry(browser_name(visit.user_agent.as_deref()).to_owned())
+            .or_default() += 1;
+        *language_regions
+            .entry(
+                language_region(visit.accept_language.as_deref())
+                    .unwrap_or_else(|| "Unknown".to_owned()),
+            )
+            .or_default() += 1;
+    }
+
+    LinkStats {
+        slug,
+        interval,
+        from,
+        to,
+        total_visits: visits.len() as u64,
+        visits: buckets
+            .into_iter()
+            .map(|start| VisitCount {
+                start,
+                visits: bucket_counts.get(&start).copied().unwrap_or_default(),
+            })
+            .collect(),
+        top_referrers: top_entries(referrers),
+        top_browsers: top_entries(browsers),
+        top_language_regions: top_entries(language_regions),
+    }
+}
@@ -0,0 +1,158 @@
+//! Tags assigned to the links.
+
+use std::collections::HashMap;
+
+use cot::db::{Auto, DatabaseBackend, ForeignKey, Model, query};
+
+use crate::models::{Link, LinkTag, Tag};
+
+/// Replaces the tags of `link` with the ones called `names`, creating the
+/// tags that do not exist yet.
+///
+/// The names are expected to be normalized with
+/// [`shrt_common::tags::normalize_tags`].
+pub async fn set_link_tags<DB: DatabaseBackend>(
+    db: &DB,
+    link: &Link,
+    names: &[String],
+) -> cot::db::Result<()> {
+    let link_key = ForeignKey::<Link>::PrimaryKey(link.id);
+    query!(LinkTag, $link == link_key).delete(db).await?;
+
+    for name in names {
+        let tag = match query!(Tag, $name == name.clone()).get(db).await? {
+            Some(tag) => tag,
+            None => {
+                let mut tag = Tag {
+                    id: Auto::auto(),
+                    name: name.clone(),
+                };
+                tag.insert(db).await?;
+                tag
+            }
+        };
+        LinkTag {
+            id: Auto::auto(),
+            link: ForeignKey::PrimaryKey(link.id),
+            tag: ForeignKey::PrimaryKey(tag.id),
+        }
+        .insert(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether any tag is assigned to `link`.
+pub async fn has_tags<DB: DatabaseBackend>(db: &DB, link: &Link) -> cot::db::Result<bool> {
+    let link_key = ForeignKey::<Link>::PrimaryKey(link.id);
+    query!(LinkTag, $link == link_key).exists(db).await
+}
+
+/// Returns the names of the tags of each of `links`, sorted.
+///
+/// The tags of a single link are queried directly; for more links, all the
+/// assignments are read at once rather than queried per link.
+pub async fn tags_of<DB: DatabaseBackend>(
+    db: &DB,
+    links: &[Link],
+) -> cot::db::Result<Vec<Vec<String>>> {
+    let mut tags_by_link = match links {
+        [] => return Ok(Vec::new()),
+        [link] => {
+            let link_key = ForeignKey::<Link>::PrimaryKey(link.id);
+            let link_tags = query!(LinkTag, $link == link_key).all(db).await?;
+            group_by_link(db, link_tags).await?
+        }
+        _ => tags_by_link(db).await?,
+    };
+
+    Ok(links
+        .iter()
+        .map(|link| match link.id {
+            Auto::Fixed(id) => tags_by_link.remove(&id).unwrap_or_default(),
+            Auto::Auto => Vec::new(),
+        })
+        .collect())
+}
+
+/// Returns the sorted names of the tags of every tagged link, by the IDs of
+/// the links.
+pub async fn tags_by_link<DB: DatabaseBackend>(
+    db: &DB,
+) -> cot::db::Result<HashMap<i32, Vec<String>>> {
+    let link_tags = LinkTag::objects().all(db).await?;
+    group_by_link(db, link_tags).await
+}
+
+/// Returns the sorted names of the tags of the tagged links with IDs greater
+/// than `after` and up to `until`, by the IDs of the links.
+pub async fn tags_of_links_between<DB: DatabaseBackend>(
+    db: &DB,
+    after: i32,
+    until: i32,
+) -> cot::db::Result<HashMap<i32, Vec<String>>> {
+    let after = ForeignKey::<Link>::PrimaryKey(Auto::fixed(after));
+    let until = ForeignKey::<Link>::PrimaryKey(Auto::fixed(until));
+    let link_tags = query!(LinkTag, $link > after && $link <= until)
+        .all(db)
+        .await?;
+    group_by_link(db, link_tags).await
+}
+
+async fn group_by_link<DB: DatabaseBackend>(
+    db: &DB,
+    link_tags: Vec<LinkTag>,
+) -> cot::db::Result<HashMap<i32, Vec<String>>> {
+    let tag_names = tag_names(db).await?;
+
+    let mut tags_by_link: HashMap<i32, Vec<String>> = HashMap::new();
+    for link_tag in link_tags {
+        let (Auto::Fixed(link_id), Auto::Fixed(tag_id)) =
+            (link_tag.link.primary_key(), link_tag.tag.primary_key())
+        else {
+            continue;
+        };
+        if let Some(name) = tag_names.get(tag_id) {
+            tags_by_link.entry(*link_id).or_default().push(name.clone());
+        }
+    }
+    for names in tags_by_link.values_mut() {
+        names.sort();
+    }
+
+    Ok(tags_by_link)
+}
+
+/// Returns the names of all the tags by their IDs.
+async fn tag_names<DB: DatabaseBackend>(db: &DB) -> cot::db::Result<HashMap<i32, String>> {
+    Ok(Tag::objects()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|tag| match tag.id {
+            Auto::Fixed(id) => Some((id, tag.name)),
+            Auto::Auto => None,
+        })
+        .collect())
+}
+
+/// Returns the names of the tags assigned to any link, sorted.
+///
+/// The tags are not removed along with their last link, so the unused ones
+/// are skipped.
+pub async fn used_tag_names<DB: DatabaseBackend>(db: &DB) -> cot::db::Result<Vec<String>> {
+    let mut tag_names = tag_names(db).await?;
+    let mut names: Vec<String> = LinkTag::objects()
+        .all(db)
+        .await?
+        .iter()
+        .filter_map(|link_tag| match link_tag.tag.primary_key() {
+            Auto::Fixed(id) => tag_names.remove(id),
+            Auto::Auto => None,
+        })
+        .collect();
+
+    names.sort();
+    Ok(names)
+}
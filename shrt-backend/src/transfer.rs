@@ -0,0 +1,332 @@
+//! Export of the links to files and parsing of the imported ones.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use cot::db::query::Query;
+use cot::db::{Auto, Database, query};
+use futures::{Stream, StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use shrt_common::enums::EnumName;
+use shrt_common::links::QueryPassthrough;
+use shrt_common::tags::split_tags;
+use shrt_common::transfer::LinkFileFormat;
+
+use crate::csv::{self, CsvRow};
+use crate::models::Link;
+use crate::tags;
+
+/// Number of consecutive link IDs to fetch from the database at once when
+/// exporting.
+const EXPORT_CHUNK_SIZE: i32 = 500;
+
+const CSV_COLUMNS: [&str; 15] = [
+    "slug",
+    "url",
+    "created_at",
+    "visits",
+    "expires_at",
+    "fallback_url",
+    "max_visits",
+    "password_hash",
+    "tags",
+    "title",
+    "description",
+    "notes",
+    "redirect_status",
+    "query_passthrough",
+    "prefix",
+];
+
+/// A link, as stored in the exported files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub slug: String,
+    pub url: String,
+    /// Creation time; the time of the import is used if not set
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub visits: i32,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub fallback_url: Option<String>,
+    #[serde(default)]
+    pub max_visits: Option<i32>,
+    /// Argon2 hash of the password required to follow the link, in the PHC
+    /// string format
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    /// Names of the tags; comma-separated in the CSV files
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Title of the link; taken from the target page if not set
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// HTTP status code of the redirect; the default one is used if not set
+    #[serde(default)]
+    pub redirect_status: Option<u16>,
+    #[serde(default)]
+    pub query_passthrough: QueryPassthrough,
+    #[serde(default)]
+    pub prefix: bool,
+}
+
+impl LinkRecord {
+    /// Returns the record of `link`, which has the sorted `tags`.
+    #[must_use]
+    pub fn from_link(link: &Link, tags: Vec<String>) -> Self {
+        Self {
+            slug: link.slug.clone(),
+            url: link.url.clone(),
+            created_at: Some(link.created_at),
+            visits: link.visits,
+            expires_at: link.expires_at,
+            fallback_url: link.fallback_url.clone(),
+            max_visits: link.max_visits,
+            password_hash: link.password_hash.clone(),
+            tags,
+            title: link.title.clone(),
+            description: link.description.clone(),
+            notes: link.notes.clone(),
+            redirect_status: link.redirect_status.map(|_| link.redirect_status().code()),
+            query_passthrough: link.query_passthrough(),
+            prefix: link.is_prefix(),
+        }
+    }
+
+    /// Appends the record to `out` as a single line of a file in `format`.
+    pub fn write(&self, format: LinkFileFormat, out: &mut String) {
+        match format {
+            LinkFileFormat::Csv => {
+                let fields = [
+                    self.slug.clone(),
+                    self.url.clone(),
+                    self.created_at
+                        .map(|created_at| created_at.to_rfc3339())
+                        .unwrap_or_default(),
+                    self.visits.to_string(),
+                    self.expires_at
+                        .map(|expires_at| expires_at.to_rfc3339())
+                        .unwrap_or_default(),
+                    self.fallback_url.clone().unwrap_or_default(),
+                    self.max_visits
+                        .map(|max_visits| max_visits.to_string())
+                        .unwrap_or_default(),
+                    self.password_hash.clone().unwrap_or_default(),
+                    self.tags.join(","),
+                    self.title.clone().unwrap_or_default(),
+                    self.description.clone().unwrap_or_default(),
+                    self.notes.clone().unwrap_or_default(),
+                    self.redirect_status
+                        .map(|status| status.to_string())
+                        .unwrap_or_default(),
+                    self.query_passthrough.name().to_owned(),
+                    self.prefix.to_string(),
+                ];
+                csv::write_row(out, fields.iter().map(String::as_str));
+            }
+            LinkFileFormat::Jsonl => {
+                out.push_str(&serde_json::to_string(self).expect("record is serializable"));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Returns the lines preceding the records in a file in `format`.
+#[must_use]
+pub fn file_header(format: LinkFileFormat) -> String {
+    let mut header = String::new();
+    if format == LinkFileFormat::Csv {
+        csv::write_row(&mut header, CSV_COLUMNS);
+    }
+    header
+}
+
+/// Streams the file with the links in `format`, fetching the links from the
+/// database in chunks; only the links owned by `owner_id` are included if it
+/// is set. The password hashes are left out unless `include_password_hashes`
+/// is set.
+///
+/// The chunks are ranges of link IDs rather than pages, so that every chunk
+/// is found through the primary key and no link is skipped or repeated when
+/// links are added or removed during the export. The tags are read along
+/// with each chunk.
+pub fn export(
+    db: Database,
+    format: LinkFileFormat,
+    owner_id: Option<i64>,
+    include_password_hashes: bool,
+) -> impl Stream<Item = cot::Result<Bytes>> + Send + 'static {
+    let header = stream::once(async move { Ok(Bytes::from(file_header(format))) });
+    let records = stream::try_unfold(Some((db, 0)), move |state| async move {
+        let Some((db, mut last_id)) = state else {
+            return Ok(None);
+        };
+
+        loop {
+            let next_id = last_id.saturating_add(EXPORT_CHUNK_SIZE);
+            let links = links_between(last_id, next_id, owner_id)
+                .all(&db)
+                .await
+                .map_err(cot::Error::internal)?;
+            if links.is_empty() {
+                // The IDs of the removed links leave gaps
+                let more = links_between(next_id, i32::MAX, owner_id)
+                    .exists(&db)
+                    .await
+                    .map_err(cot::Error::internal)?;
+                if !more {
+                    return Ok(None);
+                }
+                last_id = next_id;
+                continue;
+            }
+
+            let mut tags_by_link = tags::tags_of_links_between(&db, last_id, next_id)
+                .await
+                .map_err(cot::Error::internal)?;
+            let mut chunk = String::new();
+            for link in &links {
+                let tags = match link.id {
+                    Auto::Fixed(id) => tags_by_link.remove(&id).unwrap_or_default(),
+                    Auto::Auto => Vec::new(),
+                };
+                let mut record = LinkRecord::from_link(link, tags);
+                if !include_password_hashes {
+                    record.password_hash = None;
+                }
+                record.write(format, &mut chunk);
+            }
+            return Ok(Some((Bytes::from(chunk), Some((db, next_id)))));
+        }
+    });
+
+    header.chain(records)
+}
+
+/// Returns the query for the links with IDs greater than `after` and up to
+/// `until`, owned by `owner_id` if set.
+fn links_between(after: i32, until: i32, owner_id: Option<i64>) -> Query<Link> {
+    let after = Auto::fixed(after);
+    let until = Auto::fixed(until);
+    if owner_id.is_some() {
+        query!(Link, $owner_id == owner_id && $id > after && $id <= until)
+    } else {
+        query!(Link, $id > after && $id <= until)
+    }
+}
+
+/// A row of an imported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRow {
+    /// Number of the line the row starts at, or of the item for the formats
+    /// that are not line-based, counting from 1
+    pub line: u64,
+    /// The link, or the reason why the row is invalid
+    pub record: Result<LinkRecord, String>,
+}
+
+/// Parses a file in `format` with the exported links.
+///
+/// An error is returned if the file as a whole cannot be read; the invalid
+/// rows are reported in [`ImportedRow::record`] instead.
+pub fn parse_records(format: LinkFileFormat, data: &str) -> Result<Vec<ImportedRow>, String> {
+    match format {
+        LinkFileFormat::Csv => parse_csv(data),
+        LinkFileFormat::Jsonl => Ok(data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| ImportedRow {
+                line: i as u64 + 1,
+                record: serde_json::from_str(line).map_err(|e| e.to_string()),
+            })
+            .collect()),
+    }
+}
+
+fn parse_csv(data: &str) -> Result<Vec<ImportedRow>, String> {
+    let rows = csv::parse(data).map_err(|e| e.to_string())?;
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(Vec::new());
+    };
+
+    let columns = CSV_COLUMNS.map(|column| csv::column_index(header, column));
+    for (column, index) in CSV_COLUMNS.iter().zip(&columns).take(2) {
+        if index.is_none() {
+            return Err(format!("The file has no {column} column"));
+        }
+    }
+
+    Ok(rows
+        .iter()
+        .map(|row| ImportedRow {
+            line: row.line,
+            record: parse_csv_record(row, &columns),
+        })
+        .collect())
+}
+
+fn parse_csv_record(row: &CsvRow, columns: &[Option<usize>; 15]) -> Result<LinkRecord, String> {
+    let field = |i: usize| {
+        columns[i]
+            .map(|index| row.get(index).trim())
+            .filter(|value| !value.is_empty())
+    };
+    let required = |i: usize| {
+        field(i)
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| format!("{} must not be empty", CSV_COLUMNS[i]))
+    };
+    let parse_date = |i: usize| {
+        field(i)
+            .map(|value| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|date| date.with_timezone(&Utc))
+                    .map_err(|e| format!("Invalid {}: {e}", CSV_COLUMNS[i]))
+            })
+            .transpose()
+    };
+    let parse_number = |i: usize| {
+        field(i)
+            .map(|value| {
+                value
+                    .parse::<i32>()
+                    .map_err(|e| format!("Invalid {}: {e}", CSV_COLUMNS[i]))
+            })
+            .transpose()
+    };
+    let invalid = |i: usize, value: &str| format!("Invalid {}: {value}", CSV_COLUMNS[i]);
+
+    Ok(LinkRecord {
+        slug: required(0)?,
+        url: required(1)?,
+        created_at: parse_date(2)?,
+        visits: parse_number(3)?.unwrap_or(0),
+        expires_at: parse_date(4)?,
+        fallback_url: field(5).map(ToOwned::to_owned),
+        max_visits: parse_number(6)?,
+        password_hash: field(7).map(ToOwned::to_owned),
+        tags: field(8).map(split_tags).unwrap_or_default(),
+        title: field(9).map(ToOwned::to_owned),
+        description: field(10).map(ToOwned::to_owned),
+        notes: field(11).map(ToOwned::to_owned),
+        redirect_status: field(12)
+            .map(|value| value.parse().map_err(|_| invalid(12, value)))
+            .transpose()?,
+        query_passthrough: field(13)
+            .map(|value| QueryPassthrough::from_name(value).ok_or_else(|| invalid(13, value)))
+            .transpose()?
+            .unwrap_or_default(),
+        prefix: field(14)
+            .map(|value| value.parse().map_err(|_| invalid(14, value)))
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
@@ -0,0 +1,140 @@
+use std::fmt::{Display, Formatter};
+
+use shrt_common::config::AppConfig;
+use url::{Host, Url};
+
+/// Reasons for a target URL to be rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlPolicyViolation {
+    /// The URL could not be parsed
+    Invalid(url::ParseError),
+    /// The scheme of the URL is not on the allowlist
+    SchemeNotAllowed(String),
+    /// The host of the URL is on the blocklist
+    BlockedDomain(String),
+    /// The URL points back to the shortener, which would cause a redirect
+    /// loop
+    SelfReference,
+}
+
+impl Display for UrlPolicyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlPolicyViolation::Invalid(e) => write!(f, "URL is invalid: {e}"),
+            UrlPolicyViolation::SchemeNotAllowed(scheme) => {
+                write!(f, "URLs with the {scheme}: scheme are not allowed")
+            }
+            UrlPolicyViolation::BlockedDomain(domain) => {
+                write!(f, "Links to {domain} are not allowed")
+            }
+            UrlPolicyViolation::SelfReference => {
+                write!(f, "Links must not point back to the link shortener")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UrlPolicyViolation {}
+
+/// Rules the target URLs of the links have to follow.
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    allowed_schemes: Vec<String>,
+    blocked_domains: Vec<String>,
+    base_url: Option<Url>,
+}
+
+impl UrlPolicy {
+    /// Creates a policy allowing the `allowed_schemes`, rejecting the
+    /// `blocked_domains` and the URLs pointing to the host of `base_url`.
+    #[must_use]
+    pub fn new(allowed_schemes: &[String], blocked_domains: &[String], base_url: &str) -> Self {
+        Self {
+            allowed_schemes: allowed_schemes
+                .iter()
+                .map(|scheme| scheme.to_ascii_lowercase())
+                .collect(),
+            blocked_domains: blocked_domains
+                .iter()
+                .filter_map(|domain| normalize_domain(domain))
+                .collect(),
+            base_url: Url::parse(base_url).ok(),
+        }
+    }
+
+    #[must_use]
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            &config.allowed_schemes,
+            &config.blocked_domains,
+            &config.base_url,
+        )
+    }
+
+    /// Parses `url` and checks it against the policy.
+    pub fn check(&self, url: &str) -> Result<Url, UrlPolicyViolation> {
+        let parsed = Url::parse(url.trim()).map_err(UrlPolicyViolation::Invalid)?;
+
+        if !self
+            .allowed_schemes
+            .iter()
+            .any(|scheme| scheme == parsed.scheme())
+        {
+            return Err(UrlPolicyViolation::SchemeNotAllowed(
+                parsed.scheme().to_owned(),
+            ));
+        }
+
+        if let Some(host) = parsed.host_str() {
+            let host = host.trim_end_matches('.');
+            if let Some(blocked) = self
+                .blocked_domains
+                .iter()
+                .find(|blocked| is_same_or_subdomain(host, blocked))
+            {
+                return Err(UrlPolicyViolation::BlockedDomain(blocked.clone()));
+            }
+        }
+
+        if self
+            .base_url
+            .as_ref()
+            .is_some_and(|base_url| is_same_origin_host(&parsed, base_url))
+        {
+            return Err(UrlPolicyViolation::SelfReference);
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Converts `domain` to the ASCII form used in parsed URLs.
+fn normalize_domain(domain: &str) -> Option<String> {
+    match Host::parse(domain.trim().trim_end_matches('.')).ok()? {
+        Host::Domain(domain) => Some(domain),
+        Host::Ipv4(ip) => Some(ip.to_string()),
+        Host::Ipv6(ip) => Some(format!("[{ip}]")),
+    }
+}
+
+fn is_same_or_subdomain(domain: &str, parent: &str) -> bool {
+    domain == parent
+        || domain
+            .strip_suffix(parent)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Returns whether `url` points to the same server as `base_url`.
+///
+/// Different schemes with their default ports are treated as the same server,
+/// as they usually redirect to each other.
+fn is_same_origin_host(url: &Url, base_url: &Url) -> bool {
+    let (Some(host), Some(base_host)) = (url.host_str(), base_url.host_str()) else {
+        return false;
+    };
+    if host.trim_end_matches('.') != base_host.trim_end_matches('.') {
+        return false;
+    }
+
+    url.port() == base_url.port() || url.port_or_known_default() == base_url.port_or_known_default()
+}
@@ -1,6 +1,17 @@
+mod common;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use common::{ADMIN, TestProject, signed_in_client, stored_visits};
 use cot::test::TestServerBuilder;
 use shrt_backend::ShrtProject;
-use shrt_common::links::{Link, LinkCreateRequest, LinkExists};
+use shrt_common::errors::ServiceError;
+use shrt_common::links::{
+    BatchResponse, Link, LinkBatchCreateRequest, LinkBatchSlugsRequest, LinkCreateRequest,
+    LinkExists, LinkUpdateRequest, LinksCursorResponse, LinksResponse, QueryPassthrough,
+};
+use shrt_common::stats::{LinkStats, StatsInterval, TopEntry};
+use shrt_common::tokens::{ApiToken, ApiTokenCreateRequest, ApiTokenCreated, TokenScope};
+use shrt_common::transfer::{DuplicateStrategy, LinkImportReport, LinkImportRequest};
 
 #[cot::e2e_test]
 async fn test_create_and_get_link() -> cot::Result<()> {
@@ -12,6 +23,7 @@ async fn test_create_and_get_link() -> cot::Result<()> {
     let create_request = LinkCreateRequest {
         slug: Some("test-slug".to_string()),
         url: "https://example.com".to_string(),
+        ..Default::default()
     };
 
     let response = client
@@ -24,7 +36,7 @@ async fn test_create_and_get_link() -> cot::Result<()> {
     assert_eq!(response.status(), reqwest::StatusCode::OK);
     let link: Link = response.json().await.expect("Failed to deserialize link");
     assert_eq!(link.slug, "test-slug");
-    assert_eq!(link.url, "https://example.com");
+    assert_eq!(link.url.as_deref(), Some("https://example.com/"));
 
     // Get link
     let response = client
@@ -60,6 +72,7 @@ async fn test_link_exists() -> cot::Result<()> {
     let create_request = LinkCreateRequest {
         slug: Some("existing".to_string()),
         url: "https://example.com".to_string(),
+        ..Default::default()
     };
     client
         .post(format!("{url}/links"))
@@ -81,3 +94,2233 @@ async fn test_link_exists() -> cot::Result<()> {
     server.close().await;
     Ok(())
 }
+
+#[cot::e2e_test]
+async fn test_expired_link() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    for (slug, fallback_url) in [
+        ("short-lived", None),
+        (
+            "with-fallback",
+            Some("https://example.org/over".to_string()),
+        ),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: "https://example.com".to_string(),
+            expires_in: Some(1),
+            fallback_url,
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let link: Link = response.json().await.unwrap();
+        assert!(link.expires_at.is_some());
+        assert!(!link.expired);
+    }
+
+    // Redirects before expiration
+    let response = client
+        .get(format!("{url}/links/short-lived/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(response.headers()["location"], "https://example.com/");
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    // Gone after expiration
+    let response = client
+        .get(format!("{url}/links/short-lived/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::GONE);
+
+    // Falls back to the fallback URL if one is set
+    let response = client
+        .get(format!("{url}/links/with-fallback/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(response.headers()["location"], "https://example.org/over");
+
+    // Listed as expired
+    let response = client
+        .get(format!("{url}/links"))
+        .send()
+        .await
+        .expect("Request failed");
+    let links: LinksResponse = response.json().await.unwrap();
+    assert!(links.links.iter().all(|link| link.expired));
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_redirect_status() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    for (slug, redirect_status, max_visits) in [
+        ("default-redirect", None, None),
+        ("moved", Some(301), None),
+        ("temporary", Some(307), None),
+        ("permanent-limited", Some(308), Some(10)),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: "https://example.com".to_string(),
+            redirect_status,
+            max_visits,
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let link: Link = response.json().await.unwrap();
+        assert_eq!(link.redirect_status, redirect_status.unwrap_or(302));
+    }
+
+    for (slug, status, cache_control) in [
+        ("default-redirect", 302, "no-store"),
+        ("moved", 301, "public, max-age=86400"),
+        ("temporary", 307, "no-store"),
+        // Cached redirects would not count towards the limit of visits
+        ("permanent-limited", 308, "no-store"),
+    ] {
+        let response = client
+            .get(format!("{url}/links/{slug}/go"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status().as_u16(), status, "{slug}");
+        assert_eq!(response.headers()["location"], "https://example.com/");
+        assert_eq!(response.headers()["cache-control"], cache_control, "{slug}");
+    }
+
+    let create_request = LinkCreateRequest {
+        url: "https://example.com".to_string(),
+        redirect_status: Some(303),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let error: ServiceError = response.json().await.unwrap();
+    assert_eq!(error.error, "Invalid redirect status");
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_query_passthrough() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    for (slug, query_passthrough) in [
+        ("campaign", QueryPassthrough::VisitorWins),
+        ("no-passthrough", QueryPassthrough::Off),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: "https://example.com/?utm_source=site&ref=1#top".to_string(),
+            query_passthrough,
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let link: Link = response.json().await.unwrap();
+        assert_eq!(link.query_passthrough, query_passthrough);
+    }
+
+    let response = client
+        .get(format!(
+            "{url}/links/campaign/go?utm_source=newsletter&utm_campaign=spring%20sale"
+        ))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(
+        response.headers()["location"],
+        "https://example.com/?ref=1&utm_source=newsletter&utm_campaign=spring%20sale#top"
+    );
+
+    let response = client
+        .get(format!(
+            "{url}/links/no-passthrough/go?utm_source=newsletter"
+        ))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(
+        response.headers()["location"],
+        "https://example.com/?utm_source=site&ref=1#top"
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_prefix_link() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    for (slug, prefix) in [("docs", true), ("single", false)] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: "https://docs.example.com/v2?lang=en".to_string(),
+            prefix,
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let link: Link = response.json().await.unwrap();
+        assert_eq!(link.prefix, prefix);
+    }
+
+    for (path, location) in [
+        ("docs/go", "https://docs.example.com/v2?lang=en"),
+        (
+            "docs/go/getting-started",
+            "https://docs.example.com/v2/getting-started?lang=en",
+        ),
+        (
+            "docs/go/guide/caf%C3%A9%3Fx/",
+            "https://docs.example.com/v2/guide/caf%C3%A9%3Fx/?lang=en",
+        ),
+    ] {
+        let response = client
+            .get(format!("{url}/links/{path}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert!(response.status().is_redirection(), "{path}");
+        assert_eq!(response.headers()["location"], location);
+    }
+
+    let response = client
+        .get(format!("{url}/links/single/go/getting-started"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // The visits with a path following the slug are counted as well
+    let response = client
+        .get(format!("{url}/links/docs"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.visits, 3);
+
+    // Password protected prefix link passing the query through
+    let create_request = LinkCreateRequest {
+        slug: Some("private-docs".to_string()),
+        url: "https://docs.example.com/v2?lang=en".to_string(),
+        password: Some("hunter2".to_string()),
+        query_passthrough: QueryPassthrough::VisitorWins,
+        prefix: true,
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let session_client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let visit_url = format!("{url}/links/private-docs/go/guide/install?ref=mail");
+    let response = session_client
+        .get(&visit_url)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("<form method=\"post\">"));
+    assert!(!body.contains("docs.example.com"));
+
+    // The form is sent to the URL it was shown at, keeping the subpath
+    let response = session_client
+        .post(&visit_url)
+        .form(&[("password", "hunter2")])
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/guide/install?lang=en&ref=mail"
+    );
+
+    // Other subpaths stay unlocked for the rest of the session
+    let response = session_client
+        .get(format!("{url}/links/private-docs/go/faq?lang=de"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(
+        response.headers()["location"],
+        "https://docs.example.com/v2/faq?lang=de"
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_create_link_invalid_expiration() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let create_request = LinkCreateRequest {
+        url: "https://example.com".to_string(),
+        expires_at: Some(chrono::Utc::now() - chrono::TimeDelta::hours(1)),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_one_time_link() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("one-time".to_string()),
+        url: "https://example.com".to_string(),
+        max_visits: Some(1),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Only one of the concurrent visits is redirected
+    let requests = (0..10).map(|_| client.get(format!("{url}/links/one-time/go")).send());
+    let responses = futures::future::join_all(requests).await;
+    let statuses: Vec<_> = responses
+        .into_iter()
+        .map(|response| response.expect("Request failed").status())
+        .collect();
+    assert_eq!(
+        statuses.iter().filter(|s| s.is_redirection()).count(),
+        1,
+        "{statuses:?}"
+    );
+    assert!(
+        statuses
+            .iter()
+            .all(|s| s.is_redirection() || *s == reqwest::StatusCode::GONE)
+    );
+
+    let response = client
+        .get(format!("{url}/links/one-time"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.visits, 1);
+    assert!(link.exhausted);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_password_protected_link() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .cookie_store(true)
+        .build()
+        .unwrap();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("secret".to_string()),
+        url: "https://example.com/secret".to_string(),
+        password: Some("hunter2".to_string()),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Target is not revealed before unlocking
+    let response = client
+        .get(format!("{url}/links/secret"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert!(link.password_protected);
+    assert_eq!(link.url, None);
+
+    // Visiting shows the password form instead of redirecting
+    let response = client
+        .get(format!("{url}/links/secret/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("<form"));
+    assert!(!body.contains("https://example.com/secret"));
+
+    // Invalid password
+    let response = client
+        .post(format!("{url}/links/secret/go"))
+        .form(&[("password", "hunter3")])
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Valid password
+    let response = client
+        .post(format!("{url}/links/secret/go"))
+        .form(&[("password", "hunter2")])
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(response.headers()["location"], "https://example.com/secret");
+
+    // The link stays unlocked for the rest of the session
+    let response = client
+        .get(format!("{url}/links/secret"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.url.as_deref(), Some("https://example.com/secret"));
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_preview() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    for create_request in [
+        LinkCreateRequest {
+            slug: Some("preview".to_string()),
+            url: "https://example.com/some/page?a=1&b=2".to_string(),
+            title: Some("<Example>".to_string()),
+            ..Default::default()
+        },
+        LinkCreateRequest {
+            slug: Some("secret-preview".to_string()),
+            url: "https://example.com/secret".to_string(),
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        },
+    ] {
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let response = client
+        .get(format!("{url}/links/preview/preview"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        "text/html; charset=utf-8"
+    );
+    let page = response.text().await.unwrap();
+    assert!(page.contains("https://example.com/some/page?a=1&amp;b=2"));
+    assert!(page.contains("<strong>example.com</strong>"));
+    assert!(page.contains("&lt;Example&gt;"));
+    assert!(page.contains("href=\"go\""));
+
+    // Previewing does not count as a visit
+    let response = client
+        .get(format!("{url}/links/preview"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.visits, 0);
+
+    let response = client
+        .get(format!("{url}/links/secret-preview/preview"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let page = response.text().await.unwrap();
+    assert!(!page.contains("https://example.com/secret"));
+    assert!(page.contains("password protected"));
+
+    let response = client
+        .get(format!("{url}/links/missing/preview"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_preview_expired_password_protected() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("expired-secret".to_string()),
+        url: "https://example.com/secret".to_string(),
+        expires_in: Some(3600),
+        fallback_url: Some("https://example.com/fallback".to_string()),
+        password: Some("hunter2".to_string()),
+        title: Some("Secret launch plans".to_string()),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = client
+        .post(format!("{url}/test/links/expired-secret/expire"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    let response = client
+        .get(format!("{url}/links/expired-secret/preview"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let page = response.text().await.unwrap();
+    assert!(!page.contains("https://example.com/fallback"));
+    assert!(!page.contains("Secret launch plans"));
+    assert!(page.contains("password protected"));
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_stats() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let owner = signed_in_client(&url, "alice").await;
+
+    let create_request = LinkCreateRequest {
+        slug: Some("tracked".to_string()),
+        url: "https://example.com".to_string(),
+        ..Default::default()
+    };
+    owner
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+
+    for _ in 0..2 {
+        let response = client
+            .get(format!("{url}/links/tracked/go"))
+            .header(
+                "User-Agent",
+                "Mozilla/5.0 (X11; Linux x86_64; rv:140.0) Gecko/20100101 Firefox/140.0",
+            )
+            .header("Referer", "https://news.example.org/article")
+            .header("Accept-Language", "en-GB,en;q=0.9")
+            .send()
+            .await
+            .expect("Request failed");
+        assert!(response.status().is_redirection());
+    }
+
+    // Only the owner can see the statistics
+    let response = client
+        .get(format!("{url}/links/tracked/stats"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let response = signed_in_client(&url, "bob")
+        .await
+        .get(format!("{url}/links/tracked/stats"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let response = owner
+        .get(format!("{url}/links/tracked/stats?interval=hour"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let stats: LinkStats = response.json().await.unwrap();
+    assert_eq!(stats.interval, StatsInterval::Hour);
+    assert_eq!(stats.total_visits, 2);
+    assert_eq!(stats.visits.last().unwrap().visits, 2);
+    let expected = vec![TopEntry {
+        name: "news.example.org".to_string(),
+        visits: 2,
+    }];
+    assert_eq!(stats.top_referrers, expected);
+    assert_eq!(stats.top_browsers[0].name, "Firefox");
+    assert_eq!(stats.top_language_regions[0].name, "GB");
+
+    // Invalid range
+    let response = owner
+        .get(format!(
+            "{url}/links/tracked/stats?interval=hour&from=2020-01-01T00:00:00Z"
+        ))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_visit_records() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("recorded".to_string()),
+        url: "https://example.com".to_string(),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let before = Utc::now();
+    let response = client
+        .get(format!("{url}/links/recorded/go"))
+        .header("Referer", "https://news.example.org/article")
+        .header("User-Agent", "curl/8.0")
+        .header("Accept-Language", "pl-PL,pl;q=0.9")
+        .header("X-Forwarded-For", "198.51.100.77")
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    let after = Utc::now();
+
+    let visits = stored_visits(&url, "recorded").await;
+    assert_eq!(visits.len(), 1);
+    let visit = &visits[0];
+    assert!(before <= visit.visited_at && visit.visited_at <= after);
+    assert_eq!(
+        visit.referrer.as_deref(),
+        Some("https://news.example.org/article")
+    );
+    assert_eq!(visit.user_agent.as_deref(), Some("curl/8.0"));
+    assert_eq!(visit.accept_language.as_deref(), Some("pl-PL,pl;q=0.9"));
+    // Only the network of the visitor is stored
+    assert_eq!(visit.client_ip.as_deref(), Some("198.51.100.0"));
+
+    // The counter is kept in sync with the records
+    let response = client
+        .get(format!("{url}/links/recorded"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.visits, 1);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_update_link() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+
+    let create_request = LinkCreateRequest {
+        slug: Some("editable".to_string()),
+        url: "https://example.com".to_string(),
+        max_visits: Some(5),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    let original: Link = response.json().await.unwrap();
+    client
+        .get(format!("{url}/links/editable/go"))
+        .send()
+        .await
+        .expect("Request failed");
+
+    let update_request = LinkUpdateRequest {
+        url: Some("https://example.org".to_string()),
+        max_visits: Some(None),
+        ..Default::default()
+    };
+    let response = client
+        .patch(format!("{url}/links/editable"))
+        .header("If-Match", &original.etag)
+        .json(&update_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let etag = response.headers()["ETag"].to_str().unwrap().to_owned();
+    let updated: Link = response.json().await.unwrap();
+    assert_eq!(updated.url.as_deref(), Some("https://example.org/"));
+    assert_eq!(updated.max_visits, None);
+    assert_eq!(updated.created_at, original.created_at);
+    assert_ne!(updated.etag, original.etag);
+    assert_eq!(etag, updated.etag);
+
+    // Updating with a stale entity tag fails
+    let response = client
+        .patch(format!("{url}/links/editable"))
+        .header("If-Match", &original.etag)
+        .json(&update_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::PRECONDITION_FAILED);
+
+    let response = client
+        .get(format!("{url}/links/editable/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.headers()["location"], "https://example.org/");
+
+    // The visits are not overwritten by the update
+    let response = client
+        .get(format!("{url}/links/editable"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.visits, 2);
+    assert_eq!(link.etag, updated.etag);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_manage_link_requires_owner() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let anonymous = reqwest::Client::new();
+    let owner = signed_in_client(&url, "alice").await;
+    let other_user = signed_in_client(&url, "bob").await;
+    let admin = signed_in_client(&url, ADMIN).await;
+
+    let create_request = LinkCreateRequest {
+        slug: Some("owned".to_string()),
+        url: "https://example.com".to_string(),
+        notes: Some("Private".to_string()),
+        ..Default::default()
+    };
+    let response = owner
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+
+    // Anonymous users can neither edit nor remove links, and other users are
+    // not allowed to
+    let update_request = LinkUpdateRequest {
+        url: Some("https://example.org".to_string()),
+        ..Default::default()
+    };
+    for (client, status) in [
+        (&anonymous, reqwest::StatusCode::UNAUTHORIZED),
+        (&other_user, reqwest::StatusCode::FORBIDDEN),
+    ] {
+        let response = client
+            .patch(format!("{url}/links/owned"))
+            .header("If-Match", &link.etag)
+            .json(&update_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), status);
+
+        let response = client
+            .delete(format!("{url}/links/owned"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), status);
+
+        let response = client
+            .get(format!("{url}/links/owned"))
+            .send()
+            .await
+            .expect("Request failed");
+        let unchanged: Link = response.json().await.unwrap();
+        assert_eq!(unchanged.url.as_deref(), Some("https://example.com/"));
+        assert_eq!(unchanged.notes, None);
+    }
+
+    // The owner sees the notes; the admins can edit the links of everyone
+    let response = owner
+        .get(format!("{url}/links/owned"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.notes.as_deref(), Some("Private"));
+
+    let response = admin
+        .patch(format!("{url}/links/owned"))
+        .header("If-Match", &link.etag)
+        .json(&update_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.url.as_deref(), Some("https://example.org/"));
+
+    let response = owner
+        .delete(format!("{url}/links/owned"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    let response = owner
+        .get(format!("{url}/links/owned"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // The admins can remove the links of everyone as well
+    let response = owner
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = admin
+        .delete(format!("{url}/links/owned"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_list_own_links() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let anonymous = reqwest::Client::new();
+
+    for (client, slug) in [
+        (&signed_in_client(&url, "alice").await, "alice-1"),
+        (&signed_in_client(&url, "alice").await, "alice-2"),
+        (&signed_in_client(&url, "bob").await, "bob-1"),
+        (&anonymous, "anonymous-1"),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: format!("https://example.com/{slug}"),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    // Listing own links requires signing in
+    let response = anonymous
+        .get(format!("{url}/links?mine=true"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let client = signed_in_client(&url, "alice").await;
+    let response = client
+        .get(format!("{url}/links?mine=true"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let links: LinksResponse = response.json().await.unwrap();
+    let mut slugs: Vec<&str> = links.links.iter().map(|link| link.slug.as_str()).collect();
+    slugs.sort_unstable();
+    assert_eq!(slugs, ["alice-1", "alice-2"]);
+
+    let response = client
+        .get(format!("{url}/links/cursor?mine=true"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let links: LinksCursorResponse = response.json().await.unwrap();
+    assert_eq!(links.links.len(), 2);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_api_tokens_require_authentication() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{url}/tokens"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let create_request = ApiTokenCreateRequest {
+        name: "script".to_string(),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/tokens"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Unknown tokens are rejected rather than treated as anonymous requests
+    let response = client
+        .get(format!("{url}/links"))
+        .bearer_auth("shrt_invalid")
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers()["WWW-Authenticate"],
+        "Bearer error=\"invalid_token\""
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_api_tokens() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+    let anonymous = reqwest::Client::new();
+
+    let create_token = async |scope: TokenScope, expires_at: Option<DateTime<Utc>>| {
+        let response = client
+            .post(format!("{url}/tokens"))
+            .json(&ApiTokenCreateRequest {
+                name: "script".to_string(),
+                scope,
+                expires_at,
+            })
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        response.json::<ApiTokenCreated>().await.unwrap()
+    };
+    let read_write = create_token(TokenScope::ReadWrite, None).await;
+    let read = create_token(TokenScope::Read, None).await;
+    let expiring = create_token(
+        TokenScope::ReadWrite,
+        Some(Utc::now() + TimeDelta::seconds(1)),
+    )
+    .await;
+
+    // The links are created on behalf of the owner of the token
+    let create_request = LinkCreateRequest {
+        slug: Some("scripted".to_string()),
+        url: "https://example.com".to_string(),
+        ..Default::default()
+    };
+    let response = anonymous
+        .post(format!("{url}/links"))
+        .bearer_auth(&read_write.secret)
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let link: Link = response.json().await.unwrap();
+    let response = client
+        .get(format!("{url}/links?mine=true"))
+        .send()
+        .await
+        .expect("Request failed");
+    let links: LinksResponse = response.json().await.unwrap();
+    assert_eq!(links.links[0].slug, "scripted");
+
+    // Tokens cannot create other tokens
+    let response = anonymous
+        .post(format!("{url}/tokens"))
+        .bearer_auth(&read_write.secret)
+        .json(&ApiTokenCreateRequest {
+            name: "another".to_string(),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Read-only tokens can only read
+    let response = anonymous
+        .get(format!("{url}/links/scripted"))
+        .bearer_auth(&read.secret)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let requests = [
+        anonymous.post(format!("{url}/links")).json(&create_request),
+        anonymous
+            .patch(format!("{url}/links/scripted"))
+            .header("If-Match", &link.etag)
+            .json(&LinkUpdateRequest::default()),
+        anonymous.delete(format!("{url}/links/scripted")),
+    ];
+    for request in requests {
+        let response = request
+            .bearer_auth(&read.secret)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()["WWW-Authenticate"],
+            "Bearer error=\"insufficient_scope\""
+        );
+    }
+
+    // Expired tokens are rejected
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let response = anonymous
+        .get(format!("{url}/links"))
+        .bearer_auth(&expiring.secret)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Revoked tokens are rejected
+    let response = client
+        .delete(format!("{url}/tokens/{}", read_write.token.id))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    let response = anonymous
+        .get(format!("{url}/links"))
+        .bearer_auth(&read_write.secret)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Using a token records its last use, without reverting the revocation
+    let response = client
+        .get(format!("{url}/tokens"))
+        .send()
+        .await
+        .expect("Request failed");
+    let tokens: Vec<ApiToken> = response.json().await.unwrap();
+    assert_eq!(tokens.len(), 3);
+    let revoked = tokens
+        .iter()
+        .find(|token| token.id == read_write.token.id)
+        .unwrap();
+    assert!(revoked.revoked_at.is_some());
+    assert!(revoked.last_used_at.is_some());
+    let unused = tokens
+        .iter()
+        .find(|token| token.id == expiring.token.id)
+        .unwrap();
+    assert!(unused.last_used_at.is_none());
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_rate_limit_not_found() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    // Default budget of 30 lookups of missing links per minute
+    for i in 0..30 {
+        let suffix = ["/go", "", "/qr", "/preview", "/go/sub/path"][i % 5];
+        let response = client
+            .get(format!("{url}/links/missing-{i}{suffix}"))
+            .header("X-Forwarded-For", "198.51.100.1")
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    let response = client
+        .get(format!("{url}/links/missing/go"))
+        .header("X-Forwarded-For", "198.51.100.1")
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=2).contains(&retry_after));
+    let error: ServiceError = response.json().await.unwrap();
+    assert_eq!(error.error, "Too many requests");
+
+    // Other clients are not affected
+    let response = client
+        .get(format!("{url}/links/missing/go"))
+        .header("X-Forwarded-For", "198.51.100.2")
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_rate_limit_link_password() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("secret".to_string()),
+        url: "https://example.com".to_string(),
+        password: Some("correct horse".to_string()),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let unlock = async |password: &str| {
+        client
+            .post(format!("{url}/links/secret/go"))
+            .header("X-Forwarded-For", "198.51.100.1")
+            .form(&[("password", password)])
+            .send()
+            .await
+            .expect("Request failed")
+            .status()
+    };
+    // Default budget of 10 wrong passwords per minute
+    for _ in 0..10 {
+        assert_eq!(unlock("wrong").await, reqwest::StatusCode::FORBIDDEN);
+    }
+    assert_eq!(
+        unlock("correct horse").await,
+        reqwest::StatusCode::TOO_MANY_REQUESTS
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_rate_limit_batches() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+
+    let create_batch = async |prefix: &str, size: usize| {
+        let links = (0..size)
+            .map(|i| LinkCreateRequest {
+                slug: Some(format!("{prefix}-{i}")),
+                url: "https://example.com".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        client
+            .post(format!("{url}/links/batch"))
+            .json(&LinkBatchCreateRequest { links })
+            .send()
+            .await
+            .expect("Request failed")
+    };
+
+    // Default budget of 30 created links per minute
+    let response = create_batch("too-many", 31).await;
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+    let response = create_batch("first", 25).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // A batch over the remaining budget is rejected as a whole
+    let response = create_batch("second", 10).await;
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("Retry-After"));
+    let response = client
+        .get(format!("{url}/links/second-0"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let data = (0..5).fold("slug,url\n".to_string(), |data, i| {
+        data + &format!("imported-{i},https://example.com\n")
+    });
+    let response = client
+        .post(format!("{url}/links/import"))
+        .json(&LinkImportRequest {
+            data,
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&LinkCreateRequest {
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_create_link_unsafe_url() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    for (target, expected_error) in [
+        ("javascript:alert(1)", "URL scheme not allowed"),
+        ("not a url", "Invalid URL"),
+        ("http://localhost:8000/loop", "Redirect loop"),
+    ] {
+        let create_request = LinkCreateRequest {
+            url: target.to_string(),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let error: ServiceError = response.json().await.unwrap();
+        assert_eq!(error.error, expected_error);
+    }
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_slug_policy() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{url}/links/swagger/exists"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let exists: LinkExists = response.json().await.unwrap();
+    assert!(!exists.exists);
+    assert_eq!(exists.invalid_reason.as_deref(), Some("Slug is reserved"));
+
+    let response = client
+        .get(format!("{url}/links/valid-slug/exists"))
+        .send()
+        .await
+        .expect("Request failed");
+    let exists: LinkExists = response.json().await.unwrap();
+    assert_eq!(exists.invalid_reason, None);
+
+    for slug in ["swagger", "", "a/b", "with space"] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: "https://example.com".to_string(),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let error: ServiceError = response.json().await.unwrap();
+        assert_eq!(error.error, "Invalid slug");
+    }
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_create_link_reuse_existing() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let create = async |target: &str, reuse_existing: bool| -> Link {
+        let create_request = LinkCreateRequest {
+            url: target.to_string(),
+            reuse_existing,
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        response.json().await.unwrap()
+    };
+
+    let first = create("https://Example.com:443/page?b=2&a=1", false).await;
+    // Canonically equal URL
+    let reused = create("https://example.com/page?a=1&b=2", true).await;
+    assert_eq!(reused.slug, first.slug);
+    // The URL is stored as parsed, but not canonicalized
+    assert_eq!(
+        reused.url.as_deref(),
+        Some("https://example.com/page?b=2&a=1")
+    );
+    // Reuse not requested
+    let new = create("https://example.com/page?a=1&b=2", false).await;
+    assert_ne!(new.slug, first.slug);
+    // Different query
+    let different = create("https://example.com/page?a=1", true).await;
+    assert_ne!(different.slug, first.slug);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_search_and_sort_links() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    for (slug, target, password) in [
+        ("search-a", "https://example.org/needle", None),
+        ("search-b", "https://example.com/Needle", None),
+        ("search-c", "https://example.net/hay", None),
+        ("hidden", "https://example.com/needle", Some("secret")),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: target.to_string(),
+            password: password.map(ToString::to_string),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let list = async |query: &str| -> Vec<String> {
+        let response = client
+            .get(format!("{url}/links?{query}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let links: LinksResponse = response.json().await.unwrap();
+        links.links.into_iter().map(|link| link.slug).collect()
+    };
+
+    // The target URLs of the password protected links are not searched
+    assert_eq!(
+        list("search=NEEDLE&sort=slug&order=desc").await,
+        ["search-b", "search-a"]
+    );
+    assert_eq!(
+        list("search=search&sort=slug&links_per_page=2&page=2").await,
+        ["search-c"]
+    );
+    assert_eq!(list("min_visits=1").await, Vec::<String>::new());
+
+    let response = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+        .get(format!("{url}/links/search-c/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+    assert_eq!(list("min_visits=1").await, ["search-c"]);
+    assert_eq!(
+        list("created_from=2000-01-01T00:00:00Z&created_to=2001-01-01T00:00:00Z").await,
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        list("created_from=2000-01-01T00:00:00Z&min_visits=1&links_per_page=1").await,
+        ["search-c"]
+    );
+
+    // The page size is capped
+    let response = client
+        .get(format!("{url}/links?links_per_page=100000"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let links: LinksResponse = response.json().await.unwrap();
+    assert_eq!(links.links_per_page, 1000);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_tags() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+
+    for (slug, tags) in [
+        ("tagged-a", vec!["Campaign", "news"]),
+        ("tagged-b", vec!["campaign"]),
+        ("untagged", vec![]),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: format!("https://example.com/{slug}"),
+            tags: tags.into_iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let response = client
+        .get(format!("{url}/links/tagged-a"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.tags, ["campaign", "news"]);
+
+    let list = async |query: &str| -> Vec<String> {
+        let response = client
+            .get(format!("{url}/links?sort=slug&{query}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let links: LinksResponse = response.json().await.unwrap();
+        links.links.into_iter().map(|link| link.slug).collect()
+    };
+    assert_eq!(list("tag=CAMPAIGN").await, ["tagged-a", "tagged-b"]);
+    assert_eq!(list("tag=news").await, ["tagged-a"]);
+    assert_eq!(list("tag=missing").await, Vec::<String>::new());
+
+    let response = client
+        .get(format!("{url}/tags"))
+        .send()
+        .await
+        .expect("Request failed");
+    let tags: Vec<String> = response.json().await.unwrap();
+    assert_eq!(tags, ["campaign", "news"]);
+
+    let response = client
+        .get(format!("{url}/links?sort=slug"))
+        .send()
+        .await
+        .expect("Request failed");
+    let links: LinksResponse = response.json().await.unwrap();
+    let tags: Vec<Vec<String>> = links.links.into_iter().map(|link| link.tags).collect();
+    assert_eq!(tags, [vec!["campaign", "news"], vec!["campaign"], vec![]]);
+
+    // Changing only the tags changes the entity tag
+    let update_request = LinkUpdateRequest {
+        tags: Some(vec!["news".to_string()]),
+        ..Default::default()
+    };
+    let response = client
+        .patch(format!("{url}/links/tagged-a"))
+        .header("If-Match", &link.etag)
+        .json(&update_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let updated: Link = response.json().await.unwrap();
+    assert_eq!(updated.tags, ["news"]);
+    assert_ne!(updated.etag, link.etag);
+    let response = client
+        .patch(format!("{url}/links/tagged-a"))
+        .header("If-Match", &link.etag)
+        .json(&update_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::PRECONDITION_FAILED);
+
+    let create_request = LinkCreateRequest {
+        url: "https://example.com/".to_string(),
+        tags: vec!["a,b".to_string()],
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_metadata() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("with-metadata".to_string()),
+        url: "https://example.com/".to_string(),
+        title: Some("  Example  ".to_string()),
+        description: Some(String::new()),
+        notes: Some("Used in the newsletter".to_string()),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!("{url}/links/with-metadata"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.title.as_deref(), Some("Example"));
+    assert_eq!(link.description, None);
+    assert_eq!(link.notes.as_deref(), Some("Used in the newsletter"));
+    assert_eq!(link.favicon_url, None);
+
+    let create_request = LinkCreateRequest {
+        url: "https://example.com/".to_string(),
+        title: Some("a".repeat(201)),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let error: ServiceError = response.json().await.unwrap();
+    assert_eq!(error.error, "Invalid title");
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_link_qr_code() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("poster".to_string()),
+        url: "https://example.com/".to_string(),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = client
+        .get(format!("{url}/links/poster/qr"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        "image/svg+xml"
+    );
+    let svg = response.text().await.unwrap();
+    assert!(svg.contains("width=\"256\" height=\"256\""));
+
+    let response = client
+        .get(format!(
+            "{url}/links/poster/qr?format=png&size=512&margin=1&ecc=h&foreground=%23003366"
+        ))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        "image/png"
+    );
+    let png = response.bytes().await.unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    for (query, expected_error) in [
+        ("size=0", "Invalid size"),
+        ("size=100000", "Invalid size"),
+        ("margin=100", "Invalid margin"),
+        ("foreground=blue", "Invalid foreground colour"),
+        ("foreground=transparent", "Invalid foreground colour"),
+    ] {
+        let response = client
+            .get(format!("{url}/links/poster/qr?{query}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(
+            response.status(),
+            reqwest::StatusCode::BAD_REQUEST,
+            "{query}"
+        );
+        let error: ServiceError = response.json().await.unwrap();
+        assert_eq!(error.error, expected_error);
+    }
+
+    let response = client
+        .get(format!("{url}/links/missing/qr"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_cursor_pagination() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let slugs = ["page-1", "page-2", "page-3", "page-4", "page-5"];
+    for slug in slugs {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: format!("https://example.com/{slug}"),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let list = async |query: &str| -> LinksCursorResponse {
+        let response = client
+            .get(format!("{url}/links/cursor?limit=2&order=asc&{query}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        response.json().await.unwrap()
+    };
+    let page_slugs = |page: &LinksCursorResponse| -> Vec<String> {
+        page.links.iter().map(|link| link.slug.clone()).collect()
+    };
+
+    let first = list("count=true").await;
+    assert_eq!(page_slugs(&first), ["page-1", "page-2"]);
+    assert_eq!(first.prev_cursor, None);
+    assert_eq!(first.total_count, Some(5));
+
+    let second = list(&format!("after={}", first.next_cursor.unwrap())).await;
+    assert_eq!(page_slugs(&second), ["page-3", "page-4"]);
+    assert_eq!(second.total_count, None);
+
+    let third = list(&format!("after={}", second.next_cursor.unwrap())).await;
+    assert_eq!(page_slugs(&third), ["page-5"]);
+    assert_eq!(third.next_cursor, None);
+
+    let previous = list(&format!("before={}", third.prev_cursor.unwrap())).await;
+    assert_eq!(page_slugs(&previous), ["page-3", "page-4"]);
+
+    // The count and the pages take the filters into account
+    let filtered = list("count=true&search=PAGE-").await;
+    assert_eq!(filtered.total_count, Some(5));
+    let filtered = list("count=true&search=page-5").await;
+    assert_eq!(page_slugs(&filtered), ["page-5"]);
+    assert_eq!(filtered.total_count, Some(1));
+    assert_eq!(filtered.next_cursor, None);
+
+    let response = client
+        .get(format!("{url}/links/cursor?after=invalid"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_batch_get_links() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    for slug in ["batch-a", "batch-b"] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: format!("https://example.com/{slug}"),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    let response = client
+        .post(format!("{url}/links/batch-get"))
+        .json(&LinkBatchSlugsRequest {
+            slugs: vec![
+                "batch-b".to_string(),
+                "missing".to_string(),
+                "batch-a".to_string(),
+            ],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let batch: BatchResponse<Link> = response.json().await.unwrap();
+    assert_eq!(batch.results.len(), 3);
+    assert_eq!(batch.results[0].status, 200);
+    assert_eq!(batch.results[0].result.as_ref().unwrap().slug, "batch-b");
+    assert_eq!(batch.results[1].status, 404);
+    assert!(batch.results[1].result.is_none());
+    assert_eq!(
+        batch.results[1].error.as_ref().unwrap().error,
+        "Link not found"
+    );
+    assert_eq!(
+        batch.results[2].result.as_ref().unwrap().url.as_deref(),
+        Some("https://example.com/batch-a")
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_batch_modifications_require_authentication() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/links/batch"))
+        .json(&LinkBatchCreateRequest {
+            links: vec![LinkCreateRequest {
+                url: "https://example.com".to_string(),
+                ..Default::default()
+            }],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("{url}/links/batch-delete"))
+        .json(&LinkBatchSlugsRequest {
+            slugs: vec!["anything".to_string()],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_batch_create_and_remove_links() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+
+    let response = client
+        .post(format!("{url}/links/batch"))
+        .json(&LinkBatchCreateRequest {
+            links: vec![
+                LinkCreateRequest {
+                    slug: Some("batch-a".to_string()),
+                    url: "https://example.com/a".to_string(),
+                    ..Default::default()
+                },
+                LinkCreateRequest {
+                    slug: Some("batch-b".to_string()),
+                    url: "javascript:alert(1)".to_string(),
+                    ..Default::default()
+                },
+                LinkCreateRequest {
+                    slug: Some("batch-c".to_string()),
+                    url: "https://example.com/c".to_string(),
+                    ..Default::default()
+                },
+            ],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let batch: BatchResponse<Link> = response.json().await.unwrap();
+    let statuses: Vec<u16> = batch.results.iter().map(|result| result.status).collect();
+    assert_eq!(statuses, [200, 400, 200]);
+    assert_eq!(batch.results[2].result.as_ref().unwrap().slug, "batch-c");
+
+    // The links of other users are left alone
+    let other_user = signed_in_client(&url, "bob").await;
+    let response = other_user
+        .post(format!("{url}/links/batch-delete"))
+        .json(&LinkBatchSlugsRequest {
+            slugs: vec!["batch-a".to_string()],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    let batch: BatchResponse<String> = response.json().await.unwrap();
+    assert_eq!(batch.results[0].status, 403);
+
+    let response = client
+        .post(format!("{url}/links/batch-delete"))
+        .json(&LinkBatchSlugsRequest {
+            slugs: vec![
+                "batch-a".to_string(),
+                "batch-b".to_string(),
+                "batch-c".to_string(),
+            ],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let batch: BatchResponse<String> = response.json().await.unwrap();
+    let statuses: Vec<u16> = batch.results.iter().map(|result| result.status).collect();
+    assert_eq!(statuses, [204, 404, 204]);
+
+    for slug in ["batch-a", "batch-c"] {
+        let response = client
+            .get(format!("{url}/links/{slug}"))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_batch_too_large() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/links/batch-get"))
+        .json(&LinkBatchSlugsRequest {
+            slugs: vec!["slug".to_string(); 1001],
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_export_import_require_authentication() -> cot::Result<()> {
+    let server = TestServerBuilder::new(ShrtProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{url}/links/export?format=csv"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("{url}/links/import"))
+        .json(&LinkImportRequest {
+            data: "slug,url\nabc,https://example.com\n".to_string(),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_export_password_hashes() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+    let admin = signed_in_client(&url, ADMIN).await;
+
+    let create_request = LinkCreateRequest {
+        slug: Some("protected".to_string()),
+        url: "https://example.com/protected".to_string(),
+        password: Some("hunter2".to_string()),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let export = async |client: &reqwest::Client, query: &str| {
+        let response = client
+            .get(format!("{url}/links/export?format=jsonl{query}"))
+            .send()
+            .await
+            .expect("Request failed");
+        let status = response.status();
+        let record = (status == reqwest::StatusCode::OK).then_some(
+            serde_json::from_str::<serde_json::Value>(&response.text().await.unwrap()).unwrap(),
+        );
+        (status, record)
+    };
+
+    // The hashes are left out by default
+    let (_, record) = export(&client, "").await;
+    assert_eq!(record.unwrap()["password_hash"], serde_json::Value::Null);
+    let (_, record) = export(&admin, "").await;
+    assert_eq!(record.unwrap()["password_hash"], serde_json::Value::Null);
+
+    let (status, _) = export(&client, "&include_password_hashes=true").await;
+    assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+    let (status, record) = export(&admin, "&include_password_hashes=true").await;
+    assert_eq!(status, reqwest::StatusCode::OK);
+    assert!(
+        record.unwrap()["password_hash"]
+            .as_str()
+            .unwrap()
+            .starts_with("$argon2")
+    );
+
+    server.close().await;
+    Ok(())
+}
+
+#[cot::e2e_test]
+async fn test_export_import_links() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = signed_in_client(&url, "alice").await;
+    let other_user = signed_in_client(&url, "bob").await;
+
+    for (client, slug) in [
+        (&client, "alice-1"),
+        (&client, "alice-2"),
+        (&other_user, "bob-1"),
+    ] {
+        let create_request = LinkCreateRequest {
+            slug: Some(slug.to_string()),
+            url: format!("https://example.com/{slug}"),
+            tags: vec!["docs".to_string()],
+            title: Some(format!("Page {slug}")),
+            ..Default::default()
+        };
+        let response = client
+            .post(format!("{url}/links"))
+            .json(&create_request)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    // Only the own links are exported
+    let response = client
+        .get(format!("{url}/links/export?format=jsonl"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let export = response.text().await.unwrap();
+    let mut records: Vec<serde_json::Value> = export
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    records.sort_unstable_by_key(|record| record["slug"].as_str().unwrap().to_owned());
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["slug"], "alice-1");
+    assert_eq!(records[0]["tags"], serde_json::json!(["docs"]));
+    assert_eq!(records[0]["title"], "Page alice-1");
+    assert_eq!(records[1]["slug"], "alice-2");
+
+    let response = client
+        .get(format!("{url}/links/export?format=csv"))
+        .send()
+        .await
+        .expect("Request failed");
+    let export = response.text().await.unwrap();
+    assert!(export.starts_with("slug,url,"));
+    assert_eq!(export.lines().count(), 3);
+
+    // Importing the export of another instance
+    let data = "slug,url,tags\n\
+                alice-1,https://example.com/changed,\"news,docs\"\n\
+                imported,https://example.com/new,\n\
+                bob-1,https://example.com/taken,\n"
+        .to_string();
+    let response = client
+        .post(format!("{url}/links/import"))
+        .json(&LinkImportRequest {
+            data: data.clone(),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: LinkImportReport = response.json().await.unwrap();
+    assert_eq!(
+        (report.created, report.overwritten, report.skipped),
+        (1, 0, 2)
+    );
+
+    let response = client
+        .post(format!("{url}/links/import"))
+        .json(&LinkImportRequest {
+            data,
+            on_duplicate: DuplicateStrategy::Overwrite,
+            ..Default::default()
+        })
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: LinkImportReport = response.json().await.unwrap();
+    // The links of other users cannot be overwritten
+    assert_eq!((report.created, report.overwritten), (0, 2));
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].slug.as_deref(), Some("bob-1"));
+
+    let response = client
+        .get(format!("{url}/links/alice-1"))
+        .send()
+        .await
+        .expect("Request failed");
+    let link: Link = response.json().await.unwrap();
+    assert_eq!(link.url.as_deref(), Some("https://example.com/changed"));
+    assert_eq!(link.tags, ["docs", "news"]);
+    // The title of the previous target is not kept
+    assert_eq!(link.title, None);
+
+    server.close().await;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use shrt_backend::canonical_url::{canonicalize, is_tracking_parameter};
+
+#[test]
+fn test_canonicalize_host_and_port() {
+    assert_eq!(
+        canonicalize("HTTPS://Example.COM:443/Path", false).unwrap(),
+        "https://example.com/Path"
+    );
+    assert_eq!(
+        canonicalize("http://example.com:80", false).unwrap(),
+        "http://example.com/"
+    );
+    assert_eq!(
+        canonicalize("http://example.com:8080/", false).unwrap(),
+        "http://example.com:8080/"
+    );
+    assert_eq!(
+        canonicalize("https://Bücher.example/", false).unwrap(),
+        "https://xn--bcher-kva.example/"
+    );
+}
+
+#[test]
+fn test_canonicalize_query() {
+    assert_eq!(
+        canonicalize("https://example.com/?b=2&a=1&a=0", false).unwrap(),
+        "https://example.com/?a=0&a=1&b=2"
+    );
+    assert_eq!(
+        canonicalize("https://example.com/?", false).unwrap(),
+        "https://example.com/"
+    );
+    assert_eq!(
+        canonicalize("https://example.com/?q=a%20b#top", false).unwrap(),
+        "https://example.com/?q=a+b#top"
+    );
+}
+
+#[test]
+fn test_canonicalize_tracking_parameters() {
+    let url = "https://example.com/?utm_source=x&id=5&fbclid=abc&gclid=def";
+    assert_eq!(
+        canonicalize(url, false).unwrap(),
+        "https://example.com/?fbclid=abc&gclid=def&id=5&utm_source=x"
+    );
+    assert_eq!(
+        canonicalize(url, true).unwrap(),
+        "https://example.com/?id=5"
+    );
+    assert_eq!(
+        canonicalize("https://example.com/?UTM_Campaign=y", true).unwrap(),
+        "https://example.com/"
+    );
+}
+
+#[test]
+fn test_is_tracking_parameter() {
+    assert!(is_tracking_parameter("utm_medium"));
+    assert!(is_tracking_parameter("fbclid"));
+    assert!(!is_tracking_parameter("utm"));
+    assert!(!is_tracking_parameter("page"));
+}
@@ -0,0 +1,177 @@
+//! Running the project with users the tests can sign in as and with access
+//! to the stored visits.
+
+// Every test binary includes this module, but not all of them use all of it
+#![allow(dead_code)]
+
+use chrono::{DateTime, TimeDelta, Utc};
+use cot::auth::db::DatabaseUser;
+use cot::auth::{Auth, Password};
+use cot::cli::{Cli, CliMetadata};
+use cot::db::{Auto, Database, ForeignKey, Model, query};
+use cot::json::Json;
+use cot::project::{MiddlewareContext, RegisterAppsContext, RootHandler, RootHandlerBuilder};
+use cot::request::extractors::Path;
+use cot::router::{Route, Router};
+use cot::{App, AppBuilder, Project, StatusCode};
+use serde::{Deserialize, Serialize};
+use shrt_backend::ShrtProject;
+use shrt_backend::models::{Link, Visit};
+
+/// Name of the user listed in `admins` in the test config.
+pub const ADMIN: &str = "admin";
+
+/// Age of the visits added by the `/test/links/{slug}/old-visit` route, past
+/// the `visit_retention_days` of the test config.
+pub const OLD_VISIT_AGE_DAYS: i64 = 400;
+
+/// [`ShrtProject`] with routes signing the client in as any user, reading
+/// the visits of the links and making them expire.
+pub struct TestProject;
+
+impl Project for TestProject {
+    fn cli_metadata(&self) -> CliMetadata {
+        ShrtProject.cli_metadata()
+    }
+
+    fn register_tasks(&self, cli: &mut Cli) {
+        ShrtProject.register_tasks(cli);
+    }
+
+    fn middlewares(&self, handler: RootHandlerBuilder, context: &MiddlewareContext) -> RootHandler {
+        ShrtProject.middlewares(handler, context)
+    }
+
+    fn register_apps(&self, apps: &mut AppBuilder, context: &RegisterAppsContext) {
+        ShrtProject.register_apps(apps, context);
+        apps.register_with_views(TestApp, "");
+    }
+}
+
+struct TestApp;
+
+impl App for TestApp {
+    fn name(&self) -> &'static str {
+        "test"
+    }
+
+    fn router(&self) -> Router {
+        Router::with_urls([
+            Route::with_handler("/test/sign-in/{username}", sign_in),
+            Route::with_handler("/test/links/{slug}/visits", get_visits),
+            Route::with_handler("/test/links/{slug}/old-visit", add_old_visit),
+            Route::with_handler("/test/links/{slug}/expire", expire_link),
+        ])
+    }
+}
+
+/// Signs in as the user called `username`, creating them if needed.
+async fn sign_in(
+    auth: Auth,
+    db: Database,
+    Path(username): Path<String>,
+) -> cot::Result<StatusCode> {
+    let user = match DatabaseUser::get_by_username(&db, &username).await? {
+        Some(user) => user,
+        None => DatabaseUser::create_user(&db, username, &Password::new("password")).await?,
+    };
+    auth.login(Box::new(user)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A visit of a link, as stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredVisit {
+    pub visited_at: DateTime<Utc>,
+    pub referrer: Option<String>,
+    pub user_agent: Option<String>,
+    pub accept_language: Option<String>,
+    pub client_ip: Option<String>,
+}
+
+async fn link_key(db: &Database, slug: String) -> cot::Result<ForeignKey<Link>> {
+    let link = query!(Link, $slug == slug)
+        .get(db)
+        .await?
+        .expect("link does not exist");
+    Ok(ForeignKey::PrimaryKey(link.id))
+}
+
+/// Returns the stored visits of the link with `slug`.
+async fn get_visits(db: Database, Path(slug): Path<String>) -> cot::Result<Json<Vec<StoredVisit>>> {
+    let link_key = link_key(&db, slug).await?;
+    let visits = query!(Visit, $link == link_key)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|visit| StoredVisit {
+            visited_at: visit.visited_at,
+            referrer: visit.referrer,
+            user_agent: visit.user_agent,
+            accept_language: visit.accept_language,
+            client_ip: visit.client_ip,
+        })
+        .collect();
+
+    Ok(Json(visits))
+}
+
+/// Stores a visit of the link with `slug` made [`OLD_VISIT_AGE_DAYS`] ago.
+async fn add_old_visit(db: Database, Path(slug): Path<String>) -> cot::Result<StatusCode> {
+    Visit {
+        id: Auto::auto(),
+        link: link_key(&db, slug).await?,
+        visited_at: Utc::now() - TimeDelta::days(OLD_VISIT_AGE_DAYS),
+        referrer: None,
+        user_agent: None,
+        accept_language: None,
+        client_ip: None,
+    }
+    .insert(&db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Makes the link with `slug` expire an hour ago.
+async fn expire_link(db: Database, Path(slug): Path<String>) -> cot::Result<StatusCode> {
+    let expires_at = Utc::now() - TimeDelta::hours(1);
+    db.raw_with(
+        "UPDATE shrt_backend__link SET expires_at = $2 WHERE slug = $1",
+        &[&slug, &expires_at],
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Returns the stored visits of the link with `slug` on the server at `url`.
+pub async fn stored_visits(url: &str, slug: &str) -> Vec<StoredVisit> {
+    let response = reqwest::Client::new()
+        .get(format!("{url}/test/links/{slug}/visits"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    response.json().await.unwrap()
+}
+
+/// Returns a client signed in as `username` to the server at `url`, not
+/// following the redirects.
+pub async fn signed_in_client(url: &str, username: &str) -> reqwest::Client {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    let response = client
+        .post(format!("{url}/test/sign-in/{username}"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    client
+}
@@ -0,0 +1,32 @@
+use std::net::IpAddr;
+
+use cot::http::{HeaderMap, HeaderValue};
+use shrt_backend::extractors::{anonymize_ip, client_ip};
+
+#[test]
+fn test_anonymize_ip() {
+    let ip: IpAddr = "192.0.2.123".parse().unwrap();
+    assert_eq!(anonymize_ip(ip), "192.0.2.0".parse::<IpAddr>().unwrap());
+
+    let ip: IpAddr = "2001:db8:85a3:8d3:1319:8a2e:370:7348".parse().unwrap();
+    assert_eq!(
+        anonymize_ip(ip),
+        "2001:db8:85a3::".parse::<IpAddr>().unwrap()
+    );
+}
+
+#[test]
+fn test_client_ip() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(client_ip(&headers), None);
+
+    headers.insert("X-Real-IP", HeaderValue::from_static("198.51.100.1"));
+    assert_eq!(client_ip(&headers), "198.51.100.1".parse().ok());
+
+    // Only the address appended by the proxy is trusted
+    headers.insert(
+        "X-Forwarded-For",
+        HeaderValue::from_static("203.0.113.7, 198.51.100.2"),
+    );
+    assert_eq!(client_ip(&headers), "198.51.100.2".parse().ok());
+}
@@ -0,0 +1,207 @@
+use chrono::{TimeZone, Utc};
+use shrt_backend::importers::{ImportSource, parse_date, slug_from_short_url};
+use shrt_backend::transfer::LinkRecord;
+
+fn records(source: ImportSource, data: &str) -> Vec<(u64, Result<LinkRecord, String>)> {
+    source
+        .parse(data)
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.line, row.record))
+        .collect()
+}
+
+#[test]
+fn test_import_yourls_sql() {
+    let data = "\
+-- MySQL dump
+/*!40101 SET NAMES utf8mb4 */;
+INSERT INTO `yourls_options` VALUES (1,'version','1.9.2');
+INSERT INTO `yourls_url` (`keyword`, `url`, `title`, `timestamp`, `ip`, `clicks`) VALUES
+('abc','https://example.com/?a=1&b=2','It''s a \\'title\\'','2024-01-02 03:04:05','127.0.0.1',12),
+('def','https://example.org',NULL,'0000-00-00 00:00:00','127.0.0.1',0),
+('bad',NULL,'No URL','2024-01-02 03:04:05','127.0.0.1',1);
+";
+
+    let records = records(ImportSource::Yourls, data);
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0],
+        (
+            5,
+            Ok(LinkRecord {
+                slug: "abc".to_owned(),
+                url: "https://example.com/?a=1&b=2".to_owned(),
+                created_at: Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+                visits: 12,
+                ..LinkRecord::default()
+            })
+        )
+    );
+    assert_eq!(
+        records[1],
+        (
+            6,
+            Ok(LinkRecord {
+                slug: "def".to_owned(),
+                url: "https://example.org".to_owned(),
+                ..LinkRecord::default()
+            })
+        )
+    );
+    assert_eq!(records[2], (7, Err("Missing target URL".to_owned())));
+}
+
+#[test]
+fn test_import_yourls_csv() {
+    let data = "\
+\"abc\",\"https://example.com\",\"Title\",\"2024-01-02 03:04:05\",\"127.0.0.1\",\"3\"
+\"def\",\"https://example.org\",\"\",\"2024-01-02 03:04:05\",\"127.0.0.1\",\"many\"
+";
+
+    let records = records(ImportSource::Yourls, data);
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].1.as_ref().unwrap().slug, "abc");
+    assert_eq!(records[0].1.as_ref().unwrap().visits, 3);
+    assert_eq!(
+        records[1],
+        (2, Err("Invalid number of visits: many".to_owned()))
+    );
+}
+
+#[test]
+fn test_import_shlink() {
+    let data = "\
+shortUrl,longUrl,title,tags,createdAt,visits
+https://s.test/abc,https://example.com,,,2024-01-02T03:04:05+02:00,\"1,234\"
+https://s.test/,https://example.org,,,2024-01-02T03:04:05+02:00,0
+";
+
+    let records = records(ImportSource::Shlink, data);
+
+    assert_eq!(
+        records,
+        vec![
+            (
+                2,
+                Ok(LinkRecord {
+                    slug: "abc".to_owned(),
+                    url: "https://example.com".to_owned(),
+                    created_at: Some(Utc.with_ymd_and_hms(2024, 1, 2, 1, 4, 5).unwrap()),
+                    visits: 1234,
+                    ..LinkRecord::default()
+                })
+            ),
+            (3, Err("Missing slug".to_owned())),
+        ]
+    );
+}
+
+#[test]
+fn test_import_shlink_without_long_url() {
+    let result = ImportSource::Shlink.parse("shortCode,url\nabc,https://example.com\n");
+
+    assert_eq!(result, Err("The file has no longUrl column".to_owned()));
+}
+
+#[test]
+fn test_import_kutt() {
+    let data = r#"{
+        "limit": 10,
+        "skip": 0,
+        "total": 3,
+        "data": [
+            {
+                "address": "abc",
+                "target": "https://example.com",
+                "created_at": "2024-01-02T03:04:05.000Z",
+                "visit_count": 7,
+                "password": false,
+                "banned": false,
+                "expire_in": "2025-01-01T00:00:00.000Z"
+            },
+            {"address": "secret", "target": "https://example.org", "password": true},
+            {"address": "spam", "target": "https://example.net", "banned": true}
+        ]
+    }"#;
+
+    let records = records(ImportSource::Kutt, data);
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0],
+        (
+            1,
+            Ok(LinkRecord {
+                slug: "abc".to_owned(),
+                url: "https://example.com".to_owned(),
+                created_at: Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+                visits: 7,
+                expires_at: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+                ..LinkRecord::default()
+            })
+        )
+    );
+    assert!(records[1].1.is_err());
+    assert_eq!(records[2], (3, Err("The link is banned".to_owned())));
+}
+
+#[test]
+fn test_import_bitly() {
+    let data = "\
+Bitlink,Long URL,Title,Created,Clicks
+bit.ly/abc,https://example.com,Example,2024-01-02 03:04:05,5
+bit.ly/def,https://example.org,Example,yesterday,5
+";
+
+    let records = records(ImportSource::Bitly, data);
+
+    assert_eq!(
+        records,
+        vec![
+            (
+                2,
+                Ok(LinkRecord {
+                    slug: "abc".to_owned(),
+                    url: "https://example.com".to_owned(),
+                    created_at: Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+                    visits: 5,
+                    ..LinkRecord::default()
+                })
+            ),
+            (3, Err("Invalid creation date: yesterday".to_owned())),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_date() {
+    let expected = Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap());
+
+    assert_eq!(parse_date("2024-01-02T03:04:05Z"), expected);
+    assert_eq!(parse_date("2024-01-02T04:04:05+01:00"), expected);
+    assert_eq!(parse_date("2024-01-02 03:04:05"), expected);
+    assert_eq!(parse_date("2024-01-02 03:04:05.000"), expected);
+    assert_eq!(parse_date("01/02/2024 03:04:05"), expected);
+    assert_eq!(
+        parse_date("2024-01-02"),
+        Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+    );
+    assert_eq!(parse_date("not a date"), None);
+}
+
+#[test]
+fn test_slug_from_short_url() {
+    assert_eq!(
+        slug_from_short_url("https://bit.ly/abc"),
+        Some("abc".to_owned())
+    );
+    assert_eq!(slug_from_short_url("bit.ly/abc/"), Some("abc".to_owned()));
+    assert_eq!(
+        slug_from_short_url("https://s.test/za%C5%BC%C3%B3%C5%82%C4%87"),
+        Some("zażółć".to_owned())
+    );
+    assert_eq!(slug_from_short_url("https://s.test/"), None);
+}
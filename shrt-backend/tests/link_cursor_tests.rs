@@ -0,0 +1,29 @@
+use chrono::{TimeZone, Utc};
+use shrt_backend::link_cursor::LinkCursor;
+
+#[test]
+fn test_cursor_round_trip() {
+    for cursor in [
+        LinkCursor {
+            created_at: Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+            id: 42,
+        },
+        LinkCursor {
+            created_at: Utc.with_ymd_and_hms(1960, 1, 1, 0, 0, 0).unwrap()
+                + chrono::TimeDelta::microseconds(123),
+            id: i32::MAX,
+        },
+    ] {
+        let encoded = cursor.encode();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(LinkCursor::decode(&encoded), Some(cursor));
+    }
+}
+
+#[test]
+fn test_invalid_cursor() {
+    assert_eq!(LinkCursor::decode(""), None);
+    assert_eq!(LinkCursor::decode("not a cursor"), None);
+    assert_eq!(LinkCursor::decode("zzzzzzzzzzzzzzzzzzzzzzzz"), None);
+    assert_eq!(LinkCursor::decode("0000000000000000000000000"), None);
+}
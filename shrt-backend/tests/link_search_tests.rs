@@ -0,0 +1,163 @@
+use chrono::{TimeZone, Utc};
+use shrt_backend::link_cursor::LinkCursor;
+use shrt_backend::link_search::{LinkFilter, SqlValue, UrlAccess, like_pattern};
+use shrt_common::links::{LinkSortField, SortOrder};
+
+#[test]
+fn test_like_pattern() {
+    assert_eq!(like_pattern("Manual"), "%manual%");
+    assert_eq!(like_pattern("50%_off\\"), "%50\\%\\_off\\\\%");
+}
+
+#[test]
+fn test_empty_filter() {
+    let filter = LinkFilter {
+        text: Some("  ".to_string()),
+        ..LinkFilter::default()
+    };
+
+    let count_query = filter.count_query();
+    assert_eq!(count_query.sql, "SELECT COUNT(*) FROM shrt_backend__link");
+    assert!(count_query.values.is_empty());
+
+    let page_query = filter.page_query(None, SortOrder::Asc, 60, 30);
+    assert!(
+        page_query
+            .sql
+            .ends_with(" FROM shrt_backend__link ORDER BY id LIMIT $1 OFFSET $2")
+    );
+    assert_eq!(page_query.values, [SqlValue::Int(30), SqlValue::Int(60)]);
+}
+
+#[test]
+fn test_filter_conditions() {
+    let created_from = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let filter = LinkFilter {
+        owner_id: Some(7),
+        created_from: Some(created_from),
+        min_visits: Some(10),
+        tag: Some(" Docs ".to_string()),
+        ..LinkFilter::default()
+    };
+
+    let count_query = filter.count_query();
+    assert!(count_query.sql.starts_with(
+        "SELECT COUNT(*) FROM shrt_backend__link \
+         WHERE owner_id = $1 AND created_at >= $2 AND visits >= $3 AND id IN ("
+    ));
+    assert!(count_query.sql.ends_with("WHERE tag.name = $4)"));
+    assert_eq!(
+        count_query.values,
+        [
+            SqlValue::Int(7),
+            SqlValue::Time(created_from),
+            SqlValue::Int(10),
+            SqlValue::Text("docs".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_filter_text() {
+    let filter = LinkFilter {
+        text: Some("MANUAL".to_string()),
+        url_access: UrlAccess {
+            all: false,
+            owner_id: Some(7),
+            unlocked_link_ids: vec![3, 5],
+        },
+        ..LinkFilter::default()
+    };
+
+    // The target URLs are only searched in the links the user can see them of
+    let count_query = filter.count_query();
+    assert_eq!(
+        count_query.sql,
+        "SELECT COUNT(*) FROM shrt_backend__link \
+         WHERE (LOWER(slug) LIKE $1 ESCAPE '\\' OR (LOWER(url) LIKE $1 ESCAPE '\\' \
+         AND (password_hash IS NULL OR owner_id = $2 OR id IN ($3, $4))))"
+    );
+    assert_eq!(
+        count_query.values,
+        [
+            SqlValue::Text("%manual%".to_string()),
+            SqlValue::Int(7),
+            SqlValue::Int(3),
+            SqlValue::Int(5),
+        ]
+    );
+
+    let admin_filter = LinkFilter {
+        url_access: UrlAccess {
+            all: true,
+            ..UrlAccess::default()
+        },
+        ..filter
+    };
+    assert!(
+        admin_filter
+            .count_query()
+            .sql
+            .ends_with("OR (LOWER(url) LIKE $1 ESCAPE '\\' AND 1 = 1))")
+    );
+}
+
+#[test]
+fn test_filter_sort() {
+    let order_by = |sort: LinkSortField, order: SortOrder| {
+        let sql = LinkFilter::default()
+            .page_query(Some(sort), order, 0, 30)
+            .sql;
+        let start = sql.find(" ORDER BY ").unwrap();
+        let end = sql.find(" LIMIT ").unwrap();
+        sql[start..end].to_string()
+    };
+
+    assert_eq!(
+        order_by(LinkSortField::CreatedAt, SortOrder::Asc),
+        " ORDER BY created_at ASC, slug ASC"
+    );
+    // Links with the same number of visits are sorted by slug
+    assert_eq!(
+        order_by(LinkSortField::Visits, SortOrder::Desc),
+        " ORDER BY visits DESC, slug DESC"
+    );
+    assert_eq!(
+        order_by(LinkSortField::Slug, SortOrder::Desc),
+        " ORDER BY slug DESC"
+    );
+}
+
+#[test]
+fn test_filter_cursor() {
+    let filter = LinkFilter {
+        min_visits: Some(10),
+        ..LinkFilter::default()
+    };
+    let cursor = LinkCursor {
+        created_at: Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+        id: 42,
+    };
+
+    let first_query = filter.cursor_query(None, SortOrder::Asc, 31);
+    assert!(first_query.sql.ends_with(
+        " FROM shrt_backend__link WHERE visits >= $1 \
+         ORDER BY created_at ASC, id ASC LIMIT $2"
+    ));
+    assert_eq!(first_query.values, [SqlValue::Int(10), SqlValue::Int(31)]);
+
+    let next_query = filter.cursor_query(Some(cursor), SortOrder::Desc, 31);
+    assert!(next_query.sql.ends_with(
+        " FROM shrt_backend__link WHERE visits >= $1 AND (created_at, id) < ($2, $3) \
+         ORDER BY created_at DESC, id DESC LIMIT $4"
+    ));
+    assert_eq!(
+        next_query.values,
+        [
+            SqlValue::Int(10),
+            SqlValue::Time(cursor.created_at),
+            SqlValue::Int(42),
+            SqlValue::Int(31),
+        ]
+    );
+}
@@ -0,0 +1,24 @@
+use shrt_common::links::target_domain;
+
+#[test]
+fn test_target_domain() {
+    assert_eq!(target_domain("https://example.com"), Some("example.com"));
+    assert_eq!(
+        target_domain("https://www.example.com:8443/path?q=1#top"),
+        Some("www.example.com")
+    );
+    assert_eq!(target_domain("http://[::1]:8000/"), Some("::1"));
+    assert_eq!(target_domain("ftp://example.com?x"), Some("example.com"));
+    // The user info is not where the link leads to
+    assert_eq!(
+        target_domain("https://bank.example@evil.example/login"),
+        Some("evil.example")
+    );
+    assert_eq!(
+        target_domain("https://evil.example\\@bank.example/"),
+        Some("evil.example")
+    );
+
+    assert_eq!(target_domain("mailto:someone@example.com"), None);
+    assert_eq!(target_domain("https:///path"), None);
+}
@@ -0,0 +1,177 @@
+use std::net::{IpAddr, SocketAddr};
+
+use shrt_backend::metadata::{MetadataError, MetadataFetcher, PageMetadata, is_public, parse_page};
+use shrt_common::config::MetadataFetcherConfig;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use url::Url;
+
+/// Starts a stand-in for a website on a local port, answering each request
+/// with the response `respond` returns for its path.
+async fn serve(respond: fn(&str) -> String) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split(' ').nth(1).unwrap_or("/");
+                let _ = stream.write_all(respond(path).as_bytes()).await;
+            });
+        }
+    });
+
+    addr
+}
+
+fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn website(path: &str) -> String {
+    match path {
+        "/" => response(
+            "200 OK",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "<!DOCTYPE html>\n<html><head>\n\
+             <title>\n  Fish &amp; Chips &#8211; Menu\n</title>\n\
+             <link rel=\"stylesheet\" href=\"/style.css\">\n\
+             <link rel=\"shortcut icon\" href=\"/static/icon.png\">\n\
+             </head><body></body></html>",
+        ),
+        "/old" => response("301 Moved Permanently", "Location: /\r\n", ""),
+        "/image.png" => response("200 OK", "Content-Type: image/png\r\n", "PNG"),
+        _ => response("404 Not Found", "Content-Type: text/html\r\n", "Not found"),
+    }
+}
+
+fn local_fetcher() -> MetadataFetcher {
+    MetadataFetcher::new(&MetadataFetcherConfig {
+        allow_private_addresses: true,
+        ..MetadataFetcherConfig::default()
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_parse_page() {
+    let page_url = Url::parse("https://example.com/blog/post").unwrap();
+
+    assert_eq!(
+        parse_page(
+            "<html><head><TITLE lang=en>Hello,   world</TITLE>\
+             <link href='icon.svg' rel=icon></head></html>",
+            &page_url
+        ),
+        PageMetadata {
+            title: Some("Hello, world".to_string()),
+            favicon_url: Some("https://example.com/blog/icon.svg".to_string()),
+        }
+    );
+    // The Open Graph title is used if the page has no title
+    assert_eq!(
+        parse_page(
+            "<meta property=\"og:title\" content=\"Q&amp;A\">\
+             <link rel=\"icon\" href=\"javascript:alert(1)\">",
+            &page_url
+        ),
+        PageMetadata {
+            title: Some("Q&A".to_string()),
+            favicon_url: None,
+        }
+    );
+    assert_eq!(
+        parse_page("<title></title><titles>No</titles>", &page_url),
+        PageMetadata::default()
+    );
+    assert_eq!(
+        parse_page(&format!("<title>{}</title>", "a".repeat(300)), &page_url)
+            .title
+            .map(|title| title.len()),
+        Some(200)
+    );
+}
+
+#[test]
+fn test_is_public() {
+    for ip in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c"] {
+        assert!(is_public(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+    for ip in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "fd00::1",
+        "fe80::1",
+        "::ffff:127.0.0.1",
+    ] {
+        assert!(!is_public(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_metadata() {
+    let addr = serve(website).await;
+    let fetcher = local_fetcher();
+
+    let expected = PageMetadata {
+        title: Some("Fish & Chips \u{2013} Menu".to_string()),
+        favicon_url: Some(format!("http://{addr}/static/icon.png")),
+    };
+    assert_eq!(
+        fetcher.fetch(&format!("http://{addr}/")).await.unwrap(),
+        expected
+    );
+    // The icon is resolved against the URL the page was redirected to
+    assert_eq!(
+        fetcher.fetch(&format!("http://{addr}/old")).await.unwrap(),
+        expected
+    );
+
+    assert!(matches!(
+        fetcher.fetch(&format!("http://{addr}/image.png")).await,
+        Err(MetadataError::NotHtml)
+    ));
+    assert!(matches!(
+        fetcher.fetch(&format!("http://{addr}/missing")).await,
+        Err(MetadataError::Status(status)) if status.as_u16() == 404
+    ));
+}
+
+#[tokio::test]
+async fn test_fetch_metadata_private_address() {
+    let addr = serve(website).await;
+    let fetcher = MetadataFetcher::new(&MetadataFetcherConfig::default()).unwrap();
+
+    assert!(matches!(
+        fetcher.fetch(&format!("http://{addr}/")).await,
+        Err(MetadataError::PrivateAddress(_))
+    ));
+    // Host names resolving to private addresses are refused as well
+    assert!(matches!(
+        fetcher
+            .fetch(&format!("http://localhost:{}/", addr.port()))
+            .await,
+        Err(MetadataError::Request(_))
+    ));
+}
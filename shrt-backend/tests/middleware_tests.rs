@@ -0,0 +1,89 @@
+use cot::http::{HeaderMap, HeaderValue, Method};
+use shrt_backend::middleware::{bearer_token, is_read_only_request, rate_limited_action};
+use shrt_backend::rate_limit::RateLimitedAction;
+
+#[test]
+fn test_bearer_token() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(bearer_token(&headers), None);
+
+    headers.insert("Authorization", HeaderValue::from_static("Bearer shrt_abc"));
+    assert_eq!(bearer_token(&headers), Some("shrt_abc"));
+
+    // The scheme is case-insensitive
+    headers.insert(
+        "Authorization",
+        HeaderValue::from_static("bearer  shrt_abc "),
+    );
+    assert_eq!(bearer_token(&headers), Some("shrt_abc"));
+
+    headers.insert(
+        "Authorization",
+        HeaderValue::from_static("Basic dXNlcjpwYXNz"),
+    );
+    assert_eq!(bearer_token(&headers), None);
+
+    headers.insert("Authorization", HeaderValue::from_static("Bearer "));
+    assert_eq!(bearer_token(&headers), None);
+}
+
+#[test]
+fn test_rate_limited_action() {
+    assert_eq!(
+        rate_limited_action(&Method::POST, "/links"),
+        Some(RateLimitedAction::CreateLink)
+    );
+    for path in ["/links/batch", "/links/import"] {
+        assert_eq!(
+            rate_limited_action(&Method::POST, path),
+            Some(RateLimitedAction::CreateLink),
+            "{path}"
+        );
+    }
+    assert_eq!(
+        rate_limited_action(&Method::GET, "/links/abc/exists"),
+        Some(RateLimitedAction::LinkExists)
+    );
+    assert_eq!(
+        rate_limited_action(&Method::POST, "/links/batch-get"),
+        Some(RateLimitedAction::LinkExists)
+    );
+    assert_eq!(
+        rate_limited_action(&Method::GET, "/links/abc/go"),
+        Some(RateLimitedAction::LinkNotFound)
+    );
+    assert_eq!(
+        rate_limited_action(&Method::GET, "/links/abc/preview"),
+        Some(RateLimitedAction::LinkNotFound)
+    );
+    for path in [
+        "/links/abc",
+        "/links/abc/qr",
+        "/links/abc/stats",
+        "/links/abc/go/guide/install",
+    ] {
+        assert_eq!(
+            rate_limited_action(&Method::GET, path),
+            Some(RateLimitedAction::LinkNotFound),
+            "{path}"
+        );
+    }
+    assert_eq!(
+        rate_limited_action(&Method::POST, "/links/abc/go"),
+        Some(RateLimitedAction::LinkPassword)
+    );
+    assert_eq!(rate_limited_action(&Method::GET, "/links"), None);
+    assert_eq!(rate_limited_action(&Method::PATCH, "/links/abc"), None);
+    assert_eq!(rate_limited_action(&Method::GET, "/tokens/1"), None);
+}
+
+#[test]
+fn test_is_read_only_request() {
+    assert!(is_read_only_request(&Method::GET, "/links"));
+    assert!(is_read_only_request(&Method::HEAD, "/links/abc"));
+    assert!(is_read_only_request(&Method::POST, "/links/batch-get"));
+    assert!(!is_read_only_request(&Method::POST, "/links"));
+    assert!(!is_read_only_request(&Method::POST, "/links/batch"));
+    assert!(!is_read_only_request(&Method::POST, "/links/batch-delete"));
+    assert!(!is_read_only_request(&Method::DELETE, "/links/abc"));
+}
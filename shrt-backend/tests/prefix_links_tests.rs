@@ -0,0 +1,79 @@
+use shrt_backend::prefix_links::{InvalidSubpath, append_subpath, split_link_path};
+
+#[test]
+fn test_split_link_path() {
+    assert_eq!(
+        split_link_path("/links/docs/go/getting-started"),
+        Some(("/links/docs/go", "getting-started"))
+    );
+    assert_eq!(
+        split_link_path("/links/docs/go/guide/install/"),
+        Some(("/links/docs/go", "guide/install/"))
+    );
+    assert_eq!(
+        split_link_path("/links/docs/go//evil.example"),
+        Some(("/links/docs/go", "/evil.example"))
+    );
+
+    for path in [
+        "/links/docs/go",
+        "/links/docs/go/",
+        "/links/docs/preview/guide",
+        "/links//go/guide",
+        "/links/docs",
+        "/tokens/1/go/guide",
+    ] {
+        assert_eq!(split_link_path(path), None, "{path}");
+    }
+}
+
+#[test]
+fn test_append_subpath() {
+    assert_eq!(
+        append_subpath("https://docs.example.com", "getting-started"),
+        Ok("https://docs.example.com/getting-started".to_string())
+    );
+    assert_eq!(
+        append_subpath("https://docs.example.com/v2/", "guide/install/"),
+        Ok("https://docs.example.com/v2/guide/install/".to_string())
+    );
+    // The query and the fragment of the target stay at the end
+    assert_eq!(
+        append_subpath("https://example.com/repo?tab=readme#top", "issues/1"),
+        Ok("https://example.com/repo/issues/1?tab=readme#top".to_string())
+    );
+}
+
+#[test]
+fn test_append_subpath_encodes_segments() {
+    // Empty segments are skipped, so the subpath cannot start a new authority
+    assert_eq!(
+        append_subpath("https://example.com", "/evil.example"),
+        Ok("https://example.com/evil.example".to_string())
+    );
+    // Encoded slashes, backslashes, queries and fragments stay in the segment
+    assert_eq!(
+        append_subpath("https://example.com/docs", "a%2F..%5Cb/%3Fq%23f"),
+        Ok("https://example.com/docs/a%2F..%5Cb/%3Fq%23f".to_string())
+    );
+    assert_eq!(
+        append_subpath("https://example.com", "caf%C3%A9 au lait/@evil"),
+        Ok("https://example.com/caf%C3%A9%20au%20lait/%40evil".to_string())
+    );
+    // Invalid UTF-8 is kept as it was sent
+    assert_eq!(
+        append_subpath("https://example.com", "%FF"),
+        Ok("https://example.com/%FF".to_string())
+    );
+}
+
+#[test]
+fn test_append_subpath_rejects_dot_segments() {
+    for subpath in ["..", "a/../b", ".", "%2e%2E/admin", "a/%2E"] {
+        assert_eq!(
+            append_subpath("https://example.com/docs", subpath),
+            Err(InvalidSubpath),
+            "{subpath}"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use shrt_backend::qr_codes::{Colour, QrCodeError, QrCodeOptions, render_png, render_svg};
+use shrt_common::qr_codes::ErrorCorrection;
+
+const SHORT_URL: &str = "https://example.com/abc";
+
+fn logo() -> Vec<u8> {
+    let image = RgbaImage::from_pixel(8, 4, Rgba([255, 0, 0, 255]));
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+    png
+}
+
+#[test]
+fn test_parse_colour() {
+    assert_eq!(
+        Colour::parse("#1a2B3c"),
+        Some(Colour([0x1a, 0x2b, 0x3c, 255]))
+    );
+    assert_eq!(
+        Colour::parse("1a2b3c"),
+        Some(Colour([0x1a, 0x2b, 0x3c, 255]))
+    );
+    assert_eq!(Colour::parse("#fa0"), Some(Colour([0xff, 0xaa, 0x00, 255])));
+    assert_eq!(Colour::parse("Transparent"), Some(Colour::TRANSPARENT));
+    for invalid in ["", "#", "#12345", "#1234567", "#ggg", "red", "#+1a2b3"] {
+        assert_eq!(Colour::parse(invalid), None, "{invalid}");
+    }
+
+    assert_eq!(Colour([0x1a, 0x2b, 0x3c, 255]).to_string(), "#1a2b3c");
+    assert_eq!(Colour::TRANSPARENT.to_string(), "transparent");
+}
+
+#[test]
+fn test_render_svg() {
+    let svg = render_svg(
+        SHORT_URL,
+        &QrCodeOptions {
+            size: 300,
+            margin: 2,
+            foreground: Colour::parse("#112233").unwrap(),
+            ..QrCodeOptions::default()
+        },
+    )
+    .unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.ends_with("</svg>"));
+    assert!(svg.contains("width=\"300\" height=\"300\""));
+    // A version 2 code, 25 modules wide, with a margin of 2 modules
+    assert!(svg.contains("viewBox=\"0 0 29 29\""));
+    assert!(svg.contains("<rect width=\"29\" height=\"29\" fill=\"#ffffff\"/>"));
+    assert!(svg.contains("<path fill=\"#112233\" d=\"M2 2h1v1h-1z"));
+    assert!(!svg.contains("<image"));
+
+    let svg = render_svg(
+        SHORT_URL,
+        &QrCodeOptions {
+            background: Colour::TRANSPARENT,
+            logo: Some(logo()),
+            ..QrCodeOptions::default()
+        },
+    )
+    .unwrap();
+    assert!(!svg.contains("<rect"));
+    assert!(svg.contains("href=\"data:image/png;base64,"));
+}
+
+#[test]
+fn test_render_png() {
+    let options = QrCodeOptions {
+        size: 256,
+        error_correction: ErrorCorrection::High,
+        background: Colour::parse("#00ff00").unwrap(),
+        logo: Some(logo()),
+        ..QrCodeOptions::default()
+    };
+    let png = render_png(SHORT_URL, &options).unwrap();
+
+    let image = image::load_from_memory(&png).unwrap().to_rgba8();
+    assert_eq!(image.dimensions(), (256, 256));
+    assert_eq!(*image.get_pixel(0, 0), Rgba([0, 255, 0, 255]));
+    // A version 3 code, 29 modules wide, with a margin of 4 modules, scaled 6
+    // times and centred; this is the top left corner of a finder pattern
+    assert_eq!(*image.get_pixel(41, 41), Rgba([0, 0, 0, 255]));
+    assert_eq!(*image.get_pixel(40, 40), Rgba([0, 255, 0, 255]));
+    // The logo is in the middle
+    let Rgba([red, green, blue, _]) = *image.get_pixel(128, 128);
+    assert!(red > 200 && green < 50 && blue < 50);
+}
+
+#[test]
+fn test_render_png_larger_than_requested() {
+    let png = render_png(
+        SHORT_URL,
+        &QrCodeOptions {
+            size: 10,
+            ..QrCodeOptions::default()
+        },
+    )
+    .unwrap();
+
+    let image = image::load_from_memory(&png).unwrap();
+    // One pixel per module: 25 modules and a margin of 4 on each side
+    assert_eq!((image.width(), image.height()), (33, 33));
+}
+
+#[test]
+fn test_render_invalid_logo() {
+    let options = QrCodeOptions {
+        logo: Some(b"not an image".to_vec()),
+        ..QrCodeOptions::default()
+    };
+
+    assert!(matches!(
+        render_svg(SHORT_URL, &options),
+        Err(QrCodeError::InvalidLogo(_))
+    ));
+    assert!(matches!(
+        render_png(SHORT_URL, &options),
+        Err(QrCodeError::InvalidLogo(_))
+    ));
+}
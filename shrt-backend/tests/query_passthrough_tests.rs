@@ -0,0 +1,88 @@
+use shrt_backend::query_passthrough::merge_query;
+use shrt_common::links::QueryPassthrough;
+
+const TARGET: &str = "https://example.com/page?utm_source=site&ref=1#section";
+
+#[test]
+fn test_merge_query_off() {
+    assert_eq!(
+        merge_query(TARGET, Some("utm_source=newsletter"), QueryPassthrough::Off),
+        TARGET
+    );
+}
+
+#[test]
+fn test_merge_query_visitor_wins() {
+    assert_eq!(
+        merge_query(
+            TARGET,
+            Some("utm_source=newsletter&utm_medium=email"),
+            QueryPassthrough::VisitorWins
+        ),
+        "https://example.com/page?ref=1&utm_source=newsletter&utm_medium=email#section"
+    );
+    // Names are compared after decoding them
+    assert_eq!(
+        merge_query(
+            TARGET,
+            Some("utm%5Fsource=x"),
+            QueryPassthrough::VisitorWins
+        ),
+        "https://example.com/page?ref=1&utm%5Fsource=x#section"
+    );
+}
+
+#[test]
+fn test_merge_query_target_wins() {
+    assert_eq!(
+        merge_query(
+            TARGET,
+            Some("utm_source=newsletter&utm_medium=email"),
+            QueryPassthrough::TargetWins
+        ),
+        "https://example.com/page?utm_source=site&ref=1&utm_medium=email#section"
+    );
+}
+
+#[test]
+fn test_merge_query_append() {
+    assert_eq!(
+        merge_query(
+            TARGET,
+            Some("utm_source=newsletter"),
+            QueryPassthrough::Append
+        ),
+        "https://example.com/page?utm_source=site&ref=1&utm_source=newsletter#section"
+    );
+}
+
+#[test]
+fn test_merge_query_target_without_query() {
+    assert_eq!(
+        merge_query(
+            "https://example.com/#top",
+            Some("a=1&&b"),
+            QueryPassthrough::VisitorWins
+        ),
+        "https://example.com/?a=1&b#top"
+    );
+    assert_eq!(
+        merge_query(
+            "https://example.com",
+            Some("a=%26"),
+            QueryPassthrough::Append
+        ),
+        "https://example.com?a=%26"
+    );
+    // Nothing to pass on
+    for visitor_query in [None, Some(""), Some("&")] {
+        assert_eq!(
+            merge_query(
+                "https://example.com?",
+                visitor_query,
+                QueryPassthrough::Append
+            ),
+            "https://example.com?"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use shrt_backend::rate_limit::{MAX_TRACKED_BUDGETS, RateLimitKey, RateLimitedAction, RateLimiter};
+use shrt_common::config::RateLimit;
+
+const LIMIT: RateLimit = RateLimit {
+    requests: 2,
+    period_secs: 10,
+};
+
+#[test]
+fn test_rate_limiter_acquire() {
+    let limiter = RateLimiter::new();
+    let key = RateLimitKey::Ip("192.0.2.1".parse::<IpAddr>().unwrap());
+    let now = Instant::now();
+
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::CreateLink, key, LIMIT, now)
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::CreateLink, key, LIMIT, now)
+            .is_ok()
+    );
+    let retry_after = limiter
+        .acquire(RateLimitedAction::CreateLink, key, LIMIT, now)
+        .unwrap_err();
+    assert_eq!(retry_after, Duration::from_secs(5));
+
+    // Other clients and actions have separate budgets
+    assert!(
+        limiter
+            .acquire(
+                RateLimitedAction::CreateLink,
+                RateLimitKey::User(1),
+                LIMIT,
+                now
+            )
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::LinkExists, key, LIMIT, now)
+            .is_ok()
+    );
+
+    // The budget is regained over time
+    let later = now + Duration::from_secs(5);
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::CreateLink, key, LIMIT, later)
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::CreateLink, key, LIMIT, later)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_rate_limiter_acquire_many() {
+    let limiter = RateLimiter::new();
+    let key = RateLimitKey::User(1);
+    let limit = RateLimit {
+        requests: 10,
+        period_secs: 10,
+    };
+    let now = Instant::now();
+
+    assert!(
+        limiter
+            .acquire_many(RateLimitedAction::CreateLink, key, limit, 8, now)
+            .is_ok()
+    );
+    // Nothing is used up if the budget is too low for all the requests
+    let retry_after = limiter
+        .acquire_many(RateLimitedAction::CreateLink, key, limit, 5, now)
+        .unwrap_err();
+    assert_eq!(retry_after, Duration::from_secs(3));
+    assert!(
+        limiter
+            .acquire_many(RateLimitedAction::CreateLink, key, limit, 2, now)
+            .is_ok()
+    );
+    assert!(
+        limiter
+            .acquire(RateLimitedAction::CreateLink, key, limit, now)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_rate_limiter_check_and_charge() {
+    let limiter = RateLimiter::new();
+    let key = RateLimitKey::Unknown;
+    let now = Instant::now();
+
+    for _ in 0..5 {
+        assert!(
+            limiter
+                .check(RateLimitedAction::LinkNotFound, key, LIMIT, now)
+                .is_ok()
+        );
+    }
+    limiter.charge(RateLimitedAction::LinkNotFound, key, LIMIT, now);
+    limiter.charge(RateLimitedAction::LinkNotFound, key, LIMIT, now);
+    assert!(
+        limiter
+            .check(RateLimitedAction::LinkNotFound, key, LIMIT, now)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_rate_limiter_disabled() {
+    let limiter = RateLimiter::new();
+    let limit = RateLimit {
+        requests: 0,
+        period_secs: 10,
+    };
+    let now = Instant::now();
+
+    for _ in 0..100 {
+        assert!(
+            limiter
+                .acquire(
+                    RateLimitedAction::CreateLink,
+                    RateLimitKey::Unknown,
+                    limit,
+                    now
+                )
+                .is_ok()
+        );
+    }
+}
+
+#[test]
+fn test_rate_limiter_forgets_least_recently_used() {
+    let limiter = RateLimiter::new();
+    let now = Instant::now();
+    let acquire = |user_id: usize, now: Instant| {
+        let key = RateLimitKey::User(i64::try_from(user_id).unwrap());
+        limiter.acquire(RateLimitedAction::CreateLink, key, LIMIT, now)
+    };
+
+    // None of the budgets are full, so only the oldest ones can be forgotten
+    for user_id in 0..MAX_TRACKED_BUDGETS {
+        let now = now + Duration::from_micros(user_id as u64);
+        assert!(acquire(user_id, now).is_ok());
+        assert!(acquire(user_id, now).is_ok());
+    }
+    let later = now + Duration::from_millis(100);
+    assert!(acquire(MAX_TRACKED_BUDGETS, later).is_ok());
+
+    assert!(acquire(0, later).is_ok());
+    assert!(acquire(MAX_TRACKED_BUDGETS - 1, later).is_err());
+}
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use shrt_backend::slug_generator::{OffensiveWordFilter, SlugContext, encode_number, from_config};
+use shrt_common::config::{DEFAULT_SLUG_ALPHABET, SlugGeneratorConfig, SlugStrategy};
+
+const CONTEXT: SlugContext<'static> = SlugContext {
+    url: "https://example.com",
+    link_count: 41,
+};
+
+fn generator_config(strategy: SlugStrategy) -> SlugGeneratorConfig {
+    SlugGeneratorConfig {
+        strategy,
+        blocked_words: Vec::new(),
+    }
+}
+
+#[test]
+fn test_random_slugs() {
+    let generator = from_config(&SlugGeneratorConfig::default()).unwrap();
+
+    let slug = generator.generate(CONTEXT, 0);
+    assert_eq!(slug.len(), 7);
+    assert!(slug.chars().all(|c| DEFAULT_SLUG_ALPHABET.contains(c)));
+
+    // Slugs get longer after repeated collisions
+    assert_eq!(generator.generate(CONTEXT, 2).len(), 7);
+    assert_eq!(generator.generate(CONTEXT, 3).len(), 8);
+    assert_eq!(generator.generate(CONTEXT, 7).len(), 9);
+}
+
+#[test]
+fn test_word_slugs() {
+    let generator = from_config(&generator_config(SlugStrategy::Words { digits: 2 })).unwrap();
+
+    let slug = generator.generate(CONTEXT, 0);
+    let parts: Vec<&str> = slug.split('-').collect();
+    assert_eq!(parts.len(), 3);
+    assert!(parts[0].chars().all(|c| c.is_ascii_lowercase()));
+    assert!(parts[1].chars().all(|c| c.is_ascii_lowercase()));
+    assert_eq!(parts[2].len(), 2);
+    assert!(parts[2].chars().all(|c| c.is_ascii_digit()));
+
+    let slug = generator.generate(CONTEXT, 3);
+    assert_eq!(slug.rsplit('-').next().unwrap().len(), 3);
+}
+
+#[test]
+fn test_sequential_slugs() {
+    let generator = from_config(&generator_config(SlugStrategy::Sequential {
+        alphabet: "0123456789".to_string(),
+        min_length: 3,
+        salt: None,
+    }))
+    .unwrap();
+    assert_eq!(generator.generate(CONTEXT, 0), "042");
+    assert_eq!(generator.generate(CONTEXT, 1), "043");
+    assert_eq!(generator.generate(CONTEXT, 2), "046");
+
+    let salted = from_config(&generator_config(SlugStrategy::Sequential {
+        alphabet: DEFAULT_SLUG_ALPHABET.to_string(),
+        min_length: 4,
+        salt: Some("secret".to_string()),
+    }))
+    .unwrap();
+    let slug = salted.generate(CONTEXT, 0);
+    assert_eq!(slug.len(), 4);
+    assert_eq!(slug, salted.generate(CONTEXT, 0));
+    assert_ne!(slug, salted.generate(CONTEXT, 1));
+
+    // Consecutive numbers get unrelated slugs, none of them the same
+    let slugs: HashSet<String> = (0..1000)
+        .map(|link_count| {
+            salted.generate(
+                SlugContext {
+                    link_count,
+                    ..CONTEXT
+                },
+                0,
+            )
+        })
+        .collect();
+    assert_eq!(slugs.len(), 1000);
+    let prefixes: HashSet<&str> = slugs.iter().map(|slug| &slug[..2]).collect();
+    assert!(prefixes.len() > 100);
+}
+
+#[test]
+fn test_slug_generator_config() {
+    let config: SlugGeneratorConfig =
+        serde_json::from_value(serde_json::json!({ "blocked_words": ["qwerty"] })).unwrap();
+    assert_eq!(config.strategy, SlugStrategy::default());
+    assert_eq!(config.blocked_words, vec!["qwerty".to_string()]);
+
+    let config: SlugGeneratorConfig = serde_json::from_value(serde_json::json!({
+        "strategy": "sequential",
+        "salt": "secret",
+    }))
+    .unwrap();
+    assert_eq!(
+        config.strategy,
+        SlugStrategy::Sequential {
+            alphabet: DEFAULT_SLUG_ALPHABET.to_string(),
+            min_length: 1,
+            salt: Some("secret".to_string()),
+        }
+    );
+
+    assert!(
+        serde_json::from_value::<SlugGeneratorConfig>(serde_json::json!({ "strategy": "dice" }))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_encode_number() {
+    let alphabet: Vec<char> = "ab".chars().collect();
+    assert_eq!(encode_number(0, &alphabet, 0), "a");
+    assert_eq!(encode_number(5, &alphabet, 0), "bab");
+    assert_eq!(encode_number(5, &alphabet, 5), "aabab");
+}
+
+#[test]
+fn test_url_hash_slugs() {
+    let generator = from_config(&generator_config(SlugStrategy::UrlHash {
+        alphabet: DEFAULT_SLUG_ALPHABET.to_string(),
+        length: 6,
+    }))
+    .unwrap();
+
+    let slug = generator.generate(CONTEXT, 0);
+    assert_eq!(slug.len(), 6);
+    assert_eq!(slug, generator.generate(CONTEXT, 0));
+
+    // Collisions are resolved with longer prefixes of the same hash
+    let longer = generator.generate(CONTEXT, 1);
+    assert_eq!(longer.len(), 7);
+    assert!(longer.starts_with(&slug));
+    assert_eq!(generator.generate(CONTEXT, 100).len(), 106);
+
+    let other = SlugContext {
+        url: "https://example.org",
+        ..CONTEXT
+    };
+    assert_ne!(generator.generate(other, 0), slug);
+}
+
+#[test]
+fn test_invalid_alphabet() {
+    let config = generator_config(SlugStrategy::Random {
+        alphabet: "aaaa".to_string(),
+        length: 7,
+    });
+    assert!(from_config(&config).is_err());
+}
+
+#[test]
+fn test_offensive_word_filter() {
+    let filter = OffensiveWordFilter::new(&["Bad-Word".to_string()]);
+    assert!(filter.is_offensive("xxFuCkxx"));
+    assert!(filter.is_offensive("5h1t"));
+    assert!(filter.is_offensive("a-badword-b"));
+    assert!(filter.is_offensive("BADW0RD"));
+    assert!(!filter.is_offensive("brave-otter-42"));
+    assert!(!filter.is_offensive("Hx7kPq2"));
+}
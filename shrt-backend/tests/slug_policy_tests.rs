@@ -0,0 +1,54 @@
+use shrt_common::slugs::{SlugPolicy, SlugPolicyViolation};
+
+#[test]
+fn test_slug_policy_default() {
+    let policy = SlugPolicy::default();
+    assert!(policy.validate("my-link_2").is_ok());
+    assert_eq!(policy.validate(""), Err(SlugPolicyViolation::TooShort(1)));
+    assert_eq!(
+        policy.validate(&"a".repeat(65)),
+        Err(SlugPolicyViolation::TooLong(64))
+    );
+    assert_eq!(
+        policy.validate("a/b"),
+        Err(SlugPolicyViolation::InvalidCharacter('/'))
+    );
+    assert_eq!(
+        policy.validate("a b"),
+        Err(SlugPolicyViolation::InvalidCharacter(' '))
+    );
+    assert_eq!(
+        policy.validate("zażółć"),
+        Err(SlugPolicyViolation::InvalidCharacter('ż'))
+    );
+    assert_eq!(
+        policy.validate("Swagger"),
+        Err(SlugPolicyViolation::Reserved)
+    );
+}
+
+#[test]
+fn test_slug_policy_unicode_and_emoji() {
+    let policy = SlugPolicy {
+        allow_unicode: true,
+        ..SlugPolicy::default()
+    };
+    assert!(policy.validate("zażółć").is_ok());
+    assert!(policy.validate("日本語").is_ok());
+    assert_eq!(
+        policy.validate("🦀"),
+        Err(SlugPolicyViolation::InvalidCharacter('🦀'))
+    );
+
+    let policy = SlugPolicy {
+        allow_emoji: true,
+        ..SlugPolicy::default()
+    };
+    assert!(policy.validate("rust-🦀").is_ok());
+    assert!(policy.validate("👍🏽").is_ok());
+    assert!(policy.validate("❤️").is_ok());
+    assert_eq!(
+        policy.validate("a🦀 "),
+        Err(SlugPolicyViolation::InvalidCharacter(' '))
+    );
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use shrt_backend::stats::{
+    browser_name, bucket_start, language_region, referrer_host, time_buckets,
+};
+use shrt_common::stats::StatsInterval;
+
+fn time(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_bucket_start() {
+    let t = time("2026-03-12T15:42:10Z");
+    assert_eq!(
+        bucket_start(StatsInterval::Hour, t),
+        time("2026-03-12T15:00:00Z")
+    );
+    assert_eq!(
+        bucket_start(StatsInterval::Day, t),
+        time("2026-03-12T00:00:00Z")
+    );
+    assert_eq!(
+        bucket_start(StatsInterval::Week, t),
+        time("2026-03-09T00:00:00Z")
+    );
+    assert_eq!(
+        bucket_start(StatsInterval::Month, t),
+        time("2026-03-01T00:00:00Z")
+    );
+}
+
+#[test]
+fn test_time_buckets() {
+    let buckets = time_buckets(
+        StatsInterval::Month,
+        time("2025-11-15T00:00:00Z"),
+        time("2026-02-01T00:00:00Z"),
+    )
+    .unwrap();
+    assert_eq!(
+        buckets,
+        [
+            time("2025-11-01T00:00:00Z"),
+            time("2025-12-01T00:00:00Z"),
+            time("2026-01-01T00:00:00Z"),
+        ]
+    );
+
+    assert!(
+        time_buckets(
+            StatsInterval::Hour,
+            time("2020-01-01T00:00:00Z"),
+            time("2026-01-01T00:00:00Z"),
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_visitor_details() {
+    assert_eq!(
+        browser_name(Some(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+             Chrome/138.0.0.0 Safari/537.36 Edg/138.0.0.0"
+        )),
+        "Edge"
+    );
+    assert_eq!(browser_name(None), "Unknown");
+
+    assert_eq!(
+        language_region(Some("pl-PL,pl;q=0.9,en;q=0.8")),
+        Some("PL".to_string())
+    );
+    assert_eq!(language_region(Some("en")), None);
+
+    assert_eq!(referrer_host(None), "Direct");
+    assert_eq!(
+        referrer_host(Some("https://example.org/path")),
+        "example.org"
+    );
+}
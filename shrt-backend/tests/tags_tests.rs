@@ -0,0 +1,44 @@
+use shrt_common::tags::{MAX_TAGS_PER_LINK, TagViolation, normalize_tags, split_tags};
+
+fn tags(names: &[&str]) -> Vec<String> {
+    names.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_normalize_tags() {
+    assert_eq!(
+        normalize_tags(&tags(&[" Marketing ", "q3-launch", "marketing"])),
+        Ok(tags(&["marketing", "q3-launch"]))
+    );
+    assert_eq!(normalize_tags(&[]), Ok(Vec::new()));
+}
+
+#[test]
+fn test_normalize_tags_invalid() {
+    assert_eq!(normalize_tags(&tags(&["  "])), Err(TagViolation::Empty));
+    assert_eq!(
+        normalize_tags(&tags(&[&"a".repeat(33)])),
+        Err(TagViolation::TooLong(32))
+    );
+    assert_eq!(
+        normalize_tags(&tags(&["a,b"])),
+        Err(TagViolation::InvalidCharacter(','))
+    );
+
+    let too_many: Vec<String> = (0..=MAX_TAGS_PER_LINK)
+        .map(|i| format!("tag-{i}"))
+        .collect();
+    assert_eq!(
+        normalize_tags(&too_many),
+        Err(TagViolation::TooMany(MAX_TAGS_PER_LINK))
+    );
+}
+
+#[test]
+fn test_split_tags() {
+    assert_eq!(
+        split_tags(" news, q3 launch ,,"),
+        tags(&["news", "q3 launch"])
+    );
+    assert!(split_tags("").is_empty());
+}
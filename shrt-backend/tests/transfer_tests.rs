@@ -0,0 +1,142 @@
+use chrono::{TimeZone, Utc};
+use shrt_backend::csv::{self, CsvRow};
+use shrt_backend::transfer::{LinkRecord, file_header, parse_records};
+use shrt_common::links::QueryPassthrough;
+use shrt_common::transfer::LinkFileFormat;
+
+fn record() -> LinkRecord {
+    LinkRecord {
+        slug: "docs".to_string(),
+        url: "https://example.com/?a=1,2&b=\"x\"".to_string(),
+        created_at: Some(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()),
+        visits: 42,
+        expires_at: None,
+        fallback_url: Some("https://example.com/gone".to_string()),
+        max_visits: Some(100),
+        password_hash: None,
+        tags: vec!["docs".to_string(), "q3-launch".to_string()],
+        title: Some("Docs, \"v2\"".to_string()),
+        description: None,
+        notes: Some("Multi\nline".to_string()),
+        redirect_status: Some(308),
+        query_passthrough: QueryPassthrough::VisitorWins,
+        prefix: true,
+    }
+}
+
+#[test]
+fn test_csv_write_row() {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        ["plain", "with,comma", "with \"quotes\"", "multi\nline", ""],
+    );
+    assert_eq!(
+        out,
+        "plain,\"with,comma\",\"with \"\"quotes\"\"\",\"multi\nline\",\n"
+    );
+}
+
+#[test]
+fn test_csv_parse() {
+    let rows = csv::parse("\u{feff}a,b,c\r\n\r\n1,\"x,\"\"y\"\"\",3\n\"multi\nline\",,\n").unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            CsvRow {
+                line: 1,
+                fields: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            },
+            CsvRow {
+                line: 3,
+                fields: vec!["1".to_string(), "x,\"y\"".to_string(), "3".to_string()],
+            },
+            CsvRow {
+                line: 4,
+                fields: vec!["multi\nline".to_string(), String::new(), String::new()],
+            },
+        ]
+    );
+
+    // No trailing newline
+    let rows = csv::parse("a,b").unwrap();
+    assert_eq!(rows[0].fields, vec!["a".to_string(), "b".to_string()]);
+
+    assert_eq!(csv::parse("a\n\"unterminated\n").unwrap_err().line, 2);
+}
+
+#[test]
+fn test_csv_round_trip() {
+    let mut data = file_header(LinkFileFormat::Csv);
+    record().write(LinkFileFormat::Csv, &mut data);
+
+    let rows = parse_records(LinkFileFormat::Csv, &data).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].line, 2);
+    assert_eq!(rows[0].record, Ok(record()));
+}
+
+#[test]
+fn test_jsonl_round_trip() {
+    let mut data = file_header(LinkFileFormat::Jsonl);
+    record().write(LinkFileFormat::Jsonl, &mut data);
+    data.push('\n');
+    record().write(LinkFileFormat::Jsonl, &mut data);
+
+    let rows = parse_records(LinkFileFormat::Jsonl, &data).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].line, 3);
+    assert_eq!(rows[1].record, Ok(record()));
+}
+
+#[test]
+fn test_parse_csv_partial_columns() {
+    let data = "URL,Slug\nhttps://example.com,abc\nhttps://example.org,\n";
+    let rows = parse_records(LinkFileFormat::Csv, data).unwrap();
+    let record = rows[0].record.as_ref().unwrap();
+    assert_eq!(record.slug, "abc");
+    assert_eq!(record.url, "https://example.com");
+    assert_eq!(record.created_at, None);
+    assert_eq!(record.visits, 0);
+    assert_eq!(rows[1].record, Err("slug must not be empty".to_string()));
+
+    assert_eq!(
+        parse_records(LinkFileFormat::Csv, "slug,visits\nabc,1\n"),
+        Err("The file has no url column".to_string())
+    );
+    let rows = parse_records(
+        LinkFileFormat::Csv,
+        "slug,url,visits\nabc,https://a.b,many\n",
+    )
+    .unwrap();
+    assert!(
+        rows[0]
+            .record
+            .as_ref()
+            .unwrap_err()
+            .starts_with("Invalid visits")
+    );
+
+    let rows = parse_records(
+        LinkFileFormat::Csv,
+        "slug,url,tags,query_passthrough,prefix
+         abc,https://a.b,\"news, docs\",,false
+         def,https://a.b,,everything,
+",
+    )
+    .unwrap();
+    let record = rows[0].record.as_ref().unwrap();
+    assert_eq!(record.tags, ["news", "docs"]);
+    assert_eq!(record.query_passthrough, QueryPassthrough::Off);
+    assert!(!record.prefix);
+    assert_eq!(
+        rows[1].record,
+        Err("Invalid query_passthrough: everything".to_string())
+    );
+}
+
+#[test]
+fn test_parse_jsonl_invalid_line() {
+    let rows = parse_records(LinkFileFormat::Jsonl, "{\"slug\": \"a\"}\n").unwrap();
+    assert!(rows[0].record.is_err());
+}
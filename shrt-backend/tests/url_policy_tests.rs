@@ -0,0 +1,91 @@
+use shrt_backend::url_policy::{UrlPolicy, UrlPolicyViolation};
+
+fn policy() -> UrlPolicy {
+    UrlPolicy::new(
+        &["http".to_owned(), "https".to_owned()],
+        &["Blocked.example".to_owned(), "bücher.example.".to_owned()],
+        "https://sh.rt",
+    )
+}
+
+#[test]
+fn test_url_policy_allows_valid_urls() {
+    let policy = policy();
+    assert!(policy.check("https://example.com").is_ok());
+    assert!(
+        policy
+            .check("http://example.com/path?query=1#fragment")
+            .is_ok()
+    );
+    assert!(policy.check("https://notblocked.example").is_ok());
+    assert!(policy.check("https://sh.rt.example.com").is_ok());
+    assert!(policy.check("https://sh.rt:8443/abc").is_ok());
+}
+
+#[test]
+fn test_url_policy_rejects_invalid_urls() {
+    let policy = policy();
+    assert!(matches!(
+        policy.check("example.com"),
+        Err(UrlPolicyViolation::Invalid(_))
+    ));
+    assert!(matches!(
+        policy.check(""),
+        Err(UrlPolicyViolation::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_url_policy_scheme_allowlist() {
+    let policy = policy();
+    assert_eq!(
+        policy.check("javascript:alert(1)"),
+        Err(UrlPolicyViolation::SchemeNotAllowed(
+            "javascript".to_owned()
+        ))
+    );
+    assert_eq!(
+        policy.check("DATA:text/html,hello"),
+        Err(UrlPolicyViolation::SchemeNotAllowed("data".to_owned()))
+    );
+}
+
+#[test]
+fn test_url_policy_domain_blocklist() {
+    let policy = policy();
+    assert_eq!(
+        policy.check("https://blocked.example/path"),
+        Err(UrlPolicyViolation::BlockedDomain(
+            "blocked.example".to_owned()
+        ))
+    );
+    assert_eq!(
+        policy.check("https://WWW.Blocked.Example."),
+        Err(UrlPolicyViolation::BlockedDomain(
+            "blocked.example".to_owned()
+        ))
+    );
+    assert_eq!(
+        policy.check("https://bücher.example"),
+        Err(UrlPolicyViolation::BlockedDomain(
+            "xn--bcher-kva.example".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn test_url_policy_self_reference() {
+    let policy = policy();
+    assert_eq!(
+        policy.check("https://sh.rt/abc"),
+        Err(UrlPolicyViolation::SelfReference)
+    );
+    assert_eq!(
+        policy.check("http://SH.RT/abc"),
+        Err(UrlPolicyViolation::SelfReference)
+    );
+    assert_eq!(
+        policy.check("https://sh.rt:443"),
+        Err(UrlPolicyViolation::SelfReference)
+    );
+}
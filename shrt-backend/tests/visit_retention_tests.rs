@@ -0,0 +1,53 @@
+//! The visits are only removed once per interval in the whole process, so
+//! the test has a binary of its own.
+
+mod common;
+
+use common::{TestProject, stored_visits};
+use cot::test::TestServerBuilder;
+use shrt_common::links::LinkCreateRequest;
+
+#[cot::e2e_test]
+async fn test_visits_past_retention_are_removed() -> cot::Result<()> {
+    let server = TestServerBuilder::new(TestProject).start().await;
+    let url = server.url();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let create_request = LinkCreateRequest {
+        slug: Some("old".to_string()),
+        url: "https://example.com".to_string(),
+        ..Default::default()
+    };
+    let response = client
+        .post(format!("{url}/links"))
+        .json(&create_request)
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let response = client
+        .post(format!("{url}/test/links/old/old-visit"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    assert_eq!(stored_visits(&url, "old").await.len(), 1);
+
+    // The next visit removes the ones past the retention period
+    let response = client
+        .get(format!("{url}/links/old/go"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert!(response.status().is_redirection());
+
+    let visits = stored_visits(&url, "old").await;
+    assert_eq!(visits.len(), 1);
+    assert!(visits[0].visited_at > chrono::Utc::now() - chrono::TimeDelta::days(1));
+
+    server.close().await;
+    Ok(())
+}
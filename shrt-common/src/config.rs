@@ -1,10 +1,294 @@
 use serde::{Deserialize, Serialize};
 
+use crate::slugs::SlugPolicy;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
 pub struct AppConfig {
     pub app_name: String,
     pub base_url: String,
+    /// Number of days to keep the per-visit records for; unlimited if not set
+    pub visit_retention_days: Option<u32>,
+    /// Usernames of the users allowed to manage all links
+    #[serde(default, skip_serializing)]
+    pub admins: Vec<String>,
+    /// Maximum request rates of each client
+    #[serde(default, skip_serializing)]
+    pub rate_limits: RateLimits,
+    /// URL schemes the links are allowed to point to
+    #[serde(default = "default_allowed_schemes", skip_serializing)]
+    pub allowed_schemes: Vec<String>,
+    /// Domains the links are not allowed to point to, including their
+    /// subdomains
+    #[serde(default, skip_serializing)]
+    pub blocked_domains: Vec<String>,
+    /// Rules the custom slugs have to follow
+    #[serde(default)]
+    pub slug_policy: SlugPolicy,
+    /// How to generate the slugs of the links created without a custom one
+    #[serde(default, skip_serializing)]
+    pub slug_generator: SlugGeneratorConfig,
+    /// Whether to ignore the tracking query parameters, such as `utm_source`,
+    /// when looking for an existing link to the same URL
+    #[serde(default, skip_serializing)]
+    pub strip_tracking_parameters: bool,
+    /// Fetching the titles and favicons of the target pages of the links
+    /// created without a title
+    #[serde(default, skip_serializing)]
+    pub metadata_fetcher: MetadataFetcherConfig,
+    /// Default look of the QR codes of the links
+    #[serde(default, skip_serializing)]
+    pub qr_codes: QrCodeConfig,
+}
+
+fn default_allowed_schemes() -> Vec<String> {
+    vec!["http".to_owned(), "https".to_owned()]
+}
+
+/// The alphabet to generate the slugs from by default. Essentially
+/// [0-9A-Za-z], but with '0', 'o', 'O', '1', 'l', 'I' removed to avoid
+/// confusion (Base56 alphabet).
+pub const DEFAULT_SLUG_ALPHABET: &str = "23456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+const DEFAULT_SLUG_LENGTH: usize = 7;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(from = "SlugGeneratorConfigFile")]
+pub struct SlugGeneratorConfig {
+    #[serde(flatten)]
+    pub strategy: SlugStrategy,
+    /// Words that must not appear in the generated slugs, in addition to the
+    /// built-in list of offensive words
+    #[serde(default)]
+    pub blocked_words: Vec<String>,
+}
+
+/// [`SlugGeneratorConfig`] as written in the config file, with all the
+/// settings of the strategies flattened, so that the strategy can be left out
+/// along with any of its settings.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+struct SlugGeneratorConfigFile {
+    #[serde(default)]
+    strategy: SlugStrategyKind,
+    #[serde(default = "default_slug_alphabet")]
+    alphabet: String,
+    #[serde(default = "default_slug_length")]
+    length: usize,
+    #[serde(default = "default_word_digits")]
+    digits: usize,
+    #[serde(default = "default_sequential_length")]
+    min_length: usize,
+    #[serde(default)]
+    salt: Option<String>,
+    #[serde(default)]
+    blocked_words: Vec<String>,
+}
+
+#[derive(Copy, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+enum SlugStrategyKind {
+    #[default]
+    Random,
+    Words,
+    Sequential,
+    UrlHash,
+}
+
+impl From<SlugGeneratorConfigFile> for SlugGeneratorConfig {
+    fn from(config: SlugGeneratorConfigFile) -> Self {
+        let strategy = match config.strategy {
+            SlugStrategyKind::Random => SlugStrategy::Random {
+                alphabet: config.alphabet,
+                length: config.length,
+            },
+            SlugStrategyKind::Words => SlugStrategy::Words {
+                digits: config.digits,
+            },
+            SlugStrategyKind::Sequential => SlugStrategy::Sequential {
+                alphabet: config.alphabet,
+                min_length: config.min_length,
+                salt: config.salt,
+            },
+            SlugStrategyKind::UrlHash => SlugStrategy::UrlHash {
+                alphabet: config.alphabet,
+                length: config.length,
+            },
+        };
+
+        Self {
+            strategy,
+            blocked_words: config.blocked_words,
+        }
+    }
+}
+
+/// Ways of generating the slugs.
+///
+/// All the strategies retry with longer slugs if the generated ones are
+/// already taken.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum SlugStrategy {
+    /// Random characters from the alphabet
+    Random {
+        #[serde(default = "default_slug_alphabet")]
+        alphabet: String,
+        #[serde(default = "default_slug_length")]
+        length: usize,
+    },
+    /// Random words followed by a random number, e.g. `brave-otter-42`
+    Words {
+        /// Number of digits of the number
+        #[serde(default = "default_word_digits")]
+        digits: usize,
+    },
+    /// Consecutive numbers written using the alphabet; if `salt` is set, the
+    /// numbers are scrambled and the alphabet shuffled with it, so that the
+    /// slugs do not look sequential
+    Sequential {
+        #[serde(default = "default_slug_alphabet")]
+        alphabet: String,
+        #[serde(default = "default_sequential_length")]
+        min_length: usize,
+        #[serde(default)]
+        salt: Option<String>,
+    },
+    /// Hash of the target URL, so that the same URL always gets the same slug
+    UrlHash {
+        #[serde(default = "default_slug_alphabet")]
+        alphabet: String,
+        #[serde(default = "default_slug_length")]
+        length: usize,
+    },
+}
+
+impl Default for SlugStrategy {
+    fn default() -> Self {
+        Self::Random {
+            alphabet: default_slug_alphabet(),
+            length: default_slug_length(),
+        }
+    }
+}
+
+fn default_slug_alphabet() -> String {
+    DEFAULT_SLUG_ALPHABET.to_owned()
+}
+
+fn default_slug_length() -> usize {
+    DEFAULT_SLUG_LENGTH
+}
+
+fn default_word_digits() -> usize {
+    2
+}
+
+fn default_sequential_length() -> usize {
+    1
+}
+
+/// Fetching the titles and favicons of the target pages of the links in the
+/// background.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct MetadataFetcherConfig {
+    pub enabled: bool,
+    /// Time limit of fetching a page, in seconds
+    pub timeout_secs: u32,
+    /// Whether the pages can be fetched from the loopback and private network
+    /// addresses, which would otherwise let the users probe the internal
+    /// network
+    pub allow_private_addresses: bool,
+}
+
+impl Default for MetadataFetcherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: 10,
+            allow_private_addresses: false,
+        }
+    }
+}
+
+/// Default look of the QR codes of the links.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct QrCodeConfig {
+    /// Colour of the dark modules, as `#rrggbb`
+    pub foreground: String,
+    /// Colour of the light modules and the margin, as `#rrggbb`, or
+    /// `transparent`
+    pub background: String,
+    /// Path to a PNG or JPEG image to put in the middle of the QR codes
+    pub logo_path: Option<String>,
+}
+
+impl Default for QrCodeConfig {
+    fn default() -> Self {
+        Self {
+            foreground: "#000000".to_owned(),
+            background: "#ffffff".to_owned(),
+            logo_path: None,
+        }
+    }
+}
+
+/// Maximum request rates of each client, identified by the user ID if signed
+/// in, or by the IP address otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct RateLimits {
+    /// Creating new links
+    pub create_link: RateLimit,
+    /// Checking whether a slug is taken
+    pub link_exists: RateLimit,
+    /// Following, previewing or looking up links that do not exist
+    pub link_not_found: RateLimit,
+    /// Entering wrong passwords of the password-protected links
+    pub link_password: RateLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            create_link: RateLimit::per_minute(30),
+            link_exists: RateLimit::per_minute(120),
+            link_not_found: RateLimit::per_minute(30),
+            link_password: RateLimit::per_minute(10),
+        }
+    }
+}
+
+/// The number of requests allowed within a period of time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct RateLimit {
+    /// Maximum number of requests in the period; `0` disables the limit
+    pub requests: u32,
+    /// Length of the period, in seconds
+    pub period_secs: u32,
+}
+
+impl RateLimit {
+    #[must_use]
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            period_secs: 60,
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.requests > 0 && self.period_secs > 0
+    }
 }
 
 #[cfg(feature = "backend")]
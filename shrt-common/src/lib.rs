@@ -2,4 +2,10 @@ pub mod config;
 pub mod enums;
 pub mod errors;
 pub mod links;
+pub mod qr_codes;
+pub mod slugs;
+pub mod stats;
+pub mod tags;
 pub mod tile_set;
+pub mod tokens;
+pub mod transfer;
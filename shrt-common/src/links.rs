@@ -1,4 +1,15 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::enums::EnumName;
+use crate::errors::ServiceError;
+
+/// Maximum number of characters of the title of a link.
+pub const MAX_TITLE_LENGTH: usize = 200;
+/// Maximum number of characters of the description of a link.
+pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+/// Maximum number of characters of the notes of a link.
+pub const MAX_NOTES_LENGTH: usize = 10_000;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
@@ -9,24 +20,433 @@ pub struct LinksResponse {
     pub links: Vec<Link>,
 }
 
+/// A page of links listed with keyset pagination, which, unlike the page
+/// numbers, does not shift when links are added or removed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinksCursorResponse {
+    pub links: Vec<Link>,
+    /// Cursor to pass as `after` to get the next page; `None` on the last
+    /// page
+    pub next_cursor: Option<String>,
+    /// Cursor to pass as `before` to get the previous page; `None` on the
+    /// first page
+    pub prev_cursor: Option<String>,
+    /// Number of the links meeting the filters; only set if requested
+    #[serde(default)]
+    pub total_count: Option<u64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
 pub struct Link {
     pub slug: String,
-    pub url: String,
-    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// The target URL; `None` if the link is password protected and has not
+    /// been unlocked
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
     pub visits: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// URL to redirect to once the link has expired; `None` as well if the
+    /// target URL is not revealed
+    pub fallback_url: Option<String>,
+    /// Whether the link has expired and no longer redirects to its target
+    pub expired: bool,
+    pub max_visits: Option<i32>,
+    /// Whether the link has reached its maximum number of visits
+    pub exhausted: bool,
+    pub password_protected: bool,
+    /// Entity tag of the link's current settings, to be sent in the
+    /// `If-Match` header when updating the link
+    pub etag: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Title of the link, given by the user or taken from the target page;
+    /// `None` as well if the target URL is not revealed
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Free-text notes about the link; only included for the users allowed
+    /// to manage it
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// URL of the icon of the target page; `None` as well if the target URL
+    /// is not revealed
+    #[serde(default)]
+    pub favicon_url: Option<String>,
+    /// HTTP status code of the redirect to the target; see
+    /// [`RedirectStatus`]
+    #[serde(default = "default_redirect_status")]
+    pub redirect_status: u16,
+    #[serde(default)]
+    pub query_passthrough: QueryPassthrough,
+    /// Whether the path following the slug is forwarded to the target
+    #[serde(default)]
+    pub prefix: bool,
+}
+
+fn default_redirect_status() -> u16 {
+    RedirectStatus::default().code()
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
 pub struct LinkExists {
     pub exists: bool,
+    /// Why the slug cannot be used for a new link, if it violates the slug
+    /// policy
+    #[serde(default)]
+    pub invalid_reason: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
 pub struct LinkCreateRequest {
     pub slug: Option<String>,
     pub url: String,
+    /// Absolute time after which the link expires
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Number of seconds from creation after which the link expires; mutually
+    /// exclusive with `expires_at`
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+    /// URL to redirect to once the link has expired, instead of returning 410
+    /// Gone
+    #[serde(default)]
+    pub fallback_url: Option<String>,
+    /// Number of visits after which the link stops redirecting; `1` creates a
+    /// one-time link
+    #[serde(default)]
+    pub max_visits: Option<i32>,
+    /// Password required to follow the link
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Whether to return an existing link with a generated slug pointing to
+    /// the same URL instead of creating a new one; only applies to the links
+    /// without a custom slug or any of the other settings
+    #[serde(default)]
+    pub reuse_existing: bool,
+    /// Names of the tags to assign to the link
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Title of the link; taken from the target page if not set and fetching
+    /// the metadata is enabled
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Free-text notes about the link
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// HTTP status code of the redirect to the target: 301, 302 (default), 307
+    /// or 308
+    #[serde(default)]
+    pub redirect_status: Option<u16>,
+    /// Whether to pass the query parameters of the visitors on to the target
+    #[serde(default)]
+    pub query_passthrough: QueryPassthrough,
+    /// Whether to forward the path following the slug to the target, so that
+    /// `/{slug}/a/b` redirects to `{url}/a/b`
+    #[serde(default)]
+    pub prefix: bool,
+}
+
+/// Changes to an existing link; fields that are not set are left unchanged.
+///
+/// Optional settings of the link can be cleared by setting the corresponding
+/// field to `null`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkUpdateRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fallback_url: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_visits: Option<Option<i32>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub password: Option<Option<String>>,
+    /// Names of the tags to replace the link's tags with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub notes: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_passthrough: Option<QueryPassthrough>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<bool>,
+}
+
+/// Links to create in a single request.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkBatchCreateRequest {
+    pub links: Vec<LinkCreateRequest>,
+}
+
+/// Slugs of the links to remove or retrieve in a single request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkBatchSlugsRequest {
+    pub slugs: Vec<String>,
+}
+
+/// Results of a batch request, in the order of the requested items.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct BatchResponse<T> {
+    pub results: Vec<BatchItemResult<T>>,
+}
+
+/// Outcome of a single item of a batch request; exactly one of `result` and
+/// `error` is set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct BatchItemResult<T> {
+    /// HTTP status code the item would have been answered with if it was
+    /// requested on its own
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ServiceError>,
+}
+
+impl<T> BatchItemResult<T> {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Fields the list of links can be sorted by
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSortField {
+    #[default]
+    CreatedAt,
+    Visits,
+    Slug,
+}
+
+impl LinkSortField {
+    pub const ALL: [LinkSortField; 3] = [
+        LinkSortField::CreatedAt,
+        LinkSortField::Visits,
+        LinkSortField::Slug,
+    ];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.name() == name)
+    }
+}
+
+impl EnumName for LinkSortField {
+    fn name(&self) -> &'static str {
+        match self {
+            LinkSortField::CreatedAt => "created_at",
+            LinkSortField::Visits => "visits",
+            LinkSortField::Slug => "slug",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 2] = [SortOrder::Asc, SortOrder::Desc];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|order| order.name() == name)
+    }
+
+    #[must_use]
+    pub fn reversed(self) -> Self {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+}
+
+impl EnumName for SortOrder {
+    fn name(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// HTTP redirects a link can respond with.
+///
+/// The permanent ones can be cached by the browsers, so the later visits may
+/// not reach the server at all; the temporary ones are never cached.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RedirectStatus {
+    /// 301 Moved Permanently
+    MovedPermanently,
+    /// 302 Found
+    #[default]
+    Found,
+    /// 307 Temporary Redirect; unlike 302, the request method is kept
+    TemporaryRedirect,
+    /// 308 Permanent Redirect; unlike 301, the request method is kept
+    PermanentRedirect,
+}
+
+impl RedirectStatus {
+    pub const ALL: [RedirectStatus; 4] = [
+        RedirectStatus::MovedPermanently,
+        RedirectStatus::Found,
+        RedirectStatus::TemporaryRedirect,
+        RedirectStatus::PermanentRedirect,
+    ];
+
+    #[must_use]
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.code() == code)
+    }
+
+    #[must_use]
+    pub fn code(self) -> u16 {
+        match self {
+            RedirectStatus::MovedPermanently => 301,
+            RedirectStatus::Found => 302,
+            RedirectStatus::TemporaryRedirect => 307,
+            RedirectStatus::PermanentRedirect => 308,
+        }
+    }
+
+    #[must_use]
+    pub fn is_permanent(self) -> bool {
+        matches!(
+            self,
+            RedirectStatus::MovedPermanently | RedirectStatus::PermanentRedirect
+        )
+    }
+}
+
+/// How the query parameters of the visitors following a link are passed on to
+/// its target; the fragment of the target is always kept
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum QueryPassthrough {
+    /// The parameters of the visitors are dropped
+    #[default]
+    Off,
+    /// The parameters of the visitors replace the ones of the target with the
+    /// same names
+    VisitorWins,
+    /// The parameters of the visitors are only added if the target does not
+    /// have ones with the same names
+    TargetWins,
+    /// The parameters of the visitors are added after the ones of the target,
+    /// even if they have the same names
+    Append,
+}
+
+impl QueryPassthrough {
+    pub const ALL: [QueryPassthrough; 4] = [
+        QueryPassthrough::Off,
+        QueryPassthrough::VisitorWins,
+        QueryPassthrough::TargetWins,
+        QueryPassthrough::Append,
+    ];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+impl EnumName for QueryPassthrough {
+    fn name(&self) -> &'static str {
+        match self {
+            QueryPassthrough::Off => "off",
+            QueryPassthrough::VisitorWins => "visitor_wins",
+            QueryPassthrough::TargetWins => "target_wins",
+            QueryPassthrough::Append => "append",
+        }
+    }
+}
+
+/// Returns the host name of `url`, which is where a visitor actually ends
+/// up, e.g. `evil.example` for `https://bank.example@evil.example/`.
+#[must_use]
+pub fn target_domain(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '\\', '?', '#']).next().unwrap_or_default();
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host_and_port)| host_and_port);
+    let host = if host_and_port.starts_with('[') {
+        host_and_port
+            .split_once(']')
+            .map_or(host_and_port, |(host, _)| &host[1..])
+    } else {
+        host_and_port
+            .split_once(':')
+            .map_or(host_and_port, |(host, _)| host)
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// Deserializes a present field as `Some`, even if it is `null`, so that
+/// missing fields can be told apart from the cleared ones.
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::enums::EnumName;
+
+/// Width and height of the QR codes, in pixels, if not given.
+pub const DEFAULT_QR_CODE_SIZE: u32 = 256;
+/// Maximum width and height of the QR codes, in pixels.
+pub const MAX_QR_CODE_SIZE: u32 = 2048;
+/// Width of the empty border around the QR codes, in modules, if not given.
+pub const DEFAULT_QR_CODE_MARGIN: u32 = 4;
+/// Maximum width of the empty border around the QR codes, in modules.
+pub const MAX_QR_CODE_MARGIN: u32 = 32;
+
+/// Image formats the QR codes can be rendered to
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum QrCodeFormat {
+    /// Scalable vector graphics
+    #[default]
+    Svg,
+    /// Portable network graphics
+    Png,
+}
+
+impl QrCodeFormat {
+    pub const ALL: [QrCodeFormat; 2] = [QrCodeFormat::Svg, QrCodeFormat::Png];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            QrCodeFormat::Svg => "image/svg+xml",
+            QrCodeFormat::Png => "image/png",
+        }
+    }
+}
+
+impl EnumName for QrCodeFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            QrCodeFormat::Svg => "svg",
+            QrCodeFormat::Png => "png",
+        }
+    }
+}
+
+/// Error correction levels of the QR codes; the higher the level, the more of
+/// the code can be damaged or covered while it stays readable, at the cost of
+/// a denser code
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub enum ErrorCorrection {
+    /// About 7% of the code can be restored
+    #[serde(rename = "l")]
+    Low,
+    /// About 15% of the code can be restored
+    #[serde(rename = "m")]
+    Medium,
+    /// About 25% of the code can be restored
+    #[serde(rename = "q")]
+    Quartile,
+    /// About 30% of the code can be restored
+    #[serde(rename = "h")]
+    High,
+}
+
+impl ErrorCorrection {
+    pub const ALL: [ErrorCorrection; 4] = [
+        ErrorCorrection::Low,
+        ErrorCorrection::Medium,
+        ErrorCorrection::Quartile,
+        ErrorCorrection::High,
+    ];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|level| level.name() == name)
+    }
+}
+
+impl EnumName for ErrorCorrection {
+    fn name(&self) -> &'static str {
+        match self {
+            ErrorCorrection::Low => "l",
+            ErrorCorrection::Medium => "m",
+            ErrorCorrection::Quartile => "q",
+            ErrorCorrection::High => "h",
+        }
+    }
+}
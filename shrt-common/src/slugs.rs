@@ -0,0 +1,134 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Rules the custom slugs have to follow.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct SlugPolicy {
+    /// Minimum number of characters
+    pub min_length: usize,
+    /// Maximum number of characters
+    pub max_length: usize,
+    /// Whether letters and digits outside ASCII are allowed
+    pub allow_unicode: bool,
+    /// Whether emoji are allowed
+    pub allow_emoji: bool,
+    /// Slugs that cannot be used, compared case-insensitively
+    pub reserved: Vec<String>,
+}
+
+impl Default for SlugPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 1,
+            max_length: 64,
+            allow_unicode: false,
+            allow_emoji: false,
+            reserved: [
+                "admin",
+                "api",
+                "app",
+                "batch",
+                "batch-delete",
+                "batch-get",
+                "config",
+                "cursor",
+                "export",
+                "import",
+                "links",
+                "static",
+                "swagger",
+                "tags",
+                "tokens",
+            ]
+            .map(ToOwned::to_owned)
+            .to_vec(),
+        }
+    }
+}
+
+impl SlugPolicy {
+    /// Checks `slug` against the policy.
+    pub fn validate(&self, slug: &str) -> Result<(), SlugPolicyViolation> {
+        let length = slug.chars().count();
+        if length < self.min_length {
+            return Err(SlugPolicyViolation::TooShort(self.min_length));
+        }
+        if length > self.max_length {
+            return Err(SlugPolicyViolation::TooLong(self.max_length));
+        }
+
+        if let Some(c) = slug.chars().find(|&c| !self.is_allowed_char(c)) {
+            return Err(SlugPolicyViolation::InvalidCharacter(c));
+        }
+
+        if self
+            .reserved
+            .iter()
+            .any(|reserved| reserved.to_lowercase() == slug.to_lowercase())
+        {
+            return Err(SlugPolicyViolation::Reserved);
+        }
+
+        Ok(())
+    }
+
+    fn is_allowed_char(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+            || c == '-'
+            || c == '_'
+            || (self.allow_unicode && c.is_alphanumeric())
+            || (self.allow_emoji && is_emoji_char(c))
+    }
+}
+
+/// Returns whether `c` is an emoji, or one of the characters combining
+/// emoji into sequences.
+fn is_emoji_char(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x1F000..=0x1FAFF // Pictographs, emoticons, flags, skin tones
+            | 0x2600..=0x27BF // Miscellaneous symbols and dingbats
+            | 0x2B00..=0x2BFF // Arrows, stars and shapes
+            | 0x200D // Zero width joiner
+            | 0x20E3 // Combining enclosing keycap
+            | 0xFE0F // Emoji presentation selector
+    )
+}
+
+/// Reasons for a slug to be rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlugPolicyViolation {
+    /// The slug is shorter than the minimum length
+    TooShort(usize),
+    /// The slug is longer than the maximum length
+    TooLong(usize),
+    /// The slug contains a character that is not allowed
+    InvalidCharacter(char),
+    /// The slug is on the reserved list
+    Reserved,
+}
+
+impl Display for SlugPolicyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SlugPolicyViolation::TooShort(min_length) => {
+                write!(f, "Slug must be at least {min_length} characters long")
+            }
+            SlugPolicyViolation::TooLong(max_length) => {
+                write!(f, "Slug must be at most {max_length} characters long")
+            }
+            SlugPolicyViolation::InvalidCharacter(c) if c.is_whitespace() => {
+                write!(f, "Slug must not contain whitespace")
+            }
+            SlugPolicyViolation::InvalidCharacter(c) => {
+                write!(f, "Slug must not contain the character '{c}'")
+            }
+            SlugPolicyViolation::Reserved => write!(f, "Slug is reserved"),
+        }
+    }
+}
+
+impl std::error::Error for SlugPolicyViolation {}
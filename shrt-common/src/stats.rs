@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Length of the time buckets the visits are grouped into
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum StatsInterval {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkStats {
+    pub slug: String,
+    pub interval: StatsInterval,
+    /// Start of the requested range (inclusive)
+    pub from: DateTime<Utc>,
+    /// End of the requested range (exclusive)
+    pub to: DateTime<Utc>,
+    /// Number of visits in the requested range
+    pub total_visits: u64,
+    /// Number of visits per time bucket, including the empty ones
+    pub visits: Vec<VisitCount>,
+    pub top_referrers: Vec<TopEntry>,
+    pub top_browsers: Vec<TopEntry>,
+    /// Regions of the visitors' preferred languages, e.g. `GB` for `en-GB`
+    pub top_language_regions: Vec<TopEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct VisitCount {
+    /// Start of the time bucket
+    pub start: DateTime<Utc>,
+    pub visits: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct TopEntry {
+    pub name: String,
+    pub visits: u64,
+}
@@ -0,0 +1,75 @@
+use std::fmt::{Display, Formatter};
+
+/// Maximum number of characters of a tag name.
+pub const MAX_TAG_LENGTH: usize = 32;
+/// Maximum number of tags of a single link.
+pub const MAX_TAGS_PER_LINK: usize = 16;
+
+/// Returns the tag names in the form they are stored in: trimmed, lowercase
+/// and without duplicates, in the order they were given in.
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, TagViolation> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err(TagViolation::Empty);
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(TagViolation::TooLong(MAX_TAG_LENGTH));
+        }
+        if let Some(c) = tag.chars().find(|c| *c == ',' || c.is_control()) {
+            return Err(TagViolation::InvalidCharacter(c));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    if normalized.len() > MAX_TAGS_PER_LINK {
+        return Err(TagViolation::TooMany(MAX_TAGS_PER_LINK));
+    }
+    Ok(normalized)
+}
+
+/// Splits a comma-separated list of tags, skipping the empty items.
+#[must_use]
+pub fn split_tags(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Reasons for tags to be rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagViolation {
+    /// The tag name is empty
+    Empty,
+    /// The tag name is longer than the maximum length
+    TooLong(usize),
+    /// The tag name contains a character that is not allowed
+    InvalidCharacter(char),
+    /// The link has more than the maximum number of tags
+    TooMany(usize),
+}
+
+impl Display for TagViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagViolation::Empty => write!(f, "Tag must not be empty"),
+            TagViolation::TooLong(max_length) => {
+                write!(f, "Tag must be at most {max_length} characters long")
+            }
+            TagViolation::InvalidCharacter(',') => write!(f, "Tag must not contain commas"),
+            TagViolation::InvalidCharacter(_) => {
+                write!(f, "Tag must not contain control characters")
+            }
+            TagViolation::TooMany(max_tags) => {
+                write!(f, "Link can have at most {max_tags} tags")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TagViolation {}
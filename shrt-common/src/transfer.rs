@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::enums::EnumName;
+
+/// File formats the links can be exported to and imported from
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LinkFileFormat {
+    /// Comma-separated values, with a header row
+    #[default]
+    Csv,
+    /// A JSON object per line
+    Jsonl,
+}
+
+impl LinkFileFormat {
+    pub const ALL: [LinkFileFormat; 2] = [LinkFileFormat::Csv, LinkFileFormat::Jsonl];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    /// Returns the format of the file with the given name, based on its
+    /// extension.
+    #[must_use]
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(LinkFileFormat::Csv),
+            "jsonl" | "ndjson" => Some(LinkFileFormat::Jsonl),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            LinkFileFormat::Csv => "text/csv; charset=utf-8",
+            LinkFileFormat::Jsonl => "application/jsonl; charset=utf-8",
+        }
+    }
+}
+
+impl EnumName for LinkFileFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            LinkFileFormat::Csv => "csv",
+            LinkFileFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// What to do with the imported links whose slugs are already taken
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateStrategy {
+    /// Keep the existing link
+    #[default]
+    Skip,
+    /// Replace the existing link with the imported one
+    Overwrite,
+    /// Abort the whole import
+    Fail,
+}
+
+impl DuplicateStrategy {
+    pub const ALL: [DuplicateStrategy; 3] = [
+        DuplicateStrategy::Skip,
+        DuplicateStrategy::Overwrite,
+        DuplicateStrategy::Fail,
+    ];
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name)
+    }
+}
+
+impl EnumName for DuplicateStrategy {
+    fn name(&self) -> &'static str {
+        match self {
+            DuplicateStrategy::Skip => "skip",
+            DuplicateStrategy::Overwrite => "overwrite",
+            DuplicateStrategy::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkImportRequest {
+    #[serde(default)]
+    pub format: LinkFileFormat,
+    #[serde(default)]
+    pub on_duplicate: DuplicateStrategy,
+    /// Contents of the file, in the format of the export
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkImportReport {
+    /// Number of links created
+    pub created: u64,
+    /// Number of existing links replaced with the imported ones
+    pub overwritten: u64,
+    /// Number of imported links left out because their slugs are taken
+    pub skipped: u64,
+    /// Rows that could not be imported
+    pub errors: Vec<LinkImportError>,
+}
+
+/// A row of the imported file that could not be imported.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "backend", derive(schemars::JsonSchema))]
+pub struct LinkImportError {
+    /// Number of the line the row starts at, counting from 1
+    pub line: u64,
+    pub slug: Option<String>,
+    pub message: String,
+}
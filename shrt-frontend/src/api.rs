@@ -3,8 +3,14 @@ use std::num::NonZeroU64;
 use gloo_net::http::{Request, Response};
 use serde::de::DeserializeOwned;
 use shrt_common::config::AppConfig;
+use shrt_common::enums::EnumName;
 use shrt_common::errors::ServiceError;
-use shrt_common::links::{Link, LinkCreateRequest, LinkExists, LinksResponse};
+use shrt_common::links::{
+    Link, LinkCreateRequest, LinkExists, LinkSortField, LinkUpdateRequest, LinksResponse, SortOrder,
+};
+use shrt_common::qr_codes::QrCodeFormat;
+use shrt_common::tokens::{ApiToken, ApiTokenCreateRequest, ApiTokenCreated};
+use shrt_common::transfer::{LinkFileFormat, LinkImportReport, LinkImportRequest};
 
 const API_URL: Option<&str> = option_env!("SHRT_API_URL");
 
@@ -47,43 +53,70 @@ impl ShrtApi {
         Self::map_response(result).await
     }
 
+    /// Returns a page of the links whose slug or target URL contains
+    /// `search`, and which have `tag` if set, sorted by `sort`.
     pub async fn get_links(
         page: Option<NonZeroU64>,
         links_per_page: Option<NonZeroU64>,
+        search: &str,
+        tag: Option<&str>,
+        sort: LinkSortField,
+        order: SortOrder,
     ) -> Result<LinksResponse, ServiceError> {
         let page = page.unwrap_or(NonZeroU64::new(1).unwrap()).get();
         let links_per_page = links_per_page.unwrap_or(NonZeroU64::new(10).unwrap()).get();
 
-        let result = Request::get(&format!(
-            "{}/links?page={}&links_per_page={}",
+        let mut url = format!(
+            "{}/links?page={}&links_per_page={}&search={}&sort={}&order={}",
             api_url(),
             page,
-            links_per_page
-        ))
-        .send()
-        .await?;
+            links_per_page,
+            urlencoding::encode(search),
+            sort.name(),
+            order.name()
+        );
+        if let Some(tag) = tag {
+            url.push_str(&format!("&tag={}", urlencoding::encode(tag)));
+        }
+        let result = Request::get(&url).send().await?;
 
         Self::map_response(result).await
     }
 
-    pub async fn shorten_url(url: &str, slug: &str) -> Result<Link, ServiceError> {
-        let request = LinkCreateRequest {
-            url: url.to_string(),
-            slug: if slug.is_empty() {
-                None
-            } else {
-                Some(slug.to_string())
-            },
-        };
+    /// Returns the names of the tags assigned to any link.
+    pub async fn get_tags() -> Result<Vec<String>, ServiceError> {
+        let result = Request::get(&format!("{}/tags", api_url())).send().await?;
 
+        Self::map_response(result).await
+    }
+
+    pub async fn shorten_url(request: &LinkCreateRequest) -> Result<Link, ServiceError> {
         let result = Request::post(&format!("{}/links", api_url()))
-            .json(&request)?
+            .json(request)?
             .send()
             .await?;
 
         Self::map_response(result).await
     }
 
+    pub async fn update_link(
+        slug: &str,
+        etag: &str,
+        request: &LinkUpdateRequest,
+    ) -> Result<Link, ServiceError> {
+        let result = Request::patch(&format!(
+            "{}/links/{}",
+            api_url(),
+            urlencoding::encode(slug)
+        ))
+        .header("If-Match", etag)
+        .json(request)?
+        .send()
+        .await?;
+
+        Self::map_response(result).await
+    }
+
     pub async fn remove_link(slug: &str) -> Result<(), ServiceError> {
         let result = Request::delete(&format!(
             "{}/links/{}",
@@ -96,6 +129,67 @@ impl ShrtApi {
         Self::map_response_empty(result).await
     }
 
+    /// Returns the URL to download the exported links from.
+    #[must_use]
+    pub fn export_links_url(format: LinkFileFormat) -> String {
+        format!("{}/links/export?format={}", api_url(), format.name())
+    }
+
+    /// Returns the URL following the link.
+    #[must_use]
+    pub fn follow_link_url(slug: &str) -> String {
+        format!("{}/links/{}/go", api_url(), urlencoding::encode(slug))
+    }
+
+    /// Returns the URL of the QR code of the link.
+    #[must_use]
+    pub fn qr_code_url(slug: &str, format: QrCodeFormat) -> String {
+        format!(
+            "{}/links/{}/qr?format={}",
+            api_url(),
+            urlencoding::encode(slug),
+            format.name()
+        )
+    }
+
+    pub async fn import_links(
+        request: &LinkImportRequest,
+    ) -> Result<LinkImportReport, ServiceError> {
+        let result = Request::post(&format!("{}/links/import", api_url()))
+            .json(request)?
+            .send()
+            .await?;
+
+        Self::map_response(result).await
+    }
+
+    pub async fn get_tokens() -> Result<Vec<ApiToken>, ServiceError> {
+        let result = Request::get(&format!("{}/tokens", api_url()))
+            .send()
+            .await?;
+
+        Self::map_response(result).await
+    }
+
+    pub async fn create_token(
+        request: &ApiTokenCreateRequest,
+    ) -> Result<ApiTokenCreated, ServiceError> {
+        let result = Request::post(&format!("{}/tokens", api_url()))
+            .json(request)?
+            .send()
+            .await?;
+
+        Self::map_response(result).await
+    }
+
+    pub async fn revoke_token(id: i32) -> Result<(), ServiceError> {
+        let result = Request::delete(&format!("{}/tokens/{}", api_url(), id))
+            .send()
+            .await?;
+
+        Self::map_response_empty(result).await
+    }
+
     async fn map_response<T: DeserializeOwned>(result: Response) -> Result<T, ServiceError> {
         if result.ok() {
             Ok(result.json().await?)
@@ -0,0 +1,226 @@
+use chrono::{TimeDelta, Utc};
+use dioxus::prelude::*;
+use shrt_common::enums::EnumName;
+use shrt_common::errors::ServiceError;
+use shrt_common::tokens::{ApiToken, ApiTokenCreateRequest, TokenScope};
+
+use crate::api::ShrtApi;
+use crate::error_alert::ErrorAlert;
+use crate::input::Input;
+
+/// Token lifetimes to choose from, in days; `0` means no expiration.
+const EXPIRATION_DAYS: [u32; 4] = [30, 90, 365, 0];
+
+#[derive(Clone, Debug)]
+enum ApiTokensState {
+    Success(Vec<ApiToken>),
+    Error(ServiceError),
+    Loading,
+}
+
+#[component]
+pub fn ApiTokens() -> Element {
+    let mut state: Signal<ApiTokensState> = use_signal(|| ApiTokensState::Loading);
+    let mut iteration: Signal<u32> = use_signal(|| 0u32);
+    let mut name = use_signal(String::new);
+    let mut scope = use_signal(TokenScope::default);
+    let mut expiration_days = use_signal(|| EXPIRATION_DAYS[0]);
+    let mut created_secret: Signal<Option<String>> = use_signal(|| None);
+    let mut action_error: Signal<Option<ServiceError>> = use_signal(|| None);
+    let mut is_creating = use_signal(|| false);
+
+    use_effect(move || {
+        let _ = iteration();
+        spawn(async move {
+            match ShrtApi::get_tokens().await {
+                Ok(tokens) => state.set(ApiTokensState::Success(tokens)),
+                Err(e) => state.set(ApiTokensState::Error(e)),
+            }
+        });
+    });
+
+    let now = Utc::now();
+
+    rsx! {
+        h2 { class: "h4 mb-3", "API tokens" }
+        p {
+            "Personal API tokens allow scripts and other applications to use the API on your behalf. "
+            "Send them in the "
+            code { "Authorization: Bearer <token>" }
+            " header."
+        }
+
+        form {
+            class: "row g-2 align-items-end mb-4",
+            onsubmit: move |evt| {
+                evt.prevent_default();
+                let days = expiration_days();
+                let request = ApiTokenCreateRequest {
+                    name: name.read().clone(),
+                    scope: scope(),
+                    expires_at: (days > 0).then(|| Utc::now() + TimeDelta::days(days.into())),
+                };
+                is_creating.set(true);
+                spawn(async move {
+                    match ShrtApi::create_token(&request).await {
+                        Ok(created) => {
+                            created_secret.set(Some(created.secret));
+                            action_error.set(None);
+                            name.set(String::new());
+                            *iteration.write() += 1;
+                        }
+                        Err(e) => action_error.set(Some(e)),
+                    }
+                    is_creating.set(false);
+                });
+            },
+            div { class: "col-md-5",
+                label { r#for: "token-name", class: "form-label", "Name:" }
+                Input {
+                    on_set_value: move |v| name.set(v),
+                    value: name.read().clone(),
+                    disabled: is_creating(),
+                    required: true,
+                    placeholder: "e.g. backup script",
+                    id: "token-name",
+                }
+            }
+            div { class: "col-md-3",
+                label { r#for: "token-scope", class: "form-label", "Scope:" }
+                select {
+                    id: "token-scope",
+                    class: "form-select",
+                    disabled: is_creating(),
+                    onchange: move |e| {
+                        if let Some(s) = TokenScope::from_name(&e.value()) {
+                            scope.set(s);
+                        }
+                    },
+                    for s in TokenScope::ALL {
+                        option { value: s.name(), selected: s == scope(), {scope_label(s)} }
+                    }
+                }
+            }
+            div { class: "col-md-2",
+                label { r#for: "token-expiration", class: "form-label", "Expires in:" }
+                select {
+                    id: "token-expiration",
+                    class: "form-select",
+                    disabled: is_creating(),
+                    onchange: move |e| {
+                        if let Ok(days) = e.value().parse() {
+                            expiration_days.set(days);
+                        }
+                    },
+                    for days in EXPIRATION_DAYS {
+                        option { value: "{days}", selected: days == expiration_days(),
+                            if days == 0 {
+                                "Never"
+                            } else {
+                                "{days} days"
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "col-md-2",
+                button {
+                    r#type: "submit",
+                    class: "btn btn-outline-light w-100",
+                    disabled: is_creating(),
+                    "Create token"
+                }
+            }
+        }
+
+        if let Some(secret) = created_secret() {
+            div { class: "alert alert-success", role: "alert",
+                p { "Make sure to copy the new token now; it will not be shown again:" }
+                code { class: "user-select-all", "{secret}" }
+            }
+        }
+        if let Some(e) = action_error() {
+            ErrorAlert { message: "Could not update the API tokens", error: Some(e) }
+        }
+
+        match state.read().clone() {
+            ApiTokensState::Error(e) => rsx! {
+                ErrorAlert { message: "Could not retrieve the list of API tokens", error: Some(e) }
+            },
+            ApiTokensState::Loading => rsx! {
+                div { class: "spinner-border", role: "status",
+                    span { class: "visually-hidden", "Loading..." }
+                }
+            },
+            ApiTokensState::Success(tokens) => rsx! {
+                div { class: "table-responsive",
+                    table { class: "table table-striped table-hover",
+                        thead {
+                            tr {
+                                th { scope: "col", "Name" }
+                                th { scope: "col", "Token" }
+                                th { scope: "col", "Scope" }
+                                th { scope: "col", "Created at" }
+                                th { scope: "col", "Expires at" }
+                                th { scope: "col", "Last used" }
+                                th { scope: "col", "Actions" }
+                            }
+                        }
+                        tbody { class: "table-group-divider",
+                            for token in tokens {
+                                tr {
+                                    td { class: "text-truncate", style: "max-width: 12rem;",
+                                        "{token.name}"
+                                        if token.revoked_at.is_some() {
+                                            span { class: "badge text-bg-danger ms-1", "Revoked" }
+                                        } else if token.expires_at.is_some_and(|expires_at| expires_at <= now) {
+                                            span { class: "badge text-bg-secondary ms-1", "Expired" }
+                                        }
+                                    }
+                                    td {
+                                        code { "{token.prefix}…" }
+                                    }
+                                    td { {scope_label(token.scope)} }
+                                    td { "{format_date(Some(token.created_at))}" }
+                                    td { "{format_date(token.expires_at)}" }
+                                    td { "{format_date(token.last_used_at)}" }
+                                    td { class: "pt-1 pb-1 text-nowrap",
+                                        button {
+                                            onclick: move |_| {
+                                                spawn(async move {
+                                                    match ShrtApi::revoke_token(token.id).await {
+                                                        Ok(()) => action_error.set(None),
+                                                        Err(e) => action_error.set(Some(e)),
+                                                    }
+                                                    *iteration.write() += 1;
+                                                });
+                                            },
+                                            class: "btn btn-danger btn-sm",
+                                            disabled: token.revoked_at.is_some(),
+                                            i { class: "bi bi-x-circle-fill" }
+                                            " Revoke"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+fn scope_label(scope: TokenScope) -> &'static str {
+    match scope {
+        TokenScope::Read => "Read-only",
+        TokenScope::ReadWrite => "Read-write",
+    }
+}
+
+fn format_date(datetime: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    match datetime {
+        Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => "Never".to_string(),
+    }
+}
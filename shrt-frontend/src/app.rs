@@ -5,7 +5,9 @@ use shrt_common::config::AppConfig;
 use shrt_common::errors::ServiceError;
 
 use crate::api::ShrtApi;
+use crate::api_tokens::ApiTokens;
 use crate::error_alert::ErrorAlert;
+use crate::link_preview::{LinkPreview, LinkPreviewShortcut, PreviewSlug};
 use crate::link_result::LinkResult;
 use crate::list_links::ListLinks;
 use crate::not_found::NotFound;
@@ -18,8 +20,15 @@ pub enum Route {
     Home {},
     #[route("/app/link/:slug")]
     LinkResult { slug: String },
+    #[route("/app/preview/:slug")]
+    LinkPreview { slug: String },
     #[route("/app/links/:page")]
     ListLinks { page: NonZeroU64 },
+    #[route("/app/tokens")]
+    ApiTokens {},
+    /// `/{slug}+`, a shorthand for the preview of a link
+    #[route("/:slug")]
+    LinkPreviewShortcut { slug: PreviewSlug },
     #[route("/:..route")]
     NotFound { route: Vec<String> },
 }
@@ -45,6 +54,9 @@ fn Layout() -> Element {
                 }
                 div { class: "collapse navbar-collapse", id: "navbarCollapse",
                     ul { class: "navbar-nav ms-auto mb-2 mb-md-0",
+                        li { class: "nav-item",
+                            Link { to: Route::ApiTokens {}, class: "nav-link", "API tokens" }
+                        }
                         li { class: "nav-item",
                             a {
                                 class: "nav-link",
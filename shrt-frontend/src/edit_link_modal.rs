@@ -0,0 +1,310 @@
+use dioxus::prelude::*;
+use shrt_common::enums::EnumName;
+use shrt_common::errors::ServiceError;
+use shrt_common::links::{Link, LinkUpdateRequest, QueryPassthrough, RedirectStatus};
+use shrt_common::tags::split_tags;
+
+fn run_js(script: &str) {
+    js_sys::eval(script).ok();
+}
+
+use crate::api::ShrtApi;
+use crate::error_alert::ErrorAlert;
+use crate::input::Input;
+
+#[derive(Clone, Debug, Default)]
+enum EditLinkModalState {
+    Error(ServiceError),
+    Loading,
+    #[default]
+    Initial,
+}
+
+impl EditLinkModalState {
+    #[must_use]
+    pub fn is_loading(&self) -> bool {
+        matches!(self, EditLinkModalState::Loading)
+    }
+
+    #[must_use]
+    pub fn get_error(&self) -> Option<&ServiceError> {
+        match self {
+            EditLinkModalState::Error(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[component]
+pub fn EditLinkModal(#[props(default)] link: Option<Link>, on_edit: EventHandler<()>) -> Element {
+    let mut state: Signal<EditLinkModalState> = use_signal(EditLinkModalState::default);
+    let mut link_signal: Signal<Option<Link>> = use_signal(|| None);
+    let mut url = use_signal(String::new);
+    let mut fallback_url = use_signal(String::new);
+    let mut title = use_signal(String::new);
+    let mut description = use_signal(String::new);
+    let mut notes = use_signal(String::new);
+    let mut tags = use_signal(String::new);
+    let mut redirect_status = use_signal(|| RedirectStatus::default().code());
+    let mut query_passthrough = use_signal(QueryPassthrough::default);
+    let mut prefix = use_signal(|| false);
+
+    if *link_signal.peek() != link {
+        link_signal.set(link.clone());
+    }
+
+    use_effect(move || {
+        let Some(l) = link_signal() else {
+            return;
+        };
+        url.set(l.url.unwrap_or_default());
+        fallback_url.set(l.fallback_url.unwrap_or_default());
+        title.set(l.title.unwrap_or_default());
+        description.set(l.description.unwrap_or_default());
+        notes.set(l.notes.unwrap_or_default());
+        tags.set(l.tags.join(", "));
+        redirect_status.set(l.redirect_status);
+        query_passthrough.set(l.query_passthrough);
+        prefix.set(l.prefix);
+        state.set(EditLinkModalState::Initial);
+        run_js(
+            "bootstrap.Modal.getOrCreateInstance(\
+             document.getElementById('editLinkModal'),\
+             {backdrop:true,focus:true,keyboard:true}).show()",
+        );
+    });
+
+    let is_loading = state.read().is_loading();
+    let error = state.read().get_error().cloned();
+    let slug = link.as_ref().map(|l| l.slug.clone()).unwrap_or_default();
+    let password_protected = link.as_ref().is_some_and(|l| l.url.is_none());
+
+    rsx! {
+        div { class: "modal", id: "editLinkModal", tabindex: "-1",
+            div { class: "modal-dialog",
+                form {
+                    class: "modal-content",
+                    onsubmit: move |evt| {
+                        evt.prevent_default();
+                        let Some(l) = link.clone() else {
+                            return;
+                        };
+                        if is_loading {
+                            return;
+                        }
+                        let url_val = url.read().clone();
+                        let fallback_url_val = fallback_url.read().clone();
+                        let request = LinkUpdateRequest {
+                            url: if url_val.is_empty() { None } else { Some(url_val) },
+                            fallback_url: Some(
+                                if fallback_url_val.is_empty() { None } else { Some(fallback_url_val) },
+                            ),
+                            title: Some(non_empty(title.read().clone())),
+                            description: Some(non_empty(description.read().clone())),
+                            notes: Some(non_empty(notes.read().clone())),
+                            tags: Some(split_tags(&tags.read())),
+                            redirect_status: Some(*redirect_status.read()),
+                            query_passthrough: Some(*query_passthrough.read()),
+                            prefix: Some(*prefix.read()),
+                            ..Default::default()
+                        };
+                        state.set(EditLinkModalState::Loading);
+                        spawn(async move {
+                            match ShrtApi::update_link(&l.slug, &l.etag, &request).await {
+                                Ok(_) => {
+                                    state.set(EditLinkModalState::Initial);
+                                    run_js(
+                                        "bootstrap.Modal.getInstance(\
+                                         document.getElementById('editLinkModal')).hide()",
+                                    );
+                                    on_edit.call(());
+                                }
+                                Err(e) => {
+                                    state.set(EditLinkModalState::Error(e));
+                                }
+                            }
+                        });
+                    },
+                    div { class: "modal-header",
+                        h5 { class: "modal-title", "Edit link `{slug}`" }
+                        button {
+                            r#type: "button",
+                            class: if is_loading { "btn-close disabled" } else { "btn-close" },
+                            "data-bs-dismiss": "modal",
+                            "aria-label": "Close",
+                        }
+                    }
+                    div { class: "modal-body",
+                        div { class: "mb-3",
+                            label { r#for: "edit-url", class: "form-label", "Target URL:" }
+                            Input {
+                                on_set_value: move |v| url.set(v),
+                                value: url.read().clone(),
+                                disabled: is_loading,
+                                required: !password_protected,
+                                placeholder: if password_protected { "<unchanged>" } else { "" },
+                                id: "edit-url",
+                                input_type: "url",
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-fallback-url", class: "form-label",
+                                "Fallback URL after expiration:"
+                            }
+                            Input {
+                                on_set_value: move |v| fallback_url.set(v),
+                                value: fallback_url.read().clone(),
+                                disabled: is_loading,
+                                placeholder: "<none>",
+                                id: "edit-fallback-url",
+                                input_type: "url",
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-redirect-status", class: "form-label",
+                                "Redirect:"
+                            }
+                            select {
+                                class: "form-select",
+                                id: "edit-redirect-status",
+                                disabled: is_loading,
+                                onchange: move |evt| {
+                                    if let Ok(code) = evt.value().parse() {
+                                        redirect_status.set(code);
+                                    }
+                                },
+                                for status in RedirectStatus::ALL {
+                                    option {
+                                        value: "{status.code()}",
+                                        selected: *redirect_status.read() == status.code(),
+                                        {redirect_status_label(status)}
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-query-passthrough", class: "form-label",
+                                "Visitors' query parameters:"
+                            }
+                            select {
+                                class: "form-select",
+                                id: "edit-query-passthrough",
+                                disabled: is_loading,
+                                onchange: move |evt| {
+                                    if let Some(mode) = QueryPassthrough::from_name(&evt.value()) {
+                                        query_passthrough.set(mode);
+                                    }
+                                },
+                                for mode in QueryPassthrough::ALL {
+                                    option {
+                                        value: mode.name(),
+                                        selected: *query_passthrough.read() == mode,
+                                        {query_passthrough_label(mode)}
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "form-check mb-3",
+                            input {
+                                class: "form-check-input",
+                                r#type: "checkbox",
+                                id: "edit-prefix",
+                                checked: *prefix.read(),
+                                disabled: is_loading,
+                                onchange: move |evt| prefix.set(evt.checked()),
+                            }
+                            label { r#for: "edit-prefix", class: "form-check-label",
+                                "Forward the path following the slug to the URL"
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-title", class: "form-label", "Title:" }
+                            Input {
+                                on_set_value: move |v| title.set(v),
+                                value: title.read().clone(),
+                                disabled: is_loading,
+                                placeholder: "<none>",
+                                id: "edit-title",
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-description", class: "form-label", "Description:" }
+                            Input {
+                                on_set_value: move |v| description.set(v),
+                                value: description.read().clone(),
+                                disabled: is_loading,
+                                placeholder: "<none>",
+                                id: "edit-description",
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-notes", class: "form-label", "Notes:" }
+                            textarea {
+                                class: "form-control",
+                                id: "edit-notes",
+                                rows: 3,
+                                disabled: is_loading,
+                                value: notes.read().clone(),
+                                oninput: move |e| notes.set(e.value()),
+                            }
+                        }
+                        div { class: "mb-3",
+                            label { r#for: "edit-tags", class: "form-label", "Tags:" }
+                            Input {
+                                on_set_value: move |v| tags.set(v),
+                                value: tags.read().clone(),
+                                disabled: is_loading,
+                                placeholder: "Comma-separated, e.g. marketing, q3-launch",
+                                id: "edit-tags",
+                            }
+                        }
+                        if let Some(e) = error {
+                            ErrorAlert { message: "Could not update the link", error: Some(e) }
+                        }
+                    }
+                    div { class: "modal-footer",
+                        button {
+                            r#type: "button",
+                            class: if is_loading { "btn btn-secondary disabled" } else { "btn btn-secondary" },
+                            "data-bs-dismiss": "modal",
+                            "Cancel"
+                        }
+                        button {
+                            r#type: "submit",
+                            class: if is_loading { "btn btn-primary disabled" } else { "btn btn-primary" },
+                            if is_loading {
+                                div { class: "spinner-border spinner-border-sm", role: "status",
+                                    span { class: "visually-hidden", "Loading..." }
+                                }
+                                " "
+                            }
+                            "Save"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() { None } else { Some(value) }
+}
+
+fn redirect_status_label(status: RedirectStatus) -> &'static str {
+    match status {
+        RedirectStatus::MovedPermanently => "301 Moved Permanently (cached by browsers)",
+        RedirectStatus::Found => "302 Found",
+        RedirectStatus::TemporaryRedirect => "307 Temporary Redirect",
+        RedirectStatus::PermanentRedirect => "308 Permanent Redirect (cached by browsers)",
+    }
+}
+
+fn query_passthrough_label(mode: QueryPassthrough) -> &'static str {
+    match mode {
+        QueryPassthrough::Off => "Dropped",
+        QueryPassthrough::VisitorWins => "Passed on, replacing the target's ones",
+        QueryPassthrough::TargetWins => "Passed on, unless the target has them",
+        QueryPassthrough::Append => "Passed on, after the target's ones",
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use dioxus::prelude::*;
+use shrt_common::errors::ServiceError;
+use shrt_common::links::{Link, target_domain};
+
+use crate::api::ShrtApi;
+use crate::error_alert::ErrorAlert;
+
+/// A slug followed by `+`, the short form of the preview URL of a link.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreviewSlug(pub String);
+
+impl FromStr for PreviewSlug {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_suffix('+')
+            .filter(|slug| !slug.is_empty())
+            .map(|slug| PreviewSlug(slug.to_owned()))
+            .ok_or("not a preview URL")
+    }
+}
+
+impl Display for PreviewSlug {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}+", self.0)
+    }
+}
+
+#[component]
+pub fn LinkPreviewShortcut(slug: PreviewSlug) -> Element {
+    rsx! {
+        LinkPreview { slug: slug.0 }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum LinkPreviewState {
+    Success(Link),
+    Error(ServiceError),
+    Loading,
+}
+
+/// Shows where a link leads without following it.
+#[component]
+pub fn LinkPreview(slug: String) -> Element {
+    let mut state: Signal<LinkPreviewState> = use_signal(|| LinkPreviewState::Loading);
+    let mut slug_signal = use_signal(|| slug.clone());
+
+    if *slug_signal.peek() != slug {
+        slug_signal.set(slug.clone());
+    }
+
+    use_effect(move || {
+        let s = slug_signal();
+        state.set(LinkPreviewState::Loading);
+        spawn(async move {
+            match ShrtApi::get_link(&s).await {
+                Ok(link) => state.set(LinkPreviewState::Success(link)),
+                Err(e) => state.set(LinkPreviewState::Error(e)),
+            }
+        });
+    });
+
+    let link = match state.read().clone() {
+        LinkPreviewState::Success(link) => link,
+        LinkPreviewState::Error(e) => {
+            return rsx! {
+                ErrorAlert { message: "Could not retrieve the link", error: Some(e) }
+            };
+        }
+        LinkPreviewState::Loading => {
+            return rsx! {
+                div { class: "placeholder-glow",
+                    span { class: "placeholder col-8" }
+                }
+            };
+        }
+    };
+
+    let (destination, notice, can_continue) = if link.expired {
+        match link.fallback_url.clone() {
+            Some(fallback_url) => (
+                Some(fallback_url),
+                Some("This link has expired and leads to its fallback URL instead."),
+                true,
+            ),
+            None => (None, Some("This link has expired."), false),
+        }
+    } else if link.exhausted {
+        (
+            None,
+            Some("This link has reached its limit of visits."),
+            false,
+        )
+    } else if link.url.is_some() {
+        (link.url.clone(), None, true)
+    } else {
+        (
+            None,
+            Some("This link is password protected; its destination is shown after unlocking it."),
+            true,
+        )
+    };
+    let domain = destination
+        .as_deref()
+        .map(|url| target_domain(url).unwrap_or(url).to_owned());
+
+    rsx! {
+        p { class: "h2", "Link preview" }
+        p { class: "lead",
+            "Short link: "
+            code { "{link.slug}" }
+        }
+        if let Some(notice) = notice {
+            div { class: "alert alert-warning", role: "alert", "{notice}" }
+        }
+        dl { class: "row",
+            if let Some(title) = link.title.clone() {
+                dt { class: "col-sm-3", "Title" }
+                dd { class: "col-sm-9 text-break", "{title}" }
+            }
+            if let (Some(destination), Some(domain)) = (destination, domain) {
+                dt { class: "col-sm-3", "Destination" }
+                dd { class: "col-sm-9 text-break",
+                    code { "{destination}" }
+                }
+                dt { class: "col-sm-3", "Domain" }
+                dd { class: "col-sm-9",
+                    strong { "{domain}" }
+                }
+            }
+            dt { class: "col-sm-3", "Created" }
+            dd { class: "col-sm-9", {link.created_at.format("%Y-%m-%d %H:%M:%S").to_string()} }
+            dt { class: "col-sm-3", "Visits" }
+            dd { class: "col-sm-9", "{link.visits}" }
+        }
+        if can_continue {
+            a {
+                class: "btn btn-primary",
+                href: ShrtApi::follow_link_url(&link.slug),
+                rel: "noreferrer",
+                "Continue"
+                i { class: "bi bi-arrow-right ms-1" }
+            }
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use dioxus::prelude::*;
 use shrt_common::config::AppConfig;
+use shrt_common::enums::EnumName;
 use shrt_common::errors::ServiceError;
+use shrt_common::qr_codes::QrCodeFormat;
 
 use crate::api::ShrtApi;
 use crate::error_alert::ErrorAlert;
 
 #[derive(Clone, Debug)]
 enum LinkResultState {
-    Success { url: String },
+    Success {
+        url: Option<String>,
+        title: Option<String>,
+    },
     Error(ServiceError),
     Loading,
 }
@@ -29,7 +34,10 @@ pub fn LinkResult(slug: String) -> Element {
         state.set(LinkResultState::Loading);
         spawn(async move {
             match ShrtApi::get_link(&s).await {
-                Ok(link) => state.set(LinkResultState::Success { url: link.url }),
+                Ok(link) => state.set(LinkResultState::Success {
+                    url: link.url,
+                    title: link.title,
+                }),
                 Err(e) => state.set(LinkResultState::Error(e)),
             }
         });
@@ -39,17 +47,49 @@ pub fn LinkResult(slug: String) -> Element {
     let base_url = config.read().base_url.clone();
     let shortened_url = format!("{}/{}", base_url, urlencoding::encode(&slug));
 
+    let title = match &*state.read() {
+        LinkResultState::Success { title, .. } => title.clone(),
+        _ => None,
+    };
+
     rsx! {
+        if let Some(title) = title {
+            p { class: "h3 text-break", {title} }
+        }
         div { class: "mb-3",
             p { class: "h1", "Shortened URL:" }
             a { href: shortened_url.clone(), class: "lead", {shortened_url.clone()} }
         }
+        div { class: "mb-3",
+            img {
+                src: ShrtApi::qr_code_url(&slug, QrCodeFormat::Svg),
+                alt: "QR code of {shortened_url}",
+                width: 192,
+                height: 192,
+                class: "d-block mb-2 rounded",
+            }
+            for format in QrCodeFormat::ALL {
+                a {
+                    class: "btn btn-outline-light btn-sm me-2",
+                    href: ShrtApi::qr_code_url(&slug, format),
+                    download: "{slug}.{format.name()}",
+                    i { class: "bi bi-download" }
+                    " {format.name().to_uppercase()}"
+                }
+            }
+        }
         div { class: "mb-3 text-truncate placeholder-glow",
             p { class: "h2", "Target URL:" }
             match state.read().clone() {
-                LinkResultState::Success { url } => rsx! {
+                LinkResultState::Success { url: Some(url), .. } => rsx! {
                     a { href: url.clone(), {url.clone()} }
                 },
+                LinkResultState::Success { url: None, .. } => rsx! {
+                    span { class: "text-body-secondary",
+                        i { class: "bi bi-lock-fill" }
+                        " Password protected"
+                    }
+                },
                 LinkResultState::Error(e) => rsx! {
                     ErrorAlert {
                         message: "Could not retrieve the target URL",
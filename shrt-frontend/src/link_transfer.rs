@@ -0,0 +1,151 @@
+use dioxus::prelude::*;
+use shrt_common::enums::EnumName;
+use shrt_common::errors::ServiceError;
+use shrt_common::transfer::{
+    DuplicateStrategy, LinkFileFormat, LinkImportReport, LinkImportRequest,
+};
+
+use crate::api::ShrtApi;
+use crate::error_alert::ErrorAlert;
+
+#[derive(Clone, Debug, Default)]
+enum LinkTransferState {
+    Imported(LinkImportReport),
+    Error(ServiceError),
+    /// The selected file could not be read, for the given reason
+    FileError(String),
+    Loading,
+    #[default]
+    Initial,
+}
+
+/// Buttons to export the links to a file and to import them from one.
+#[component]
+pub fn LinkTransfer(on_import: EventHandler<()>) -> Element {
+    let mut state: Signal<LinkTransferState> = use_signal(LinkTransferState::default);
+    let mut on_duplicate = use_signal(DuplicateStrategy::default);
+
+    let is_loading = matches!(*state.read(), LinkTransferState::Loading);
+
+    rsx! {
+        div { class: "d-flex flex-wrap gap-2 justify-content-end align-items-center mb-3",
+            for format in LinkFileFormat::ALL {
+                a {
+                    class: "btn btn-outline-light btn-sm",
+                    href: ShrtApi::export_links_url(format),
+                    download: "links.{format.name()}",
+                    i { class: "bi bi-download" }
+                    " Export {format_label(format)}"
+                }
+            }
+            select {
+                class: "form-select form-select-sm w-auto",
+                title: "What to do with the imported links whose slugs are taken",
+                disabled: is_loading,
+                onchange: move |e| {
+                    if let Some(strategy) = DuplicateStrategy::from_name(&e.value()) {
+                        on_duplicate.set(strategy);
+                    }
+                },
+                for strategy in DuplicateStrategy::ALL {
+                    option {
+                        value: strategy.name(),
+                        selected: strategy == on_duplicate(),
+                        {duplicate_strategy_label(strategy)}
+                    }
+                }
+            }
+            label {
+                r#for: "import-file",
+                class: if is_loading { "btn btn-outline-light btn-sm mb-0 disabled" } else { "btn btn-outline-light btn-sm mb-0" },
+                i { class: "bi bi-upload" }
+                " Import"
+                if is_loading {
+                    div { class: "spinner-border spinner-border-sm ms-2", role: "status",
+                        span { class: "visually-hidden", "Loading..." }
+                    }
+                }
+            }
+            input {
+                id: "import-file",
+                r#type: "file",
+                class: "d-none",
+                accept: ".csv,.jsonl,.ndjson",
+                disabled: is_loading,
+                onchange: move |e| {
+                    let Some(file) = e.files().into_iter().next() else {
+                        return;
+                    };
+                    let format = LinkFileFormat::from_file_name(&file.name()).unwrap_or_default();
+                    state.set(LinkTransferState::Loading);
+                    spawn(async move {
+                        let data = match file.read_string().await {
+                            Ok(data) => data,
+                            Err(e) => {
+                                state.set(LinkTransferState::FileError(e.to_string()));
+                                return;
+                            }
+                        };
+                        let request = LinkImportRequest {
+                            format,
+                            on_duplicate: on_duplicate(),
+                            data,
+                        };
+                        match ShrtApi::import_links(&request).await {
+                            Ok(report) => {
+                                state.set(LinkTransferState::Imported(report));
+                                on_import.call(());
+                            }
+                            Err(e) => state.set(LinkTransferState::Error(e)),
+                        }
+                    });
+                },
+            }
+        }
+        match state() {
+            LinkTransferState::Imported(report) => rsx! {
+                div {
+                    class: if report.errors.is_empty() { "alert alert-success" } else { "alert alert-warning" },
+                    role: "alert",
+                    "Created {report.created}, overwritten {report.overwritten} and skipped {report.skipped} links."
+                    if !report.errors.is_empty() {
+                        " The following rows could not be imported:"
+                        ul { class: "mb-0",
+                            for row in report.errors {
+                                li {
+                                    "Line {row.line}"
+                                    if let Some(slug) = row.slug {
+                                        " ({slug})"
+                                    }
+                                    ": {row.message}"
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            LinkTransferState::Error(e) => rsx! {
+                ErrorAlert { message: "Could not import the links", error: Some(e) }
+            },
+            LinkTransferState::FileError(reason) => rsx! {
+                ErrorAlert { message: "Could not read the file: {reason}" }
+            },
+            LinkTransferState::Loading | LinkTransferState::Initial => rsx! {},
+        }
+    }
+}
+
+fn format_label(format: LinkFileFormat) -> &'static str {
+    match format {
+        LinkFileFormat::Csv => "CSV",
+        LinkFileFormat::Jsonl => "JSON Lines",
+    }
+}
+
+fn duplicate_strategy_label(strategy: DuplicateStrategy) -> &'static str {
+    match strategy {
+        DuplicateStrategy::Skip => "Skip existing slugs",
+        DuplicateStrategy::Overwrite => "Overwrite existing slugs",
+        DuplicateStrategy::Fail => "Fail on existing slugs",
+    }
+}
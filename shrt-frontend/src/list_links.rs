@@ -4,11 +4,14 @@ use dioxus::prelude::*;
 use rand::seq::IndexedRandom;
 use shrt_common::config::AppConfig;
 use shrt_common::errors::ServiceError;
-use shrt_common::links::LinksResponse;
+use shrt_common::links::{Link, LinkSortField, LinksResponse, SortOrder};
 
 use crate::api::ShrtApi;
 use crate::app::Route;
+use crate::edit_link_modal::EditLinkModal;
 use crate::error_alert::ErrorAlert;
+use crate::input::Input;
+use crate::link_transfer::LinkTransfer;
 use crate::pagination::Pagination;
 use crate::remove_link_modal::RemoveLinkModal;
 
@@ -33,8 +36,15 @@ impl ListLinksState {
 pub fn ListLinks(page: NonZeroU64) -> Element {
     let mut state: Signal<ListLinksState> = use_signal(|| ListLinksState::Loading);
     let mut removing_link_slug = use_signal(String::new);
+    let mut editing_link: Signal<Option<Link>> = use_signal(|| None);
     let mut iteration: Signal<u32> = use_signal(|| 0u32);
     let mut page_signal = use_signal(|| page);
+    let mut search_input = use_signal(String::new);
+    let mut search = use_signal(String::new);
+    let mut sort = use_signal(|| LinkSortField::CreatedAt);
+    let mut order = use_signal(|| SortOrder::Desc);
+    let mut tag: Signal<Option<String>> = use_signal(|| None);
+    let mut tags: Signal<Vec<String>> = use_signal(Vec::new);
     let navigator = use_navigator();
 
     if *page_signal.peek() != page {
@@ -43,16 +53,31 @@ pub fn ListLinks(page: NonZeroU64) -> Element {
 
     use_effect(move || {
         let p = page_signal();
+        let search = search();
+        let tag = tag();
+        let sort = sort();
+        let order = order();
         let _ = iteration();
         state.set(ListLinksState::Loading);
         spawn(async move {
-            match ShrtApi::get_links(Some(p), None).await {
+            match ShrtApi::get_links(Some(p), None, &search, tag.as_deref(), sort, order).await {
                 Ok(r) => state.set(ListLinksState::Success(r)),
                 Err(e) => state.set(ListLinksState::Error(e)),
             }
         });
     });
 
+    use_effect(move || {
+        let _ = iteration();
+        spawn(async move {
+            // The filter is only a convenience, so the list is shown without
+            // it if the tags cannot be retrieved
+            if let Ok(t) = ShrtApi::get_tags().await {
+                tags.set(t);
+            }
+        });
+    });
+
     let page_num = match &*state.read() {
         ListLinksState::Success(r) => NonZeroU64::new(r.num_pages),
         _ => None,
@@ -61,24 +86,103 @@ pub fn ListLinks(page: NonZeroU64) -> Element {
     let error = state.read().get_error().cloned();
     let config = use_context::<Signal<AppConfig>>();
 
+    let go_to_first_page = move || {
+        if page.get() != 1 {
+            navigator.push(Route::ListLinks {
+                page: NonZeroU64::MIN,
+            });
+        }
+    };
+
+    let mut on_sort = move |field: LinkSortField| {
+        if sort() == field {
+            order.set(order().reversed());
+        } else {
+            sort.set(field);
+            order.set(default_order(field));
+        }
+    };
+
     rsx! {
         if let Some(e) = error {
             ErrorAlert { message: "Could not retrieve the list of links", error: Some(e) }
         } else {
+            LinkTransfer { on_import: move |_| *iteration.write() += 1 }
+
+            div { class: "mb-3 d-flex gap-2",
+                div { class: "flex-grow-1",
+                    Input {
+                        id: "link-search",
+                        input_type: "search",
+                        placeholder: "Search by slug or URL",
+                        value: search_input.read().clone(),
+                        on_set_value: move |v| search_input.set(v),
+                        on_debounce: move |v: String| {
+                            if v != *search.peek() {
+                                search.set(v);
+                                go_to_first_page();
+                            }
+                        },
+                    }
+                }
+                select {
+                    class: "form-select w-auto",
+                    title: "Only show the links with this tag",
+                    onchange: move |e| {
+                        let value = e.value();
+                        tag.set(if value.is_empty() { None } else { Some(value) });
+                        go_to_first_page();
+                    },
+                    option { value: "", selected: tag().is_none(), "All tags" }
+                    for t in tags() {
+                        option {
+                            value: t.clone(),
+                            selected: tag().as_ref() == Some(&t),
+                            "{t}"
+                        }
+                    }
+                }
+            }
+
             div { class: "table-responsive",
                 table { class: "table table-striped table-hover",
                     thead {
                         tr {
-                            th { scope: "col", "Slug" }
+                            SortableHeader {
+                                label: "Slug",
+                                field: LinkSortField::Slug,
+                                sort: sort(),
+                                order: order(),
+                                on_sort: move |field| on_sort(field),
+                            }
                             th { scope: "col", "URL" }
-                            th { scope: "col", "Visits" }
-                            th { scope: "col", "Created at" }
+                            SortableHeader {
+                                label: "Visits",
+                                field: LinkSortField::Visits,
+                                sort: sort(),
+                                order: order(),
+                                on_sort: move |field| on_sort(field),
+                            }
+                            SortableHeader {
+                                label: "Created at",
+                                field: LinkSortField::CreatedAt,
+                                sort: sort(),
+                                order: order(),
+                                on_sort: move |field| on_sort(field),
+                            }
                             th { scope: "col", "Actions" }
                         }
                     }
                     tbody { class: "table-group-divider",
                         match state.read().clone() {
                             ListLinksState::Success(response) => rsx! {
+                                if response.links.is_empty() {
+                                    tr {
+                                        td { colspan: 5, class: "text-center text-body-secondary",
+                                            "No links found"
+                                        }
+                                    }
+                                }
                                 for link in response.links {
                                     tr {
                                         td { class: "text-truncate", style: "max-width: 8rem;",
@@ -90,13 +194,58 @@ pub fn ListLinks(page: NonZeroU64) -> Element {
                                                 ),
                                                 "{link.slug}"
                                             }
+                                            if link.expired {
+                                                span { class: "badge text-bg-secondary ms-1", "Expired" }
+                                            }
+                                            if !link.tags.is_empty() {
+                                                div { class: "text-wrap",
+                                                    for t in link.tags.clone() {
+                                                        span { class: "badge text-bg-info me-1", "{t}" }
+                                                    }
+                                                }
+                                            }
                                         }
                                         td { class: "text-truncate", style: "max-width: 20rem;",
-                                            a { href: link.url.clone(), "{link.url}" }
+                                            if let Some(title) = link.title.clone() {
+                                                div { class: "text-truncate fw-semibold",
+                                                    if let Some(favicon_url) = link.favicon_url.clone() {
+                                                        img {
+                                                            src: favicon_url,
+                                                            alt: "",
+                                                            width: 16,
+                                                            height: 16,
+                                                            class: "me-1 align-text-bottom",
+                                                        }
+                                                    }
+                                                    "{title}"
+                                                }
+                                            }
+                                            if let Some(url) = link.url.clone() {
+                                                a { href: url.clone(), "{url}" }
+                                            } else {
+                                                span { class: "text-body-secondary",
+                                                    i { class: "bi bi-lock-fill" }
+                                                    " Password protected"
+                                                }
+                                            }
+                                        }
+                                        td {
+                                            "{link.visits}"
+                                            if let Some(max_visits) = link.max_visits {
+                                                " / {max_visits}"
+                                            }
                                         }
-                                        td { "{link.visits}" }
                                         td { "{format_date(link.created_at)}" }
-                                        td { class: "pt-1 pb-1",
+                                        td { class: "pt-1 pb-1 text-nowrap",
+                                            button {
+                                                onclick: {
+                                                    let link = link.clone();
+                                                    move |_| editing_link.set(Some(link.clone()))
+                                                },
+                                                class: "btn btn-secondary btn-sm me-1",
+                                                i { class: "bi bi-pencil-fill" }
+                                                " Edit"
+                                            }
                                             button {
                                                 onclick: move |_| removing_link_slug.set(link.slug.clone()),
                                                 class: "btn btn-danger btn-sm",
@@ -132,6 +281,11 @@ pub fn ListLinks(page: NonZeroU64) -> Element {
                 },
             }
 
+            EditLinkModal {
+                link: editing_link.read().clone(),
+                on_edit: move |_| *iteration.write() += 1,
+            }
+
             RemoveLinkModal {
                 slug: removing_link_slug.read().clone(),
                 on_remove: move |_| *iteration.write() += 1,
@@ -140,6 +294,52 @@ pub fn ListLinks(page: NonZeroU64) -> Element {
     }
 }
 
+/// Column header that sorts the list by `field` when clicked, or reverses the
+/// order if the list is already sorted by it.
+#[component]
+fn SortableHeader(
+    label: &'static str,
+    field: LinkSortField,
+    sort: LinkSortField,
+    order: SortOrder,
+    on_sort: EventHandler<LinkSortField>,
+) -> Element {
+    let is_sorted = sort == field;
+    let aria_sort = match (is_sorted, order) {
+        (false, _) => "none",
+        (true, SortOrder::Asc) => "ascending",
+        (true, SortOrder::Desc) => "descending",
+    };
+
+    rsx! {
+        th { scope: "col", "aria-sort": aria_sort,
+            a {
+                href: "#",
+                class: "link-light link-underline-opacity-0 text-nowrap",
+                onclick: move |e| {
+                    e.prevent_default();
+                    on_sort.call(field);
+                },
+                "{label}"
+                if is_sorted {
+                    i {
+                        class: if order == SortOrder::Asc { "bi bi-caret-up-fill ms-1" } else { "bi bi-caret-down-fill ms-1" },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The order to sort by `field` in when it is first chosen; the newest and
+/// most visited links come first.
+fn default_order(field: LinkSortField) -> SortOrder {
+    match field {
+        LinkSortField::Slug => SortOrder::Asc,
+        LinkSortField::CreatedAt | LinkSortField::Visits => SortOrder::Desc,
+    }
+}
+
 fn gen_random_col_class() -> String {
     let cols = ["3", "4", "5", "6", "7", "8", "10", "12"];
     let col = cols.choose(&mut rand::rng()).unwrap();
@@ -1,8 +1,12 @@
 mod api;
+mod api_tokens;
 mod app;
+mod edit_link_modal;
 mod error_alert;
 mod input;
+mod link_preview;
 mod link_result;
+mod link_transfer;
 mod list_links;
 mod not_found;
 mod pagination;
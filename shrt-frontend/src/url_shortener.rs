@@ -1,6 +1,8 @@
 use dioxus::prelude::*;
 use shrt_common::config::AppConfig;
 use shrt_common::errors::ServiceError;
+use shrt_common::links::LinkCreateRequest;
+use shrt_common::tags::split_tags;
 
 use crate::api::ShrtApi;
 use crate::app::Route;
@@ -11,6 +13,8 @@ use crate::input::Input;
 pub enum UrlShortenerState {
     Error(ServiceError),
     LinkExists,
+    /// The slug violates the slug policy, for the given reason
+    SlugInvalid(String),
     Loading,
     #[default]
     Initial,
@@ -27,6 +31,14 @@ impl UrlShortenerState {
         matches!(self, UrlShortenerState::LinkExists)
     }
 
+    #[must_use]
+    pub fn get_slug_invalid_reason(&self) -> Option<&str> {
+        match self {
+            UrlShortenerState::SlugInvalid(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn get_error(&self) -> Option<&ServiceError> {
         match self {
@@ -40,6 +52,10 @@ impl UrlShortenerState {
 pub fn UrlShortener() -> Element {
     let mut url = use_signal(|| "http://".to_string());
     let mut link_name = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut title = use_signal(String::new);
+    let mut tags = use_signal(String::new);
+    let mut reuse_existing = use_signal(|| true);
     let mut state: Signal<UrlShortenerState> = use_signal(UrlShortenerState::default);
     let navigator = use_navigator();
     let config = use_context::<Signal<AppConfig>>();
@@ -48,21 +64,34 @@ pub fn UrlShortener() -> Element {
     let is_loading = state.read().is_loading();
     let is_link_exists = state.read().is_link_exists();
     let error = state.read().get_error().cloned();
-    let link_name_error = if is_link_exists {
-        "Link already taken; please choose another or leave the field empty"
+    let slug_invalid_reason = state
+        .read()
+        .get_slug_invalid_reason()
+        .map(ToOwned::to_owned);
+    let link_name_error = if let Some(reason) = slug_invalid_reason {
+        format!("{reason}; please choose another or leave the field empty")
+    } else if is_link_exists {
+        "Link already taken; please choose another or leave the field empty".to_string()
     } else {
-        ""
+        String::new()
     };
 
     rsx! {
         form {
             onsubmit: move |evt| {
                 evt.prevent_default();
-                let url_val = url.read().clone();
-                let link_name_val = link_name.read().clone();
+                let request = LinkCreateRequest {
+                    url: url.read().clone(),
+                    slug: non_empty(link_name.read().clone()),
+                    password: non_empty(password.read().clone()),
+                    reuse_existing: *reuse_existing.read(),
+                    title: non_empty(title.read().clone()),
+                    tags: split_tags(&tags.read()),
+                    ..Default::default()
+                };
                 state.set(UrlShortenerState::Loading);
                 spawn(async move {
-                    match ShrtApi::shorten_url(&url_val, &link_name_val).await {
+                    match ShrtApi::shorten_url(&request).await {
                         Ok(link) => {
                             navigator.push(Route::LinkResult { slug: link.slug });
                         }
@@ -96,7 +125,9 @@ pub fn UrlShortener() -> Element {
                             }
                             spawn(async move {
                                 match ShrtApi::get_link_exists(&v).await {
-                                    Ok(r) => state.set(if r.exists {
+                                    Ok(r) => state.set(if let Some(reason) = r.invalid_reason {
+                                        UrlShortenerState::SlugInvalid(reason)
+                                    } else if r.exists {
                                         UrlShortenerState::LinkExists
                                     } else {
                                         UrlShortenerState::Initial
@@ -109,10 +140,54 @@ pub fn UrlShortener() -> Element {
                         disabled: is_loading,
                         placeholder: "<random>",
                         id: "link-name",
-                        error: link_name_error.to_string(),
+                        error: link_name_error,
                     }
                 }
             }
+            div { class: "mb-3",
+                label { r#for: "password", class: "form-label", "Password (optional):" }
+                Input {
+                    on_set_value: move |v| password.set(v),
+                    value: password.read().clone(),
+                    disabled: is_loading,
+                    placeholder: "<none>",
+                    id: "password",
+                    input_type: "password",
+                }
+            }
+            div { class: "mb-3",
+                label { r#for: "title", class: "form-label", "Title (optional):" }
+                Input {
+                    on_set_value: move |v| title.set(v),
+                    value: title.read().clone(),
+                    disabled: is_loading,
+                    placeholder: "<taken from the page>",
+                    id: "title",
+                }
+            }
+            div { class: "mb-3",
+                label { r#for: "tags", class: "form-label", "Tags (optional):" }
+                Input {
+                    on_set_value: move |v| tags.set(v),
+                    value: tags.read().clone(),
+                    disabled: is_loading,
+                    placeholder: "Comma-separated, e.g. marketing, q3-launch",
+                    id: "tags",
+                }
+            }
+            div { class: "form-check mb-3",
+                input {
+                    class: "form-check-input",
+                    r#type: "checkbox",
+                    id: "reuse-existing",
+                    checked: *reuse_existing.read(),
+                    disabled: is_loading,
+                    onchange: move |evt| reuse_existing.set(evt.checked()),
+                }
+                label { r#for: "reuse-existing", class: "form-check-label",
+                    "Reuse my existing link to the same URL"
+                }
+            }
             p { class: "text-center",
                 button {
                     r#type: "submit",
@@ -132,3 +207,7 @@ pub fn UrlShortener() -> Element {
         }
     }
 }
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() { None } else { Some(value) }
+}